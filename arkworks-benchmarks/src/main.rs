@@ -1,28 +1,37 @@
 use ark_bls12_381::{Bls12_381, Fr as BlsFr};
-use ark_crypto_primitives::SNARK;
-use ark_ed_on_bls12_381::{EdwardsAffine, Fr as EdBlsFr};
-use ark_ff::{One, PrimeField, UniformRand};
+use ark_bn254::Fr as Bn254Fr;
+use ark_crypto_primitives::{
+	crh::{constraints::CRHGadget as CRHGadgetTrait, CRH as CRHTrait},
+	SNARK,
+};
+use ark_ed_on_bn254::Fr as EdBn254Fr;
+use ark_ff::{to_bytes, One, PrimeField, UniformRand};
 use ark_groth16::Groth16;
 use ark_marlin::Marlin;
 use ark_poly::univariate::DensePolynomial;
-use ark_poly_commit::{ipa_pc::InnerProductArgPC, marlin_pc::MarlinKZG10, sonic_pc::SonicKZG10};
+use ark_poly_commit::{marlin_pc::MarlinKZG10, sonic_pc::SonicKZG10};
+use ark_r1cs_std::{alloc::AllocVar, uint8::UInt8};
+use ark_relations::r1cs::ConstraintSystem;
 use ark_std::{self, rc::Rc, test_rng, time::Instant, vec::Vec};
-use arkworks_circuits::circuit::bridge::BridgeCircuit;
+use arkworks_circuits::{circuit::bridge::BridgeCircuit, setup::common::MarlinIPA_EdOnBn254};
 use arkworks_gadgets::{
 	arbitrary::bridge_data::Input as BridgeDataInput,
 	leaf::bridge::{
 		constraints::BridgeLeafGadget, BridgeLeaf, Private as LeafPrivate, Public as LeafPublic,
 	},
 	merkle_tree::{Config as MerkleConfig, SparseMerkleTree},
-	poseidon::{constraints::CRHGadget, CRH},
+	poseidon::{
+		constraints::{CRHGadget, PoseidonParametersVar},
+		CRH,
+	},
 	set::membership::{constraints::SetMembershipGadget, SetMembership},
 };
 
-use arkworks_utils::utils::common::{setup_params_x5_3, setup_params_x5_5};
+use arkworks_utils::utils::common::{setup_params_x5_3, setup_params_x5_5, Curve};
 use blake2::Blake2s;
 
 macro_rules! setup_circuit {
-	($test_field:ty) => {{
+	($test_field:ty, $curve:expr) => {{
 		const M: usize = 4;
 		const N: usize = 30;
 
@@ -58,7 +67,7 @@ macro_rules! setup_circuit {
 		>;
 
 		let rng = &mut test_rng();
-		let curve = arkworks_utils::utils::common::Curve::Bn254;
+		let curve = $curve;
 		// Secret inputs for the leaf
 		let leaf_private = LeafPrivate::generate(rng);
 		// Public inputs for the leaf
@@ -93,16 +102,16 @@ macro_rules! setup_circuit {
 		// Making the merkle tree
 		let mt = BridgeTree::new_sequential(inner_params, leaf_params, &leaves).unwrap();
 		// Getting the proof path
-		let path = mt.generate_membership_proof(2);
-		let root = mt.root();
+		let path = mt.generate_membership_proof(2).unwrap();
+		let root = mt.root().unwrap();
 		let roots = [
 			<$test_field>::rand(rng),
 			<$test_field>::rand(rng),
 			<$test_field>::rand(rng),
-			root.clone().inner(),
+			root.clone().inner().unwrap(),
 		];
 		let set_private_inputs =
-			TestSetMembership::generate_secrets(&root.clone().inner(), &roots).unwrap();
+			TestSetMembership::generate_secrets(&root.clone().inner().unwrap(), &roots).unwrap();
 		let mc = Circuit::new(
 			arbitrary_input.clone(),
 			leaf_private,
@@ -111,14 +120,14 @@ macro_rules! setup_circuit {
 			roots.clone(),
 			params5,
 			path,
-			root.clone().inner(),
+			root.clone().inner().unwrap(),
 			nullifier_hash,
 		);
 		let mut public_inputs = Vec::new();
 		public_inputs.push(chain_id);
 		public_inputs.push(nullifier_hash);
 		public_inputs.extend(roots.to_vec());
-		public_inputs.push(root.inner());
+		public_inputs.push(root.inner().unwrap());
 		public_inputs.push(arbitrary_input.recipient);
 		public_inputs.push(arbitrary_input.relayer);
 		public_inputs.push(arbitrary_input.fee);
@@ -147,9 +156,9 @@ macro_rules! measure {
 }
 
 macro_rules! benchmark_marlin {
-	($marlin:ty, $field:ty, $name:expr, $nc:expr, $nv:expr, $num_iter:expr) => {
+	($marlin:ty, $field:ty, $curve:expr, $name:expr, $nc:expr, $nv:expr, $num_iter:expr) => {
 		let rng = &mut test_rng();
-		let (public_inputs, circuit) = setup_circuit!($field);
+		let (public_inputs, circuit) = setup_circuit!($field, $curve);
 
 		// Setup
 		let srs = measure!(
@@ -188,7 +197,7 @@ macro_rules! benchmark_marlin {
 macro_rules! benchmark_groth {
 	($groth:ty, $field:ty, $num_iter:expr) => {
 		let rng = &mut test_rng();
-		let (public_inputs, circuit) = setup_circuit!($field);
+		let (public_inputs, circuit) = setup_circuit!($field, Curve::Bn254);
 
 		// Setup
 		let keys = measure!(
@@ -224,21 +233,87 @@ fn benchmark_groth16(num_iter: u32) {
 fn benchmark_marlin_poly(nc: usize, nv: usize, num_iter: u32) {
 	type KZG10 = MarlinKZG10<Bls12_381, DensePolynomial<BlsFr>>;
 	type MarlinSetup = Marlin<BlsFr, KZG10, Blake2s>;
-	benchmark_marlin!(MarlinSetup, BlsFr, "Marlin_PolyKZG10", nc, nv, num_iter);
+	benchmark_marlin!(
+		MarlinSetup,
+		BlsFr,
+		Curve::Bn254,
+		"Marlin_PolyKZG10",
+		nc,
+		nv,
+		num_iter
+	);
 }
 
 fn benchmark_marlin_sonic(nc: usize, nv: usize, num_iter: u32) {
 	type Sonic = SonicKZG10<Bls12_381, DensePolynomial<BlsFr>>;
 	type MarlinSetup = Marlin<BlsFr, Sonic, Blake2s>;
 
-	benchmark_marlin!(MarlinSetup, BlsFr, "Marlin_Sonic", nc, nv, num_iter);
+	benchmark_marlin!(
+		MarlinSetup,
+		BlsFr,
+		Curve::Bn254,
+		"Marlin_Sonic",
+		nc,
+		nv,
+		num_iter
+	);
 }
 
 fn benchmark_marlin_ipa_pc(nc: usize, nv: usize, num_iter: u32) {
-	type IPA = InnerProductArgPC<EdwardsAffine, Blake2s, DensePolynomial<EdBlsFr>>;
-	type MarlinSetup = Marlin<EdBlsFr, IPA, Blake2s>;
+	type MarlinSetup = MarlinIPA_EdOnBn254;
+
+	benchmark_marlin!(
+		MarlinSetup,
+		EdBn254Fr,
+		Curve::EdOnBn254,
+		"Marlin_IPA_PC",
+		nc,
+		nv,
+		num_iter
+	);
+}
+
+fn benchmark_poseidon(num_iter: u32) {
+	let rng = &mut test_rng();
+	let params = setup_params_x5_5::<Bn254Fr>(Curve::Bn254);
+	let optimized = params.clone().optimized().unwrap();
+	let input = to_bytes![
+		Bn254Fr::rand(rng),
+		Bn254Fr::rand(rng),
+		Bn254Fr::rand(rng),
+		Bn254Fr::rand(rng)
+	]
+	.unwrap();
+
+	for (name, params) in &[("Poseidon", &params), ("Poseidon_optimized", &optimized)] {
+		let _ = measure!(
+			{ <CRH<Bn254Fr> as CRHTrait>::evaluate(params, &input).unwrap() },
+			name,
+			"hash",
+			num_iter
+		);
 
-	benchmark_marlin!(MarlinSetup, EdBlsFr, "Marlin_IPA_PC", nc, nv, num_iter);
+		let cs = measure!(
+			{
+				let cs = ConstraintSystem::<Bn254Fr>::new_ref();
+				let params_var = PoseidonParametersVar::new_constant(cs.clone(), *params).unwrap();
+				let input_var =
+					Vec::<UInt8<Bn254Fr>>::new_witness(cs.clone(), || Ok(input.clone())).unwrap();
+				<CRHGadget<Bn254Fr> as CRHGadgetTrait<_, _>>::evaluate(&params_var, &input_var)
+					.unwrap();
+				cs
+			},
+			name,
+			"synthesis",
+			num_iter
+		);
+		println!(
+			"{}: {} constraints, {} linear combinations",
+			name,
+			cs.num_constraints(),
+			cs.borrow().unwrap().num_linear_combinations
+		);
+	}
 }
 
 fn main() {
@@ -246,6 +321,9 @@ fn main() {
 	let nv = 65536;
 	let num_iter = 5;
 
+	// Poseidon, dense and optimized
+	benchmark_poseidon(1000);
+
 	// Groth16
 	benchmark_groth16(num_iter);
 	// MarlinKZG10
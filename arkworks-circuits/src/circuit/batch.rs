@@ -0,0 +1,188 @@
+use ark_crypto_primitives::{crh::constraints::CRHGadget, CRH};
+use ark_ff::fields::PrimeField;
+use ark_r1cs_std::{eq::EqGadget, fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{marker::PhantomData, vec::Vec};
+use arkworks_gadgets::merkle_tree::{
+	compact::CompactPath,
+	constraints::{NodeVar, UpdatePathVar},
+	Config as MerkleConfig,
+};
+
+/// Circuit proving that appending `K` leaves at consecutive indices starting
+/// at `next_index` moves a tree from `old_root` to `new_root`.
+///
+/// Every path is taken from the tree after the previous leaves of the batch
+/// were written, so the root computed for one leaf is the old root of the
+/// next one.
+pub struct BatchInsertionCircuit<
+	F: PrimeField,
+	// Merkle config and hasher gadget for the tree
+	C: MerkleConfig,
+	LHGT: CRHGadget<C::LeafH, F>,
+	HGT: CRHGadget<C::H, F>,
+	const N: usize,
+	const K: usize,
+> {
+	old_root: <C::H as CRH>::Output,
+	new_root: <C::H as CRH>::Output,
+	next_index: F,
+	leaves: [F; K],
+	paths: [CompactPath<C, N>; K],
+	_field: PhantomData<F>,
+	_leaf_hasher_gadget: PhantomData<LHGT>,
+	_tree_hasher_gadget: PhantomData<HGT>,
+	_merkle_config: PhantomData<C>,
+}
+
+impl<F, C, LHGT, HGT, const N: usize, const K: usize> BatchInsertionCircuit<F, C, LHGT, HGT, N, K>
+where
+	F: PrimeField,
+	C: MerkleConfig,
+	LHGT: CRHGadget<C::LeafH, F>,
+	HGT: CRHGadget<C::H, F>,
+{
+	pub fn new(
+		old_root: <C::H as CRH>::Output,
+		new_root: <C::H as CRH>::Output,
+		next_index: F,
+		leaves: [F; K],
+		paths: [CompactPath<C, N>; K],
+	) -> Self {
+		Self {
+			old_root,
+			new_root,
+			next_index,
+			leaves,
+			paths,
+			_field: PhantomData,
+			_leaf_hasher_gadget: PhantomData,
+			_tree_hasher_gadget: PhantomData,
+			_merkle_config: PhantomData,
+		}
+	}
+}
+
+impl<F, C, LHGT, HGT, const N: usize, const K: usize> Clone
+	for BatchInsertionCircuit<F, C, LHGT, HGT, N, K>
+where
+	F: PrimeField,
+	C: MerkleConfig,
+	LHGT: CRHGadget<C::LeafH, F>,
+	HGT: CRHGadget<C::H, F>,
+{
+	fn clone(&self) -> Self {
+		let old_root = self.old_root.clone();
+		let new_root = self.new_root.clone();
+		let next_index = self.next_index;
+		let leaves = self.leaves;
+		let paths = self.paths.clone();
+		Self::new(old_root, new_root, next_index, leaves, paths)
+	}
+}
+
+impl<F, C, LHGT, HGT, const N: usize, const K: usize> ConstraintSynthesizer<F>
+	for BatchInsertionCircuit<F, C, LHGT, HGT, N, K>
+where
+	F: PrimeField,
+	C: MerkleConfig,
+	LHGT: CRHGadget<C::LeafH, F>,
+	HGT: CRHGadget<C::H, F>,
+{
+	fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+		let old_root = self.old_root;
+		let new_root = self.new_root;
+		let next_index = self.next_index;
+		let leaves = self.leaves;
+		let paths = self.paths;
+
+		// Generating vars
+		// Public inputs
+		let old_root_var = HGT::OutputVar::new_input(cs.clone(), || Ok(old_root))?;
+		let new_root_var = HGT::OutputVar::new_input(cs.clone(), || Ok(new_root))?;
+		let next_index_var = FpVar::<F>::new_input(cs.clone(), || Ok(next_index))?;
+		let mut leaves_var = Vec::with_capacity(K);
+		for leaf in leaves.iter() {
+			leaves_var.push(FpVar::<F>::new_input(cs.clone(), || Ok(leaf))?);
+		}
+
+		// Private inputs
+		let mut paths_var = Vec::with_capacity(K);
+		for path in paths.iter() {
+			paths_var.push(UpdatePathVar::<F, C, HGT, LHGT, N>::new_witness(
+				cs.clone(),
+				|| Ok(path),
+			)?);
+		}
+
+		// Writing the leaves one after the other, each at the next index
+		let mut root_var = NodeVar::Inner(old_root_var);
+		for (i, (leaf_var, path_var)) in leaves_var.iter().zip(paths_var.iter()).enumerate() {
+			let (is_empty, updated_root_var) = path_var.update_root(&root_var, leaf_var)?;
+			let index_var = path_var.get_index()?;
+
+			// Enforcing constraints
+			is_empty.enforce_equal(&Boolean::TRUE)?;
+			index_var.enforce_equal(&(&next_index_var + F::from(i as u64)))?;
+			root_var = updated_root_var;
+		}
+		root_var.enforce_equal(&NodeVar::Inner(new_root_var))?;
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::setup::{batch::*, common::*};
+	use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+	use ark_ff::UniformRand;
+	use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+	use ark_std::test_rng;
+	use arkworks_utils::utils::common::{verify_groth16, Curve};
+
+	// merkle proof path legth
+	// TreeConfig_x5, x7 HEIGHT is hardcoded to 30
+	pub const LEN: usize = 30;
+	pub const BATCH: usize = 2;
+
+	#[test]
+	fn setup_and_prove_batch_insertion_groth16() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+		let (circuit, .., public_inputs) =
+			setup_random_circuit_x5::<_, BlsFr, LEN, BATCH>(rng, curve);
+
+		let (pk, vk) = setup_groth16_circuit_x5::<_, Bls12_381, LEN, BATCH>(rng, circuit.clone());
+		let proof = prove_groth16_circuit_x5::<_, Bls12_381, LEN, BATCH>(&pk, circuit, rng);
+
+		let res = verify_groth16::<Bls12_381>(&vk, &public_inputs, &proof);
+		assert!(res);
+	}
+
+	#[test]
+	fn should_fail_with_wrong_new_root() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+		let leaves = vec![BlsFr::rand(rng), BlsFr::rand(rng)];
+		let new_leaves = [BlsFr::rand(rng), BlsFr::rand(rng)];
+		let (circuit, old_root, ..) =
+			setup_circuit_x5::<BlsFr, LEN, BATCH>(&leaves, new_leaves, curve);
+
+		let cs = ConstraintSystem::<BlsFr>::new_ref();
+		circuit.clone().generate_constraints(cs.clone()).unwrap();
+		assert!(cs.is_satisfied().unwrap());
+
+		// claiming the batch leaves the tree untouched
+		let wrong = Circuit_x5::<BlsFr, LEN, BATCH>::new(
+			old_root,
+			old_root,
+			BlsFr::from(leaves.len() as u64),
+			new_leaves,
+			circuit.paths,
+		);
+		let cs = ConstraintSystem::<BlsFr>::new_ref();
+		wrong.generate_constraints(cs.clone()).unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+}
@@ -1,4 +1,5 @@
 pub mod basic;
+pub mod batch;
 pub mod bridge;
 pub mod mixer;
 pub mod poseidon;
@@ -539,21 +539,21 @@ mod test {
 		let leaves = [leaf_1, leaf_2];
 		let tree = Tree_x5::new_sequential(inner_params, Rc::new(()), &leaves).unwrap();
 
-		let path_1 = tree.generate_membership_proof(0);
-		let path_2 = tree.generate_membership_proof(1);
+		let path_1 = tree.generate_membership_proof(0).unwrap();
+		let path_2 = tree.generate_membership_proof(1).unwrap();
 		let paths = vec![path_1.clone(), path_2.clone()];
 
 		let public_amount = BnFr::one();
 
 		let ext_data_hash_1 = setup_vanchor_arbitrary_data(commitment_1);
 		let ext_data_hash = ext_data_hash_1; // We used it as a sample value for ext_data_hash in the tests
-		let root = tree.root().inner();
+		let root = tree.root().unwrap().inner().unwrap();
 
 		let mut root_set = [BnFr::rand(rng); TEST_M];
 		root_set[0] = root;
 		assert_eq!(root_set.len(), TEST_M);
-		let index_0: BnFr = path_1.get_index(&tree.root(), &leaf_1).unwrap();
-		let index_1: BnFr = path_1.get_index(&tree.root(), &leaf_2).unwrap();
+		let index_0: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_1).unwrap();
+		let index_1: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_2).unwrap();
 		assert_eq!(index_0, BnFr::zero());
 		assert_eq!(index_1, BnFr::one());
 		let indices = vec![index_0, index_1];
@@ -695,8 +695,8 @@ mod test {
 		let leaves = [leaf_1, leaf_2];
 		let tree = Tree_x5::new_sequential(inner_params, Rc::new(()), &leaves).unwrap();
 
-		let path_1 = tree.generate_membership_proof(0);
-		let path_2 = tree.generate_membership_proof(1);
+		let path_1 = tree.generate_membership_proof(0).unwrap();
+		let path_2 = tree.generate_membership_proof(1).unwrap();
 		let paths = vec![path_1.clone(), path_2.clone()];
 
 		let public_amount = BnFr::one();
@@ -707,8 +707,8 @@ mod test {
 
 		let root_set = [root; TEST_M];
 
-		let index_0: BnFr = path_1.get_index(&tree.root(), &leaf_1).unwrap();
-		let index_1: BnFr = path_1.get_index(&tree.root(), &leaf_2).unwrap();
+		let index_0: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_1).unwrap();
+		let index_1: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_2).unwrap();
 		assert_eq!(index_0, BnFr::zero());
 		assert_eq!(index_1, BnFr::one());
 		let indices = vec![index_0, index_1];
@@ -850,8 +850,8 @@ mod test {
 		let leaves = [leaf_1, leaf_2];
 		let tree = Tree_x5::new_sequential(inner_params, Rc::new(()), &leaves).unwrap();
 
-		let path_1 = tree.generate_membership_proof(0);
-		let path_2 = tree.generate_membership_proof(1);
+		let path_1 = tree.generate_membership_proof(0).unwrap();
+		let path_2 = tree.generate_membership_proof(1).unwrap();
 		let paths = vec![path_1.clone(), path_2.clone()];
 
 		let public_amount = BnFr::one();
@@ -863,8 +863,8 @@ mod test {
 		let mut root_set = [BnFr::rand(rng); TEST_M];
 		root_set[0] = root;
 		assert_eq!(root_set.len(), TEST_M);
-		let index_0: BnFr = path_1.get_index(&tree.root(), &leaf_1).unwrap();
-		let index_1: BnFr = path_1.get_index(&tree.root(), &leaf_2).unwrap();
+		let index_0: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_1).unwrap();
+		let index_1: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_2).unwrap();
 		assert_eq!(index_0, BnFr::zero());
 		assert_eq!(index_1, BnFr::one());
 		let indices = vec![index_0, index_1];
@@ -1006,21 +1006,21 @@ mod test {
 		let leaves = [leaf_1, leaf_2];
 		let tree = Tree_x5::new_sequential(inner_params, Rc::new(()), &leaves).unwrap();
 
-		let path_1 = tree.generate_membership_proof(0);
-		let path_2 = tree.generate_membership_proof(1);
+		let path_1 = tree.generate_membership_proof(0).unwrap();
+		let path_2 = tree.generate_membership_proof(1).unwrap();
 		let paths = vec![path_1.clone(), path_2.clone()];
 
 		let public_amount = BnFr::one();
 
 		let ext_data_hash_1 = setup_vanchor_arbitrary_data(commitment_1);
 		let ext_data_hash = ext_data_hash_1; // We used it as a sample value for ext_data_hash in the tests
-		let root = tree.root().inner();
+		let root = tree.root().unwrap().inner().unwrap();
 
 		let mut root_set = [BnFr::rand(rng); TEST_M];
 		root_set[0] = root;
 		assert_eq!(root_set.len(), TEST_M);
-		let index_0: BnFr = path_1.get_index(&tree.root(), &leaf_1).unwrap();
-		let index_1: BnFr = path_1.get_index(&tree.root(), &leaf_2).unwrap();
+		let index_0: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_1).unwrap();
+		let index_1: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_2).unwrap();
 		assert_eq!(index_0, BnFr::zero());
 		assert_eq!(index_1, BnFr::one());
 		let indices = vec![index_0, index_1];
@@ -1158,21 +1158,21 @@ mod test {
 		let leaves = [leaf_1, leaf_2];
 		let tree = Tree_x5::new_sequential(inner_params, Rc::new(()), &leaves).unwrap();
 
-		let path_1 = tree.generate_membership_proof(0);
-		let path_2 = tree.generate_membership_proof(1);
+		let path_1 = tree.generate_membership_proof(0).unwrap();
+		let path_2 = tree.generate_membership_proof(1).unwrap();
 		let paths = vec![path_1.clone(), path_2.clone()];
 
 		let public_amount = BnFr::one();
 
 		let ext_data_hash_1 = setup_vanchor_arbitrary_data(commitment_1);
 		let ext_data_hash = ext_data_hash_1; // We used it as a sample value for ext_data_hash in the tests
-		let root = tree.root().inner();
+		let root = tree.root().unwrap().inner().unwrap();
 
 		let mut root_set = [BnFr::rand(rng); TEST_M];
 		root_set[0] = root;
 		assert_eq!(root_set.len(), TEST_M);
-		let index_0: BnFr = path_1.get_index(&tree.root(), &leaf_1).unwrap();
-		let index_1: BnFr = path_1.get_index(&tree.root(), &leaf_2).unwrap();
+		let index_0: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_1).unwrap();
+		let index_1: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_2).unwrap();
 		assert_eq!(index_0, BnFr::zero());
 		assert_eq!(index_1, BnFr::one());
 		let indices = vec![index_0, index_1];
@@ -1309,21 +1309,21 @@ mod test {
 		let leaves = [leaf_1, leaf_2];
 		let tree = Tree_x5::new_sequential(inner_params, Rc::new(()), &leaves).unwrap();
 
-		let path_1 = tree.generate_membership_proof(0);
-		let path_2 = tree.generate_membership_proof(1);
+		let path_1 = tree.generate_membership_proof(0).unwrap();
+		let path_2 = tree.generate_membership_proof(1).unwrap();
 		let paths = vec![path_1.clone(), path_2.clone()];
 
 		let public_amount = BnFr::one();
 
 		let ext_data_hash_1 = setup_vanchor_arbitrary_data(commitment_1);
 		let ext_data_hash = ext_data_hash_1; // We used it as a sample value for ext_data_hash in the tests
-		let root = tree.root().inner();
+		let root = tree.root().unwrap().inner().unwrap();
 
 		let mut root_set = [BnFr::rand(rng); TEST_M];
 		root_set[0] = root;
 		assert_eq!(root_set.len(), TEST_M);
-		let index_0: BnFr = path_1.get_index(&tree.root(), &leaf_1).unwrap();
-		let index_1: BnFr = path_1.get_index(&tree.root(), &leaf_2).unwrap();
+		let index_0: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_1).unwrap();
+		let index_1: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_2).unwrap();
 		assert_eq!(index_0, BnFr::zero());
 		assert_eq!(index_1, BnFr::one());
 		let indices = vec![index_0, index_1];
@@ -1475,21 +1475,21 @@ mod test {
 		let leaves = [leaf_1, leaf_2];
 		let tree = Tree_x5::new_sequential(inner_params, Rc::new(()), &leaves).unwrap();
 
-		let path_1 = tree.generate_membership_proof(0);
-		let path_2 = tree.generate_membership_proof(1);
+		let path_1 = tree.generate_membership_proof(0).unwrap();
+		let path_2 = tree.generate_membership_proof(1).unwrap();
 		let paths = vec![path_1.clone(), path_2.clone()];
 
 		let public_amount = BnFr::one();
 
 		let ext_data_hash_1 = setup_vanchor_arbitrary_data(commitment_1);
 		let ext_data_hash = ext_data_hash_1; // We used it as a sample value for ext_data_hash in the tests
-		let root = tree.root().inner();
+		let root = tree.root().unwrap().inner().unwrap();
 
 		let mut root_set = [BnFr::rand(rng); TEST_M];
 		root_set[0] = root;
 		assert_eq!(root_set.len(), TEST_M);
-		let index_0: BnFr = path_1.get_index(&tree.root(), &leaf_1).unwrap();
-		let index_1: BnFr = path_1.get_index(&tree.root(), &leaf_2).unwrap();
+		let index_0: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_1).unwrap();
+		let index_1: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_2).unwrap();
 		assert_eq!(index_0, BnFr::zero());
 		assert_eq!(index_1, BnFr::one());
 		let indices = vec![index_0, index_1];
@@ -1632,21 +1632,21 @@ mod test {
 		let leaves = [leaf_1, leaf_2];
 		let tree = Tree_x5::new_sequential(inner_params, Rc::new(()), &leaves).unwrap();
 
-		let path_1 = tree.generate_membership_proof(0);
-		let path_2 = tree.generate_membership_proof(1);
+		let path_1 = tree.generate_membership_proof(0).unwrap();
+		let path_2 = tree.generate_membership_proof(1).unwrap();
 		let paths = vec![path_1.clone(), path_2.clone()];
 
 		let public_amount = BnFr::one();
 
 		let ext_data_hash_1 = setup_vanchor_arbitrary_data(commitment_1);
 		let ext_data_hash = ext_data_hash_1; // We used it as a sample value for ext_data_hash in the tests
-		let root = tree.root().inner();
+		let root = tree.root().unwrap().inner().unwrap();
 
 		let mut root_set = [BnFr::rand(rng); TEST_M];
 		root_set[0] = root;
 		assert_eq!(root_set.len(), TEST_M);
-		let index_0: BnFr = path_1.get_index(&tree.root(), &leaf_1).unwrap();
-		let index_1: BnFr = path_1.get_index(&tree.root(), &leaf_2).unwrap();
+		let index_0: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_1).unwrap();
+		let index_1: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_2).unwrap();
 		assert_eq!(index_0, BnFr::zero());
 		assert_eq!(index_1, BnFr::one());
 		let indices = vec![index_0, index_1];
@@ -1790,19 +1790,19 @@ mod test {
 		let leaves = [leaf_1];
 		let tree = Tree_x5::new_sequential(inner_params, Rc::new(()), &leaves).unwrap();
 
-		let path_1 = tree.generate_membership_proof(0);
+		let path_1 = tree.generate_membership_proof(0).unwrap();
 		let paths = vec![path_1.clone()];
 
 		let public_amount = BnFr::one();
 
 		let ext_data_hash_1 = setup_vanchor_arbitrary_data(commitment_1);
 		let ext_data_hash = ext_data_hash_1; // We used it as a sample value for ext_data_hash in the tests
-		let root = tree.root().inner();
+		let root = tree.root().unwrap().inner().unwrap();
 
 		let mut root_set = [BnFr::rand(rng); TEST_M];
 		root_set[0] = root;
 		assert_eq!(root_set.len(), TEST_M);
-		let index_0: BnFr = path_1.get_index(&tree.root(), &leaf_1).unwrap();
+		let index_0: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_1).unwrap();
 		let indices = vec![index_0];
 
 		let signature = keypair_1
@@ -1919,19 +1919,19 @@ mod test {
 		let leaves = [leaf_1];
 		let tree = Tree_x5::new_sequential(inner_params, Rc::new(()), &leaves).unwrap();
 
-		let path_1 = tree.generate_membership_proof(0);
+		let path_1 = tree.generate_membership_proof(0).unwrap();
 		let paths = vec![path_1.clone()];
 
 		let public_amount = BnFr::one();
 
 		let ext_data_hash_1 = setup_vanchor_arbitrary_data(commitment_1);
 		let ext_data_hash = ext_data_hash_1; // We used it as a sample value for ext_data_hash in the tests
-		let root = tree.root().inner();
+		let root = tree.root().unwrap().inner().unwrap();
 
 		let mut root_set = [BnFr::rand(rng); TEST_M];
 		root_set[0] = root;
 		assert_eq!(root_set.len(), TEST_M);
-		let index_0: BnFr = path_1.get_index(&tree.root(), &leaf_1).unwrap();
+		let index_0: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_1).unwrap();
 		let indices = vec![index_0];
 
 		let signature = keypair_1
@@ -2078,21 +2078,21 @@ mod test {
 		let leaves = [leaf_1, leaf_2];
 		let tree = Tree_x5::new_sequential(inner_params, Rc::new(()), &leaves).unwrap();
 
-		let path_1 = tree.generate_membership_proof(0);
-		let path_2 = tree.generate_membership_proof(1);
+		let path_1 = tree.generate_membership_proof(0).unwrap();
+		let path_2 = tree.generate_membership_proof(1).unwrap();
 		let paths = vec![path_1.clone(), path_2.clone()];
 
 		let public_amount = BnFr::one();
 
 		let ext_data_hash_1 = setup_vanchor_arbitrary_data(commitment_1);
 		let ext_data_hash = ext_data_hash_1; // We used it as a sample value for ext_data_hash in the tests
-		let root = tree.root().inner();
+		let root = tree.root().unwrap().inner().unwrap();
 
 		let mut root_set = [BnFr::rand(rng); TEST_M];
 		root_set[0] = root;
 		assert_eq!(root_set.len(), TEST_M);
-		let index_0: BnFr = path_1.get_index(&tree.root(), &leaf_1).unwrap();
-		let index_1: BnFr = path_1.get_index(&tree.root(), &leaf_2).unwrap();
+		let index_0: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_1).unwrap();
+		let index_1: BnFr = path_1.get_index(&tree.root().unwrap(), &leaf_2).unwrap();
 		assert_eq!(index_0, BnFr::zero());
 		assert_eq!(index_1, BnFr::one());
 		let indices = vec![index_0, index_1];
@@ -2342,14 +2342,14 @@ mod test {
 		let tree_2 =
 			Tree_x5::new_sequential(inner_params, Rc::new(()), &leaves_on_chain_2).unwrap();
 
-		let path_1 = tree_1.generate_membership_proof(0);
-		let path_2 = tree_1.generate_membership_proof(1);
-		let path_3 = tree_1.generate_membership_proof(2);
-		let path_4 = tree_1.generate_membership_proof(3);
-		let path_5 = tree_2.generate_membership_proof(0);
-		let path_6 = tree_2.generate_membership_proof(1);
-		let path_7 = tree_2.generate_membership_proof(2);
-		let path_8 = tree_2.generate_membership_proof(3);
+		let path_1 = tree_1.generate_membership_proof(0).unwrap();
+		let path_2 = tree_1.generate_membership_proof(1).unwrap();
+		let path_3 = tree_1.generate_membership_proof(2).unwrap();
+		let path_4 = tree_1.generate_membership_proof(3).unwrap();
+		let path_5 = tree_2.generate_membership_proof(0).unwrap();
+		let path_6 = tree_2.generate_membership_proof(1).unwrap();
+		let path_7 = tree_2.generate_membership_proof(2).unwrap();
+		let path_8 = tree_2.generate_membership_proof(3).unwrap();
 		let paths = vec![
 			path_1.clone(),
 			path_2.clone(),
@@ -2365,21 +2365,21 @@ mod test {
 
 		let ext_data_hash_1 = setup_vanchor_arbitrary_data(commitment_1);
 		let ext_data_hash = ext_data_hash_1; // We used it as a sample value for ext_data_hash in the tests
-		let root_1 = tree_1.root().inner();
-		let root_2 = tree_2.root().inner();
+		let root_1 = tree_1.root().unwrap().inner().unwrap();
+		let root_2 = tree_2.root().unwrap().inner().unwrap();
 
 		let mut root_set = [BnFr::rand(rng); TEST_M];
 		root_set[0] = root_1;
 		root_set[1] = root_2;
 		assert_eq!(root_set.len(), TEST_M);
-		let index_0: BnFr = path_1.get_index(&tree_1.root(), &leaf_1).unwrap();
-		let index_1: BnFr = path_2.get_index(&tree_1.root(), &leaf_2).unwrap();
-		let index_2: BnFr = path_3.get_index(&tree_1.root(), &leaf_3).unwrap();
-		let index_3: BnFr = path_4.get_index(&tree_1.root(), &leaf_4).unwrap();
-		let index_4: BnFr = path_5.get_index(&tree_2.root(), &leaf_5).unwrap();
-		let index_5: BnFr = path_6.get_index(&tree_2.root(), &leaf_6).unwrap();
-		let index_6: BnFr = path_7.get_index(&tree_2.root(), &leaf_7).unwrap();
-		let index_7: BnFr = path_8.get_index(&tree_2.root(), &leaf_8).unwrap();
+		let index_0: BnFr = path_1.get_index(&tree_1.root().unwrap(), &leaf_1).unwrap();
+		let index_1: BnFr = path_2.get_index(&tree_1.root().unwrap(), &leaf_2).unwrap();
+		let index_2: BnFr = path_3.get_index(&tree_1.root().unwrap(), &leaf_3).unwrap();
+		let index_3: BnFr = path_4.get_index(&tree_1.root().unwrap(), &leaf_4).unwrap();
+		let index_4: BnFr = path_5.get_index(&tree_2.root().unwrap(), &leaf_5).unwrap();
+		let index_5: BnFr = path_6.get_index(&tree_2.root().unwrap(), &leaf_6).unwrap();
+		let index_6: BnFr = path_7.get_index(&tree_2.root().unwrap(), &leaf_7).unwrap();
+		let index_7: BnFr = path_8.get_index(&tree_2.root().unwrap(), &leaf_8).unwrap();
 		let indices = vec![
 			index_0, index_1, index_2, index_3, index_4, index_5, index_6, index_7,
 		];
@@ -2768,14 +2768,14 @@ mod test {
 		let tree_2 =
 			Tree_x5::new_sequential(inner_params, Rc::new(()), &leaves_on_chain_2).unwrap();
 
-		let path_1 = tree_1.generate_membership_proof(0);
-		let path_2 = tree_1.generate_membership_proof(1);
-		let path_3 = tree_1.generate_membership_proof(2);
-		let path_4 = tree_1.generate_membership_proof(3);
-		let path_5 = tree_2.generate_membership_proof(0);
-		let path_6 = tree_2.generate_membership_proof(1);
-		let path_7 = tree_2.generate_membership_proof(2);
-		let path_8 = tree_2.generate_membership_proof(3);
+		let path_1 = tree_1.generate_membership_proof(0).unwrap();
+		let path_2 = tree_1.generate_membership_proof(1).unwrap();
+		let path_3 = tree_1.generate_membership_proof(2).unwrap();
+		let path_4 = tree_1.generate_membership_proof(3).unwrap();
+		let path_5 = tree_2.generate_membership_proof(0).unwrap();
+		let path_6 = tree_2.generate_membership_proof(1).unwrap();
+		let path_7 = tree_2.generate_membership_proof(2).unwrap();
+		let path_8 = tree_2.generate_membership_proof(3).unwrap();
 		let paths = vec![
 			path_1.clone(),
 			path_2.clone(),
@@ -2791,21 +2791,21 @@ mod test {
 
 		let ext_data_hash_1 = setup_vanchor_arbitrary_data(commitment_1);
 		let ext_data_hash = ext_data_hash_1; // We used it as a sample value for ext_data_hash in the tests
-		let root_1 = tree_1.root().inner();
-		let root_2 = tree_2.root().inner();
+		let root_1 = tree_1.root().unwrap().inner().unwrap();
+		let root_2 = tree_2.root().unwrap().inner().unwrap();
 
 		let mut root_set = [BnFr::rand(rng); TEST_M];
 		root_set[0] = root_1;
 		root_set[1] = root_2;
 		assert_eq!(root_set.len(), TEST_M);
-		let index_0: BnFr = path_1.get_index(&tree_1.root(), &leaf_1).unwrap();
-		let index_1: BnFr = path_2.get_index(&tree_1.root(), &leaf_2).unwrap();
-		let index_2: BnFr = path_3.get_index(&tree_1.root(), &leaf_3).unwrap();
-		let index_3: BnFr = path_4.get_index(&tree_1.root(), &leaf_4).unwrap();
-		let index_4: BnFr = path_5.get_index(&tree_2.root(), &leaf_5).unwrap();
-		let index_5: BnFr = path_6.get_index(&tree_2.root(), &leaf_6).unwrap();
-		let index_6: BnFr = path_7.get_index(&tree_2.root(), &leaf_7).unwrap();
-		let index_7: BnFr = path_8.get_index(&tree_2.root(), &leaf_8).unwrap();
+		let index_0: BnFr = path_1.get_index(&tree_1.root().unwrap(), &leaf_1).unwrap();
+		let index_1: BnFr = path_2.get_index(&tree_1.root().unwrap(), &leaf_2).unwrap();
+		let index_2: BnFr = path_3.get_index(&tree_1.root().unwrap(), &leaf_3).unwrap();
+		let index_3: BnFr = path_4.get_index(&tree_1.root().unwrap(), &leaf_4).unwrap();
+		let index_4: BnFr = path_5.get_index(&tree_2.root().unwrap(), &leaf_5).unwrap();
+		let index_5: BnFr = path_6.get_index(&tree_2.root().unwrap(), &leaf_6).unwrap();
+		let index_6: BnFr = path_7.get_index(&tree_2.root().unwrap(), &leaf_7).unwrap();
+		let index_7: BnFr = path_8.get_index(&tree_2.root().unwrap(), &leaf_8).unwrap();
 		let indices = vec![
 			index_0, index_1, index_2, index_3, index_4, index_5, index_6, index_7,
 		];
@@ -0,0 +1,164 @@
+use super::common::*;
+use crate::circuit::batch::BatchInsertionCircuit;
+use arkworks_gadgets::merkle_tree::{compact::CompactPath, zeros::ZeroHashes};
+use arkworks_utils::utils::common::{setup_params_x17_3, setup_params_x5_3, Curve};
+
+use ark_crypto_primitives::SNARK;
+use ark_ec::PairingEngine;
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
+use ark_std::{
+	collections::BTreeMap,
+	rand::{CryptoRng, Rng, RngCore},
+	vec::Vec,
+};
+use core::convert::TryInto;
+use paste::paste;
+
+pub type Circuit_x5<F, const N: usize, const K: usize> =
+	BatchInsertionCircuit<F, TreeConfig_x5<F>, LeafCRHGadget<F>, PoseidonCRH_x5_3Gadget<F>, N, K>;
+
+pub type Circuit_x17<F, const N: usize, const K: usize> =
+	BatchInsertionCircuit<F, TreeConfig_x17<F>, LeafCRHGadget<F>, PoseidonCRH_x17_3Gadget<F>, N, K>;
+
+pub fn get_public_inputs<F: PrimeField, const K: usize>(
+	old_root: F,
+	new_root: F,
+	next_index: F,
+	leaves: [F; K],
+) -> Vec<F> {
+	let mut pub_ins = vec![old_root, new_root, next_index];
+	pub_ins.extend(leaves.iter());
+	pub_ins
+}
+
+// Generate code for batch insertion circuit setup functions:
+//	1. `setup_<circuit>`
+//	2. `setup_random_<circuit>`
+macro_rules! impl_setup_batch_circuit {
+	(
+		circuit: $circuit_ty:ident, // circuit type
+		config: $tc_ty:ident, // tree configuration type
+		params3_fn: $params3_fn:ident,
+		tree_setup_fn: $tree_setup_fn:ident
+	) => {
+		paste! {
+			pub fn [<setup_ $circuit_ty:lower>]<F: PrimeField, const N: usize, const K: usize>(
+				leaves: &[F],
+				new_leaves: [F; K],
+				curve: Curve,
+			) -> ($circuit_ty<F, N, K>, F, F, Vec<F>) {
+				let params3 = $params3_fn::<F>(curve);
+
+				let mut tree = $tree_setup_fn(leaves, &params3);
+				let old_root = match tree.root() {
+					Ok(root) => root.inner().unwrap(),
+					// nothing was inserted yet, so the root is the one of the empty tree
+					Err(_) => *ZeroHashes::<F>::new::<$tc_ty<F>>(&(), &params3)
+						.unwrap()
+						.as_field_elements()
+						.last()
+						.unwrap(),
+				};
+
+				// Every path is taken before its leaf is written
+				let next_index = leaves.len() as u64;
+				let mut paths = Vec::with_capacity(K);
+				for (i, leaf) in new_leaves.iter().enumerate() {
+					let index = next_index + i as u64;
+					paths.push(tree.generate_compact_membership_proof::<N>(index).unwrap());
+
+					let mut batch = BTreeMap::new();
+					batch.insert(index as u32, *leaf);
+					tree.insert_batch(&batch).unwrap();
+				}
+				let paths: [CompactPath<$tc_ty<F>, N>; K] = match paths.try_into() {
+					Ok(paths) => paths,
+					Err(_) => unreachable!("one path per new leaf"),
+				};
+				let new_root = tree.root().unwrap().inner().unwrap();
+
+				let next_index = F::from(next_index);
+				let mc = $circuit_ty::<F, N, K>::new(old_root, new_root, next_index, new_leaves, paths);
+				let public_inputs = get_public_inputs(old_root, new_root, next_index, new_leaves);
+				(mc, old_root, new_root, public_inputs)
+			}
+
+			pub fn [<setup_random_ $circuit_ty:lower>]<R: Rng, F: PrimeField, const N: usize, const K: usize>(
+				rng: &mut R,
+				curve: Curve,
+			) -> ($circuit_ty<F, N, K>, F, F, Vec<F>) {
+				let leaves = Vec::new();
+				let mut new_leaves = [F::zero(); K];
+				for leaf in new_leaves.iter_mut() {
+					*leaf = F::rand(rng);
+				}
+				[<setup_ $circuit_ty:lower>]::<F, N, K>(&leaves, new_leaves, curve)
+			}
+		}
+	};
+}
+
+impl_setup_batch_circuit!(
+	circuit: Circuit_x5,
+	config: TreeConfig_x5,
+	params3_fn: setup_params_x5_3,
+	tree_setup_fn: setup_tree_x5
+);
+impl_setup_batch_circuit!(
+	circuit: Circuit_x17,
+	config: TreeConfig_x17,
+	params3_fn: setup_params_x17_3,
+	tree_setup_fn: setup_tree_x17
+);
+
+macro_rules! impl_groth16_api_wrappers {
+	(
+		circuit: $circuit_ty:ident // circuit type
+	) => {
+		paste! {
+			pub fn [<prove_groth16_ $circuit_ty:lower>]<
+				R: RngCore + CryptoRng,
+				E: PairingEngine,
+				const N: usize,
+				const K: usize,
+			>(
+				pk: &ProvingKey<E>,
+				c: $circuit_ty<E::Fr, N, K>,
+				rng: &mut R,
+			) -> Proof<E> {
+				Groth16::<E>::prove(pk, c, rng).unwrap()
+			}
+
+			pub fn [<setup_groth16_ $circuit_ty:lower>]<
+				R: RngCore + CryptoRng,
+				E: PairingEngine,
+				const N: usize,
+				const K: usize,
+			>(
+				rng: &mut R,
+				c: $circuit_ty<E::Fr, N, K>,
+			) -> (ProvingKey<E>, VerifyingKey<E>) {
+				let (pk, vk) = Groth16::<E>::circuit_specific_setup(c, rng).unwrap();
+				(pk, vk)
+			}
+
+			pub fn [<setup_groth16_random_ $circuit_ty:lower>]<
+				R: RngCore + CryptoRng,
+				E: PairingEngine,
+				const N: usize,
+				const K: usize,
+			>(
+				rng: &mut R,
+				curve: Curve,
+			) -> (ProvingKey<E>, VerifyingKey<E>) {
+				let (circuit, ..) = [<setup_random_ $circuit_ty:lower>]::<R, E::Fr, N, K>(rng, curve);
+				let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit, rng).unwrap();
+				(pk, vk)
+			}
+		}
+	};
+}
+
+impl_groth16_api_wrappers!(circuit: Circuit_x5);
+impl_groth16_api_wrappers!(circuit: Circuit_x17);
@@ -19,6 +19,8 @@ use arkworks_utils::{
 
 use ark_crypto_primitives::{CRH as CRHTrait, SNARK};
 use ark_ec::PairingEngine;
+#[cfg(feature = "insecure_ed_on_bn254")]
+use ark_ed_on_bn254::Fr as EdOnBn254Fr;
 use ark_ff::PrimeField;
 use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
 use ark_std::{
@@ -61,6 +63,13 @@ pub type Circuit_x17<F, const N: usize, const M: usize> = BridgeCircuit<
 	M,
 >;
 
+// Circuits over the ed_on_bn254 scalar field, whose poseidon parameters are
+// not secure, for tests and benchmarks only
+#[cfg(feature = "insecure_ed_on_bn254")]
+pub type Circuit_x5_EdOnBn254<const N: usize, const M: usize> = Circuit_x5<EdOnBn254Fr, N, M>;
+#[cfg(feature = "insecure_ed_on_bn254")]
+pub type Circuit_x17_EdOnBn254<const N: usize, const M: usize> = Circuit_x17<EdOnBn254Fr, N, M>;
+
 pub fn setup_set<F: PrimeField, const M: usize>(
 	root: &F,
 	roots: &[F; M],
@@ -184,7 +193,7 @@ macro_rules! impl_setup_bridge_circuit {
 		$leaf_setup_fn(chain_id, &params5, rng); 				let mut leaves_new =
 		leaves.to_vec(); 				leaves_new.push(leaf);
 						let (tree, path) = $tree_setup_fn(&leaves_new, index, &params3);
-						let root = tree.root().inner();
+						let root = tree.root().unwrap().inner().unwrap();
 						let mut roots_new: [F; M] = [F::default(); M];
 						roots_new[0] = root;
 						let size_to_copy = if roots.len() > (M - 1) {
@@ -308,6 +317,81 @@ macro_rules! impl_groth16_api_wrappers {
 
 impl_groth16_api_wrappers!(circuit: Circuit_x5);
 impl_groth16_api_wrappers!(circuit: Circuit_x17);
+
+// Generate Marlin IPA wrappers for the circuits over the ed_on_bn254 scalar
+// field:
+//	1. `setup_marlin_ipa_<circuit>`
+//	2. `setup_marlin_ipa_random_<circuit>`
+//	3. `prove_marlin_ipa_<circuit>`
+#[cfg(feature = "insecure_ed_on_bn254")]
+macro_rules! impl_marlin_ipa_api_wrappers {
+	(
+		circuit: $circuit_ty:ident // circuit type
+	) => {
+		paste! {
+			pub fn [<setup_marlin_ipa_ $circuit_ty:lower>]<
+				R: RngCore + CryptoRng,
+				const N: usize,
+				const M: usize,
+			>(
+				rng: &mut R,
+				c: [<$circuit_ty _EdOnBn254>]<N, M>,
+				num_constraints: usize,
+				num_variables: usize,
+				num_non_zero: usize,
+			) -> (MarlinIPAProvingKey_EdOnBn254, MarlinIPAVerifyingKey_EdOnBn254) {
+				let srs = MarlinIPA_EdOnBn254::universal_setup(
+					num_constraints,
+					num_variables,
+					num_non_zero,
+					rng,
+				)
+				.unwrap();
+				MarlinIPA_EdOnBn254::index(&srs, c).unwrap()
+			}
+
+			pub fn [<setup_marlin_ipa_random_ $circuit_ty:lower>]<
+				R: RngCore + CryptoRng,
+				const N: usize,
+				const M: usize,
+			>(
+				rng: &mut R,
+				num_constraints: usize,
+				num_variables: usize,
+				num_non_zero: usize,
+			) -> (MarlinIPAProvingKey_EdOnBn254, MarlinIPAVerifyingKey_EdOnBn254) {
+				let (circuit, ..) = [<setup_random_ $circuit_ty:lower>]::<R, EdOnBn254Fr, N, M>(
+					rng,
+					Curve::EdOnBn254,
+				);
+				[<setup_marlin_ipa_ $circuit_ty:lower>](
+					rng,
+					circuit,
+					num_constraints,
+					num_variables,
+					num_non_zero,
+				)
+			}
+
+			pub fn [<prove_marlin_ipa_ $circuit_ty:lower>]<
+				R: RngCore + CryptoRng,
+				const N: usize,
+				const M: usize,
+			>(
+				pk: &MarlinIPAProvingKey_EdOnBn254,
+				c: [<$circuit_ty _EdOnBn254>]<N, M>,
+				rng: &mut R,
+			) -> MarlinIPAProof_EdOnBn254 {
+				MarlinIPA_EdOnBn254::prove(pk, c, rng).unwrap()
+			}
+		}
+	};
+}
+
+#[cfg(feature = "insecure_ed_on_bn254")]
+impl_marlin_ipa_api_wrappers!(circuit: Circuit_x5);
+#[cfg(feature = "insecure_ed_on_bn254")]
+impl_marlin_ipa_api_wrappers!(circuit: Circuit_x17);
 #[cfg(test)]
 mod test {
 	use super::*;
@@ -404,7 +488,7 @@ mod test {
 		leaves_new.push(leaf);
 		let (tree, path) =
 			setup_tree_and_create_path_tree_x5::<Bls381, TEST_N>(&leaves_new, 0, &params3);
-		let root = tree.root().inner();
+		let root = tree.root().unwrap().inner().unwrap();
 		roots[0] = root;
 		let set_private_inputs = setup_set::<Bls381, TEST_M>(&root, &roots);
 
@@ -8,6 +8,15 @@ use arkworks_utils::{mimc::MiMCParameters, poseidon::PoseidonParameters};
 use ark_ff::fields::PrimeField;
 use ark_std::{marker::PhantomData, rc::Rc};
 use paste::paste;
+#[cfg(feature = "insecure_ed_on_bn254")]
+use {
+	ark_ed_on_bn254::{EdwardsAffine as EdOnBn254Affine, Fr as EdOnBn254Fr},
+	ark_marlin::{IndexProverKey, IndexVerifierKey, Marlin, Proof as MarlinProof},
+	ark_poly::univariate::DensePolynomial,
+	ark_poly_commit::ipa_pc::InnerProductArgPC,
+	ark_std::rand::{CryptoRng, RngCore},
+	blake2::Blake2s,
+};
 
 pub type PoseidonCRH_x3_3<F> = CRH<F>;
 pub type PoseidonCRH_x3_3Gadget<F> = CRHGadget<F>;
@@ -33,6 +42,31 @@ pub type PoseidonCRH_x17_3Gadget<F> = CRHGadget<F>;
 pub type PoseidonCRH_x17_5<F> = CRH<F>;
 pub type PoseidonCRH_x17_5Gadget<F> = CRHGadget<F>;
 
+/// Marlin with the inner product argument over BabyJubJub, for circuits over
+/// its scalar field, which no pairing friendly curve has. Its poseidon
+/// parameters are not secure, so these are for tests and benchmarks only.
+#[cfg(feature = "insecure_ed_on_bn254")]
+pub type IPA_EdOnBn254 = InnerProductArgPC<EdOnBn254Affine, Blake2s, DensePolynomial<EdOnBn254Fr>>;
+#[cfg(feature = "insecure_ed_on_bn254")]
+pub type MarlinIPA_EdOnBn254 = Marlin<EdOnBn254Fr, IPA_EdOnBn254, Blake2s>;
+#[cfg(feature = "insecure_ed_on_bn254")]
+pub type MarlinIPAProvingKey_EdOnBn254 = IndexProverKey<EdOnBn254Fr, IPA_EdOnBn254>;
+#[cfg(feature = "insecure_ed_on_bn254")]
+pub type MarlinIPAVerifyingKey_EdOnBn254 = IndexVerifierKey<EdOnBn254Fr, IPA_EdOnBn254>;
+#[cfg(feature = "insecure_ed_on_bn254")]
+pub type MarlinIPAProof_EdOnBn254 = MarlinProof<EdOnBn254Fr, IPA_EdOnBn254>;
+
+#[cfg(feature = "insecure_ed_on_bn254")]
+pub fn verify_marlin_ipa_ed_on_bn254<R: RngCore + CryptoRng>(
+	vk: &MarlinIPAVerifyingKey_EdOnBn254,
+	public_inputs: &[EdOnBn254Fr],
+	proof: &MarlinIPAProof_EdOnBn254,
+	rng: &mut R,
+) -> bool {
+	// A malformed proof is an invalid one
+	MarlinIPA_EdOnBn254::verify(vk, public_inputs, proof, rng).unwrap_or(false)
+}
+
 #[derive(Default, Clone)]
 pub struct MiMCRounds_220_3;
 
@@ -105,7 +139,7 @@ macro_rules! impl_setup_tree {
 				// Making the merkle tree
 				let mt = [<setup_ $tree_ty:lower>](leaves, params);
 				// Getting the proof path
-				let path = mt.generate_membership_proof(index);
+				let path = mt.generate_membership_proof(index).unwrap();
 				(mt, path)
 			}
 		}
@@ -127,3 +161,79 @@ impl_setup_tree!(
 	config: TreeConfig_MiMC220,
 	params: MiMCParameters
 );
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_bls12_381::Fr as Bls381;
+	use ark_ff::{UniformRand, Zero};
+	use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::test_rng;
+	use arkworks_gadgets::merkle_tree::{
+		compact::CompactPath,
+		constraints::{CompactPathVar, NodeVar, PathVar},
+		zeros::ZeroHashes,
+	};
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	const HEIGHT: usize = 30;
+
+	type NodeVar_x5 = NodeVar<
+		Bls381,
+		TreeConfig_x5<Bls381>,
+		PoseidonCRH_x5_3Gadget<Bls381>,
+		LeafCRHGadget<Bls381>,
+	>;
+
+	#[test]
+	fn compact_path_should_need_fewer_constraints() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_3(Curve::Bls381);
+
+		let leaves = vec![Bls381::rand(rng), Bls381::rand(rng), Bls381::rand(rng)];
+		let (tree, path) = setup_tree_and_create_path_tree_x5::<_, HEIGHT>(&leaves, 1, &params);
+		let root = tree.root().unwrap();
+		let compact = CompactPath::from_path(&path, &leaves[1]).unwrap();
+
+		let cs = ConstraintSystem::<Bls381>::new_ref();
+		let path_var = PathVar::<_, _, _, _, HEIGHT>::new_witness(cs.clone(), || Ok(path)).unwrap();
+		let root_var = NodeVar_x5::new_witness(cs.clone(), || Ok(root.clone())).unwrap();
+		let leaf_var = FpVar::new_witness(cs.clone(), || Ok(leaves[1])).unwrap();
+		let res = path_var.check_membership(&root_var, &leaf_var).unwrap();
+		assert!(cs.is_satisfied().unwrap());
+		assert!(res.value().unwrap());
+		let path_constraints = cs.num_constraints();
+
+		let cs = ConstraintSystem::<Bls381>::new_ref();
+		let path_var =
+			CompactPathVar::<_, _, _, _, HEIGHT>::new_witness(cs.clone(), || Ok(compact)).unwrap();
+		let root_var = NodeVar_x5::new_witness(cs.clone(), || Ok(root)).unwrap();
+		let leaf_var = FpVar::new_witness(cs.clone(), || Ok(leaves[1])).unwrap();
+		let res = path_var.check_membership(&root_var, &leaf_var).unwrap();
+		assert!(cs.is_satisfied().unwrap());
+		assert!(res.value().unwrap());
+		let compact_constraints = cs.num_constraints();
+
+		assert!(
+			compact_constraints < path_constraints,
+			"compact path: {} constraints, path: {} constraints",
+			compact_constraints,
+			path_constraints
+		);
+	}
+
+	#[test]
+	fn should_export_zero_hashes_of_tree_x5() {
+		let params = setup_params_x5_3::<Bls381>(Curve::Bls381);
+
+		let zeros = ZeroHashes::<Bls381>::new::<TreeConfig_x5<Bls381>>(&(), &params).unwrap();
+		let hashes = zeros.as_field_elements();
+		assert_eq!(hashes.len(), HEIGHT + 1);
+		// the identity leaf hasher keeps the zero leaf as it is
+		assert_eq!(hashes[0], Bls381::zero());
+
+		let tree = setup_tree_x5::<Bls381>(&[Bls381::zero()], &params);
+		assert_eq!(tree.root().unwrap().inner().unwrap(), hashes[HEIGHT]);
+	}
+}
@@ -15,6 +15,8 @@ use arkworks_utils::{
 
 use ark_crypto_primitives::{CRH as CRHTrait, SNARK};
 use ark_ec::PairingEngine;
+#[cfg(feature = "insecure_ed_on_bn254")]
+use ark_ed_on_bn254::Fr as EdOnBn254Fr;
 use ark_ff::PrimeField;
 use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
 use ark_std::{
@@ -52,6 +54,13 @@ pub type Circuit_x17<F, const N: usize> = MixerCircuit<
 	N,
 >;
 
+// Circuits over the ed_on_bn254 scalar field, whose poseidon parameters are
+// not secure, for tests and benchmarks only
+#[cfg(feature = "insecure_ed_on_bn254")]
+pub type Circuit_x5_EdOnBn254<const N: usize> = Circuit_x5<EdOnBn254Fr, N>;
+#[cfg(feature = "insecure_ed_on_bn254")]
+pub type Circuit_x17_EdOnBn254<const N: usize> = Circuit_x17<EdOnBn254Fr, N>;
+
 pub type Leaf_MiMC220<F> = MixerLeaf<F, MiMCCRH_220<F>>;
 pub type LeafGadget_MiMC220<F> = MixerLeafGadget<F, MiMCCRH_220<F>, MiMCCRH_220Gadget<F>>;
 
@@ -153,7 +162,7 @@ macro_rules! impl_setup_mixer_circuit {
 		F>(&params5, rng); 				let mut leaves_new = leaves.to_vec();
 						leaves_new.push(leaf);
 						let (tree, path) = $tree_setup_fn::<F, N>(&leaves_new, index, &params3);
-						let root = tree.root().inner();
+						let root = tree.root().unwrap().inner().unwrap();
 
 						let mc = $circuit_ty::<F, N>::new(
 							arbitrary_input,
@@ -255,6 +264,70 @@ impl_groth16_api_wrappers!(circuit: Circuit_x5);
 impl_groth16_api_wrappers!(circuit: Circuit_x17);
 impl_groth16_api_wrappers!(circuit: Circuit_MiMC220);
 
+// Generate Marlin IPA wrappers for the circuits over the ed_on_bn254 scalar
+// field:
+//	1. `setup_marlin_ipa_<circuit>`
+//	2. `setup_marlin_ipa_random_<circuit>`
+//	3. `prove_marlin_ipa_<circuit>`
+#[cfg(feature = "insecure_ed_on_bn254")]
+macro_rules! impl_marlin_ipa_api_wrappers {
+	(
+		circuit: $circuit_ty:ident // circuit type
+	) => {
+		paste! {
+			pub fn [<setup_marlin_ipa_ $circuit_ty:lower>]<R: RngCore + CryptoRng, const N: usize>(
+				rng: &mut R,
+				c: [<$circuit_ty _EdOnBn254>]<N>,
+				num_constraints: usize,
+				num_variables: usize,
+				num_non_zero: usize,
+			) -> (MarlinIPAProvingKey_EdOnBn254, MarlinIPAVerifyingKey_EdOnBn254) {
+				let srs = MarlinIPA_EdOnBn254::universal_setup(
+					num_constraints,
+					num_variables,
+					num_non_zero,
+					rng,
+				)
+				.unwrap();
+				MarlinIPA_EdOnBn254::index(&srs, c).unwrap()
+			}
+
+			pub fn [<setup_marlin_ipa_random_ $circuit_ty:lower>]<
+				R: RngCore + CryptoRng,
+				const N: usize,
+			>(
+				rng: &mut R,
+				num_constraints: usize,
+				num_variables: usize,
+				num_non_zero: usize,
+			) -> (MarlinIPAProvingKey_EdOnBn254, MarlinIPAVerifyingKey_EdOnBn254) {
+				let (circuit, ..) =
+					[<setup_random_ $circuit_ty:lower>]::<R, EdOnBn254Fr, N>(rng, Curve::EdOnBn254);
+				[<setup_marlin_ipa_ $circuit_ty:lower>](
+					rng,
+					circuit,
+					num_constraints,
+					num_variables,
+					num_non_zero,
+				)
+			}
+
+			pub fn [<prove_marlin_ipa_ $circuit_ty:lower>]<R: RngCore + CryptoRng, const N: usize>(
+				pk: &MarlinIPAProvingKey_EdOnBn254,
+				c: [<$circuit_ty _EdOnBn254>]<N>,
+				rng: &mut R,
+			) -> MarlinIPAProof_EdOnBn254 {
+				MarlinIPA_EdOnBn254::prove(pk, c, rng).unwrap()
+			}
+		}
+	};
+}
+
+#[cfg(feature = "insecure_ed_on_bn254")]
+impl_marlin_ipa_api_wrappers!(circuit: Circuit_x5);
+#[cfg(feature = "insecure_ed_on_bn254")]
+impl_marlin_ipa_api_wrappers!(circuit: Circuit_x17);
+
 #[cfg(test)]
 mod test {
 	use super::*;
@@ -335,7 +408,7 @@ mod test {
 		leaves_new.push(leaf);
 		let (tree, path) =
 			setup_tree_and_create_path_tree_x5::<Bls381, LEN>(&leaves_new, 0, &params3);
-		let root = tree.root().inner();
+		let root = tree.root().unwrap().inner().unwrap();
 
 		let mc = Circuit_x5::<Bls381, LEN>::new(
 			arbitrary_input,
@@ -424,7 +497,7 @@ mod test {
 		leaves_new.push(leaf);
 		let (tree, path) =
 			setup_tree_and_create_path_tree_mimc220::<Bn254Fr, LEN>(&leaves_new, 0, &params);
-		let root = tree.root().inner();
+		let root = tree.root().unwrap().inner().unwrap();
 
 		let mc = Circuit_MiMC220::<Bn254Fr, LEN>::new(
 			arbitrary_input,
@@ -457,4 +530,33 @@ mod test {
 
 		assert!(res);
 	}
+
+	#[cfg(feature = "insecure_ed_on_bn254")]
+	#[test]
+	fn should_prove_with_marlin_ipa_over_ed_on_bn254() {
+		let mut rng = test_rng();
+		let curve = Curve::EdOnBn254;
+		let recipient = EdOnBn254Fr::from(0u8);
+		let relayer = EdOnBn254Fr::from(0u8);
+		let fee = EdOnBn254Fr::from(0u8);
+		let refund = EdOnBn254Fr::from(0u8);
+		let leaves = Vec::new();
+		let (circuit, _, _, _, public_inputs) = setup_circuit_x5::<_, EdOnBn254Fr, LEN>(
+			&leaves, 0, recipient, relayer, fee, refund, &mut rng, curve,
+		);
+
+		// Bounds on the circuit size, the number of non zero entries being the
+		// largest for poseidon
+		let (pk, vk) = setup_marlin_ipa_circuit_x5::<_, LEN>(
+			&mut rng,
+			circuit.clone(),
+			1 << 14,
+			1 << 14,
+			1 << 17,
+		);
+		let proof = prove_marlin_ipa_circuit_x5::<_, LEN>(&pk, circuit, &mut rng);
+		let res = verify_marlin_ipa_ed_on_bn254(&vk, &public_inputs, &proof, &mut rng);
+
+		assert!(res);
+	}
 }
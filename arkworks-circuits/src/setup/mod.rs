@@ -1,5 +1,7 @@
 #![allow(non_camel_case_types)]
 
+pub mod batch;
+
 pub mod bridge;
 
 pub mod common;
@@ -54,6 +54,28 @@ impl<
 		const OUTS: usize,
 	> VAnchorProverSetup<F, H, HG, LHGT, HGT, P, K, M, INS, OUTS>
 {
+	pub fn new(
+		h2_params: H::Parameters,
+		h4_params: H::Parameters,
+		h5_params: H::Parameters,
+		leaf_params: <P::LeafH as CRHTrait>::Parameters,
+		inner_params: <P::H as CRHTrait>::Parameters,
+	) -> Self {
+		Self {
+			h2_params,
+			h4_params,
+			h5_params,
+			leaf_params,
+			inner_params,
+			_field: PhantomData,
+			_h: PhantomData,
+			_hg: PhantomData,
+			_lhgt: PhantomData,
+			_hgt: PhantomData,
+			_p: PhantomData,
+		}
+	}
+
 	pub fn new_key_pairs(&self, private_keys: &[F]) -> (Vec<Keypair<F, H>>, Vec<H::Output>) {
 		let mut keypairs = Vec::new();
 		let mut pub_keys = Vec::new();
@@ -140,6 +162,8 @@ impl<
 		(private, public, leaves, nullifiers)
 	}
 
+	/// Creates a leaf and its nullifier, which is derived from the keypair's
+	/// signature over the leaf and its index, as the circuit expects
 	pub fn new_leaf(
 		&self,
 		chain_id: F,
@@ -160,13 +184,10 @@ impl<
 		)
 		.unwrap();
 
-		let nullifier = VAnchorLeaf::<F, H>::create_nullifier(
-			&keypair.private_key,
-			&leaf,
-			&self.h4_params,
-			index,
-		)
-		.unwrap();
+		let signature = keypair.signature(&leaf, index, &self.h4_params).unwrap();
+		let nullifier =
+			VAnchorLeaf::<F, H>::create_nullifier(&signature, &leaf, &self.h4_params, index)
+				.unwrap();
 
 		(leaf_private, leaf_public, leaf, nullifier)
 	}
@@ -192,7 +213,7 @@ impl<
 		// Getting the proof paths
 		let mut paths = Vec::new();
 		for i in indices {
-			let path = mt.generate_membership_proof(*i);
+			let path = mt.generate_membership_proof(*i).unwrap();
 			paths.push(path);
 		}
 		paths
@@ -202,6 +223,8 @@ impl<
 		SetMembership::generate_secrets(root, roots).unwrap()
 	}
 
+	/// Creates a valid circuit spending `INS` inputs into `OUTS` outputs,
+	/// together with its public inputs
 	#[allow(clippy::type_complexity)]
 	pub fn setup_circuit<R: Rng>(
 		self,
@@ -210,25 +233,41 @@ impl<
 		VAnchorCircuit<F, H, HG, P, LHGT, HGT, K, INS, OUTS, M>,
 		Vec<F>,
 	) {
-		let in_chain_ids: Vec<F> = (0..INS).into_iter().map(|_| F::rand(rng)).collect();
-		// TODO: set proper input amounts
-		let in_amounts: Vec<F> = (0..INS).into_iter().map(|_| F::rand(rng)).collect();
+		// The circuit checks every input against the same chain id
+		let in_chain_id = F::rand(rng);
+		let in_chain_ids: Vec<F> = vec![in_chain_id; INS];
+		let in_amounts: Vec<F> = (0..INS)
+			.into_iter()
+			.map(|_| F::from(rng.gen::<u32>()))
+			.collect();
 		let in_blindings: Vec<F> = (0..INS).into_iter().map(|_| F::rand(rng)).collect();
 		let in_private_keys: Vec<F> = (0..INS).into_iter().map(|_| F::rand(rng)).collect();
 
+		let public_amount = F::from(5_u32);
+
 		let out_chain_ids: Vec<F> = (0..OUTS).into_iter().map(|_| F::rand(rng)).collect();
-		// TODO: set proper output amounts
-		let out_amounts: Vec<F> = (0..OUTS).into_iter().map(|_| F::rand(rng)).collect();
+		// The first output takes the inputs and the public amount, so that the
+		// amounts balance
+		let mut out_amounts: Vec<F> = vec![F::zero(); OUTS];
+		out_amounts[0] = in_amounts
+			.iter()
+			.fold(public_amount, |sum, amount| sum + amount);
 		let out_blindings: Vec<F> = (0..OUTS).into_iter().map(|_| F::rand(rng)).collect();
-		let out_public_keys: Vec<F> = (0..OUTS).into_iter().map(|_| F::rand(rng)).collect();
-
-		let public_amount = F::from(5_u32);
+		let out_private_keys: Vec<F> = (0..OUTS).into_iter().map(|_| F::rand(rng)).collect();
+		// Outputs belong to new keypairs and are not yet in the tree
+		let out_indices: Vec<F> = vec![F::zero(); OUTS];
 		let ext_data = F::rand(rng);
 		let indices: Vec<u64> = (0..INS).into_iter().map(|x| x as u64).collect();
 		let indices_f: Vec<F> = (0..INS).into_iter().map(|x| F::from(x as u64)).collect();
 
 		let arbitrary = Self::new_arbitrary_data(ext_data);
 		let (in_keypairs, _) = self.new_key_pairs(&in_private_keys);
+		let (out_keypairs, out_public_keys) = self.new_key_pairs(&out_private_keys);
+		// Hash outputs are decoded little-endian, matching `to_bytes!`
+		let out_public_keys: Vec<F> = out_public_keys
+			.iter()
+			.map(|x| F::from_le_bytes_mod_order(&to_bytes![x].unwrap()))
+			.collect();
 
 		let (in_leaf_private, in_leaf_public, in_leaves, in_nullifier_hashes) = self
 			.new_input_leaves(
@@ -242,8 +281,8 @@ impl<
 			out_chain_ids,
 			out_amounts,
 			out_blindings,
-			&indices_f,
-			&in_keypairs,
+			&out_indices,
+			&out_keypairs,
 		);
 
 		let paths = self.new_paths(&in_leaves, &indices);
@@ -264,12 +303,12 @@ impl<
 
 		let in_nullifier_hashes_f: Vec<F> = in_nullifier_hashes
 			.iter()
-			.map(|x| F::from_be_bytes_mod_order(&to_bytes![x].unwrap()))
+			.map(|x| F::from_le_bytes_mod_order(&to_bytes![x].unwrap()))
 			.collect();
 
 		let out_commitments_f: Vec<F> = out_commitments
 			.iter()
-			.map(|x| F::from_be_bytes_mod_order(&to_bytes![x].unwrap()))
+			.map(|x| F::from_le_bytes_mod_order(&to_bytes![x].unwrap()))
 			.collect();
 
 		let mut public_inputs = vec![public_amount];
@@ -309,3 +348,67 @@ impl<
 pub fn setup_vanchor_arbitrary_data<F: PrimeField>(ext_data: F) -> VAnchorArbitraryData<F> {
 	VAnchorArbitraryData::new(ext_data)
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::setup::common::{
+		LeafCRHGadget, PoseidonCRH_x5_3Gadget, PoseidonCRH_x5_5, PoseidonCRH_x5_5Gadget,
+		TreeConfig_x5,
+	};
+	use ark_bls12_381::Bls12_381;
+	use ark_bn254::Bn254;
+	use ark_crypto_primitives::SNARK;
+	use ark_ec::PairingEngine;
+	use ark_groth16::Groth16;
+	use ark_std::test_rng;
+	use arkworks_utils::utils::common::{
+		setup_params_x5_2, setup_params_x5_3, setup_params_x5_4, setup_params_x5_5, verify_groth16,
+		Curve,
+	};
+
+	// TreeConfig_x5 HEIGHT is hardcoded to 30
+	const K: usize = 30;
+	const M: usize = 2;
+	const INS: usize = 2;
+	const OUTS: usize = 2;
+
+	type VAnchorSetup_x5<F> = VAnchorProverSetup<
+		F,
+		PoseidonCRH_x5_5<F>,
+		PoseidonCRH_x5_5Gadget<F>,
+		LeafCRHGadget<F>,
+		PoseidonCRH_x5_3Gadget<F>,
+		TreeConfig_x5<F>,
+		K,
+		M,
+		INS,
+		OUTS,
+	>;
+
+	fn setup_and_prove_groth16<E: PairingEngine>(curve: Curve) -> bool {
+		let rng = &mut test_rng();
+		let prover = VAnchorSetup_x5::<E::Fr>::new(
+			setup_params_x5_2(curve),
+			setup_params_x5_4(curve),
+			setup_params_x5_5(curve),
+			(),
+			setup_params_x5_3(curve),
+		);
+		let (circuit, public_inputs) = prover.setup_circuit(rng);
+
+		let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit.clone(), rng).unwrap();
+		let proof = Groth16::<E>::prove(&pk, circuit, rng).unwrap();
+		verify_groth16(&vk, &public_inputs, &proof)
+	}
+
+	#[test]
+	fn should_setup_and_prove_vanchor_on_bn254() {
+		assert!(setup_and_prove_groth16::<Bn254>(Curve::Bn254));
+	}
+
+	#[test]
+	fn should_setup_and_prove_vanchor_on_bls381() {
+		assert!(setup_and_prove_groth16::<Bls12_381>(Curve::Bls381));
+	}
+}
@@ -0,0 +1,24 @@
+use super::FieldHasher;
+use ark_crypto_primitives::crh::constraints::CRHGadget as CRHGadgetTrait;
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::SynthesisError;
+
+/// Gadget for `FieldHasher`, hashing field element variables without
+/// decomposing them into bytes.
+pub trait FieldHasherGadget<H: FieldHasher<F>, F: PrimeField>:
+	CRHGadgetTrait<H, F, OutputVar = FpVar<F>>
+{
+	fn hash(
+		parameters: &<Self as CRHGadgetTrait<H, F>>::ParametersVar,
+		inputs: &[FpVar<F>],
+	) -> Result<FpVar<F>, SynthesisError>;
+
+	fn hash_two(
+		parameters: &<Self as CRHGadgetTrait<H, F>>::ParametersVar,
+		left: &FpVar<F>,
+		right: &FpVar<F>,
+	) -> Result<FpVar<F>, SynthesisError> {
+		Self::hash(parameters, &[left.clone(), right.clone()])
+	}
+}
@@ -0,0 +1,23 @@
+use ark_crypto_primitives::{Error, CRH as CRHTrait};
+use ark_ff::PrimeField;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// A CRH that can hash field elements directly, skipping the serialization
+/// of `CRH::evaluate`.
+///
+/// `hash(parameters, inputs)` must equal `evaluate(parameters, bytes)` where
+/// `bytes` are the `inputs` serialized with `to_bytes!`, so both can be used
+/// on the same data.
+pub trait FieldHasher<F: PrimeField>: CRHTrait<Output = F> {
+	fn hash(parameters: &<Self as CRHTrait>::Parameters, inputs: &[F]) -> Result<F, Error>;
+
+	fn hash_two(
+		parameters: &<Self as CRHTrait>::Parameters,
+		left: &F,
+		right: &F,
+	) -> Result<F, Error> {
+		Self::hash(parameters, &[*left, *right])
+	}
+}
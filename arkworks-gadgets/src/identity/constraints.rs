@@ -1,4 +1,5 @@
 use super::CRH;
+use crate::field_hasher::constraints::FieldHasherGadget;
 use ark_crypto_primitives::crh::constraints::CRHGadget as CRHGadgetTrait;
 use ark_ff::PrimeField;
 use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, prelude::*, uint8::UInt8};
@@ -33,6 +34,15 @@ impl<F: PrimeField> CRHGadgetTrait<CRH<F>, F> for CRHGadget<F> {
 	}
 }
 
+impl<F: PrimeField> FieldHasherGadget<CRH<F>, F> for CRHGadget<F> {
+	fn hash(_: &Params<F>, inputs: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+		match inputs {
+			[input] => Ok(input.clone()),
+			_ => Err(SynthesisError::Unsatisfiable),
+		}
+	}
+}
+
 impl<F: PrimeField> AllocVar<(), F> for Params<F> {
 	fn new_variable<T: Borrow<()>>(
 		_: impl Into<Namespace<F>>,
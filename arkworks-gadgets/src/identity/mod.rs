@@ -1,3 +1,4 @@
+use crate::field_hasher::FieldHasher;
 use ark_crypto_primitives::{CryptoError, Error, CRH as CRHTrait};
 use ark_ff::{fields::PrimeField, BigInteger};
 use ark_std::{marker::PhantomData, rand::Rng, vec::Vec};
@@ -32,6 +33,15 @@ impl<F: PrimeField> CRHTrait for CRH<F> {
 	}
 }
 
+impl<F: PrimeField> FieldHasher<F> for CRH<F> {
+	fn hash(_: &(), inputs: &[F]) -> Result<F, Error> {
+		match inputs {
+			[input] => Ok(*input),
+			_ => Err(CryptoError::IncorrectInputLength(inputs.len()).into()),
+		}
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use super::CRH;
@@ -1,3 +1,4 @@
+use crate::field_hasher::{constraints::FieldHasherGadget, FieldHasher};
 use ark_crypto_primitives::{crh::CRHGadget, CRH};
 use ark_ff::fields::PrimeField;
 use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
@@ -49,6 +50,27 @@ impl<F: PrimeField, H: CRH, HG: CRHGadget<H, F>> KeypairVar<F, H, HG> {
 	}
 }
 
+impl<F: PrimeField, H: FieldHasher<F>, HG: FieldHasherGadget<H, F>> KeypairVar<F, H, HG> {
+	/// Same as `public_key`, without decomposing the private key into bytes
+	pub fn public_key_fields(
+		&self,
+		parameters2: &HG::ParametersVar,
+	) -> Result<FpVar<F>, SynthesisError> {
+		HG::hash(parameters2, &[self.private_key.clone()])
+	}
+
+	/// Same as `signature`, without decomposing the field elements into bytes
+	pub fn signature_fields(
+		&self,
+		commitment: &FpVar<F>,
+		index: &FpVar<F>,
+		h_w4: &HG::ParametersVar,
+	) -> Result<FpVar<F>, SynthesisError> {
+		let inputs = [self.private_key.clone(), commitment.clone(), index.clone()];
+		HG::hash(h_w4, &inputs)
+	}
+}
+
 impl<F: PrimeField, H2: CRH, HG2: CRHGadget<H2, F>> AllocVar<Keypair<F, H2>, F>
 	for KeypairVar<F, H2, HG2>
 {
@@ -172,4 +194,52 @@ mod test {
 		assert_eq!(signature, new_signature_var.value().unwrap());
 		assert!(res.cs().is_satisfied().unwrap());
 	}
+
+	#[test]
+	fn should_create_keypair_from_fields_with_fewer_constraints() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bn254;
+		let params2 = setup_params_x5_2(curve);
+		let params4 = setup_params_x5_4(curve);
+		let private_key = Fq::rand(rng);
+		let commitment = Fq::rand(rng);
+		let index = Fq::zero();
+
+		let keypair = Keypair::<Fq, PoseidonCRH>::new(private_key);
+		let public_key = keypair.public_key(&params2).unwrap();
+		let signature = keypair.signature(&commitment, &index, &params4).unwrap();
+
+		let synthesize = |from_fields: bool| {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let params2_var = PoseidonParametersVar::new_constant(cs.clone(), &params2).unwrap();
+			let params4_var = PoseidonParametersVar::new_constant(cs.clone(), &params4).unwrap();
+			let privkey_var = FpVar::<Fq>::new_witness(cs.clone(), || Ok(private_key)).unwrap();
+			let commitment_var = FpVar::<Fq>::new_witness(cs.clone(), || Ok(commitment)).unwrap();
+			let index_var = FpVar::<Fq>::new_witness(cs.clone(), || Ok(index)).unwrap();
+			let keypair_var =
+				KeypairVar::<Fq, PoseidonCRH, PoseidonCRHGadget>::new(&privkey_var).unwrap();
+
+			let (public_key_var, signature_var) = if from_fields {
+				(
+					keypair_var.public_key_fields(&params2_var).unwrap(),
+					keypair_var
+						.signature_fields(&commitment_var, &index_var, &params4_var)
+						.unwrap(),
+				)
+			} else {
+				(
+					keypair_var.public_key(&params2_var).unwrap(),
+					keypair_var
+						.signature(&commitment_var, &index_var, &params4_var)
+						.unwrap(),
+				)
+			};
+			assert_eq!(public_key_var.value().unwrap(), public_key);
+			assert_eq!(signature_var.value().unwrap(), signature);
+			assert!(cs.is_satisfied().unwrap());
+			cs.num_constraints()
+		};
+
+		assert!(synthesize(true) < synthesize(false));
+	}
 }
@@ -1,5 +1,6 @@
+use crate::field_hasher::FieldHasher;
 use ark_crypto_primitives::{Error, CRH};
-use ark_ff::{to_bytes, ToBytes};
+use ark_ff::{to_bytes, PrimeField, ToBytes};
 use ark_std::marker::PhantomData;
 
 #[cfg(feature = "r1cs")]
@@ -36,6 +37,23 @@ impl<B: Clone + ToBytes, H: CRH> Keypair<B, H> {
 	}
 }
 
+impl<F: PrimeField, H: FieldHasher<F>> Keypair<F, H> {
+	/// Same as `public_key`, without serializing the private key
+	pub fn public_key_fields(&self, h: &H::Parameters) -> Result<F, Error> {
+		H::hash(h, &[self.private_key])
+	}
+
+	/// Same as `signature`, without serializing the field elements
+	pub fn signature_fields(
+		&self,
+		commitment: &F,
+		index: &F,
+		h_w4: &H::Parameters,
+	) -> Result<F, Error> {
+		H::hash(h_w4, &[self.private_key, *commitment, *index])
+	}
+}
+
 impl<B: Clone + ToBytes, H2: CRH> Clone for Keypair<B, H2> {
 	fn clone(&self) -> Self {
 		let private_key = self.private_key.clone();
@@ -76,7 +94,8 @@ mod test {
 		let keypair = Keypair::<Fq, PoseidonCRH>::new(private_key.clone());
 		let new_pubkey = keypair.public_key(&params).unwrap();
 
-		assert_eq!(new_pubkey, pubkey)
+		assert_eq!(new_pubkey, pubkey);
+		assert_eq!(keypair.public_key_fields(&params).unwrap(), pubkey);
 	}
 	#[test]
 	fn should_crate_new_signature() {
@@ -96,5 +115,9 @@ mod test {
 		let ev_res = PoseidonCRH::evaluate(&params4, &inputs_signature).unwrap();
 		let signature = keypair.signature(&commitment, &index, &params4).unwrap();
 		assert_eq!(ev_res, signature);
+		let signature = keypair
+			.signature_fields(&commitment, &index, &params4)
+			.unwrap();
+		assert_eq!(ev_res, signature);
 	}
 }
@@ -1,5 +1,8 @@
 use super::Private;
-use crate::Vec;
+use crate::{
+	field_hasher::{constraints::FieldHasherGadget, FieldHasher},
+	Vec,
+};
 use ark_crypto_primitives::{crh::CRHGadget, CRH};
 use ark_ff::fields::PrimeField;
 use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
@@ -52,6 +55,26 @@ impl<F: PrimeField, H: CRH, HG: CRHGadget<H, F>> BasicLeafGadget<F, H, HG> {
 	}
 }
 
+impl<F: PrimeField, H: FieldHasher<F>, HG: FieldHasherGadget<H, F>> BasicLeafGadget<F, H, HG> {
+	/// Same as `create_leaf`, without decomposing the field elements into
+	/// bytes
+	pub fn create_leaf_fields(
+		private: &PrivateVar<F>,
+		h: &HG::ParametersVar,
+	) -> Result<FpVar<F>, SynthesisError> {
+		HG::hash_two(h, &private.r, &private.nullifier)
+	}
+
+	/// Same as `create_nullifier`, without decomposing the field elements
+	/// into bytes
+	pub fn create_nullifier_fields(
+		private: &PrivateVar<F>,
+		h: &HG::ParametersVar,
+	) -> Result<FpVar<F>, SynthesisError> {
+		HG::hash_two(h, &private.nullifier, &private.nullifier)
+	}
+}
+
 impl<F: PrimeField> AllocVar<Private<F>, F> for PrivateVar<F> {
 	fn new_variable<T: Borrow<Private<F>>>(
 		into_ns: impl Into<Namespace<F>>,
@@ -116,4 +139,37 @@ mod test {
 		assert!(res.value().unwrap());
 		assert!(res.cs().is_satisfied().unwrap());
 	}
+
+	#[test]
+	fn should_create_basic_leaf_from_fields_with_fewer_constraints() {
+		let rng = &mut test_rng();
+		let curve = arkworks_utils::utils::common::Curve::Bls381;
+		let secrets = Private::generate(rng);
+		let params = setup_params_x5_3(curve);
+		let leaf = Leaf::create_leaf(&secrets, &params).unwrap();
+		let nullifier = Leaf::create_nullifier(&secrets, &params).unwrap();
+
+		let synthesize = |from_fields: bool| {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let secrets_var = PrivateVar::new_witness(cs.clone(), || Ok(&secrets)).unwrap();
+			let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+			let hashes = if from_fields {
+				(
+					LeafGadget::create_leaf_fields(&secrets_var, &params_var).unwrap(),
+					LeafGadget::create_nullifier_fields(&secrets_var, &params_var).unwrap(),
+				)
+			} else {
+				(
+					LeafGadget::create_leaf(&secrets_var, &params_var).unwrap(),
+					LeafGadget::create_nullifier(&secrets_var, &params_var).unwrap(),
+				)
+			};
+			assert_eq!(hashes.0.value().unwrap(), leaf);
+			assert_eq!(hashes.1.value().unwrap(), nullifier);
+			assert!(cs.is_satisfied().unwrap());
+			cs.num_constraints()
+		};
+
+		assert!(synthesize(true) < synthesize(false));
+	}
 }
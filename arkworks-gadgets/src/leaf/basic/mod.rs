@@ -1,3 +1,4 @@
+use crate::field_hasher::FieldHasher;
 use ark_crypto_primitives::{crh::CRH, Error};
 use ark_ff::{fields::PrimeField, to_bytes};
 use ark_std::{marker::PhantomData, rand::Rng};
@@ -37,6 +38,18 @@ impl<F: PrimeField, H: CRH> BasicLeaf<F, H> {
 	}
 }
 
+impl<F: PrimeField, H: FieldHasher<F>> BasicLeaf<F, H> {
+	/// Same as `create_leaf`, without serializing the field elements
+	pub fn create_leaf_fields(private: &Private<F>, h: &H::Parameters) -> Result<F, Error> {
+		H::hash(h, &[private.r, private.nullifier])
+	}
+
+	/// Same as `create_nullifier`, without serializing the field elements
+	pub fn create_nullifier_fields(private: &Private<F>, h: &H::Parameters) -> Result<F, Error> {
+		H::hash_two(h, &private.nullifier, &private.nullifier)
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
@@ -64,5 +77,10 @@ mod test {
 
 		let leaf = Leaf::create_leaf(&secrets, &params).unwrap();
 		assert_eq!(ev_res, leaf);
+		assert_eq!(Leaf::create_leaf_fields(&secrets, &params).unwrap(), leaf);
+		assert_eq!(
+			Leaf::create_nullifier_fields(&secrets, &params).unwrap(),
+			Leaf::create_nullifier(&secrets, &params).unwrap()
+		);
 	}
 }
@@ -1,5 +1,8 @@
 use super::{Private, Public};
-use crate::Vec;
+use crate::{
+	field_hasher::{constraints::FieldHasherGadget, FieldHasher},
+	Vec,
+};
 use ark_crypto_primitives::{crh::CRHGadget, CRH};
 use ark_ff::fields::PrimeField;
 use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
@@ -61,6 +64,32 @@ impl<F: PrimeField, H: CRH, HG: CRHGadget<H, F>> BridgeLeafGadget<F, H, HG> {
 	}
 }
 
+impl<F: PrimeField, H: FieldHasher<F>, HG: FieldHasherGadget<H, F>> BridgeLeafGadget<F, H, HG> {
+	/// Same as `create_leaf`, without decomposing the field elements into
+	/// bytes
+	pub fn create_leaf_fields(
+		private: &PrivateVar<F>,
+		public: &PublicVar<F>,
+		h: &HG::ParametersVar,
+	) -> Result<FpVar<F>, SynthesisError> {
+		let inputs = [
+			private.r.clone(),
+			private.nullifier.clone(),
+			public.chain_id.clone(),
+		];
+		HG::hash(h, &inputs)
+	}
+
+	/// Same as `create_nullifier`, without decomposing the field elements
+	/// into bytes
+	pub fn create_nullifier_fields(
+		private: &PrivateVar<F>,
+		h: &HG::ParametersVar,
+	) -> Result<FpVar<F>, SynthesisError> {
+		HG::hash_two(h, &private.nullifier, &private.nullifier)
+	}
+}
+
 impl<F: PrimeField> AllocVar<Private<F>, F> for PrivateVar<F> {
 	fn new_variable<T: Borrow<Private<F>>>(
 		into_ns: impl Into<Namespace<F>>,
@@ -155,4 +184,39 @@ mod test {
 		assert!(nullifier_res.value().unwrap());
 		assert!(nullifier_res.cs().is_satisfied().unwrap());
 	}
+
+	#[test]
+	fn should_create_bridge_leaf_from_fields_with_fewer_constraints() {
+		let rng = &mut test_rng();
+		let curve = arkworks_utils::utils::common::Curve::Bls381;
+		let params = setup_params_x5_5(curve);
+		let public = Public::new(Fq::one());
+		let private = Private::generate(rng);
+		let leaf_hash = Leaf::create_leaf(&private, &public, &params).unwrap();
+		let nullifier = Leaf::create_nullifier(&private, &params).unwrap();
+
+		let synthesize = |from_fields: bool| {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+			let public_var = PublicVar::new_input(cs.clone(), || Ok(&public)).unwrap();
+			let private_var = PrivateVar::new_witness(cs.clone(), || Ok(&private)).unwrap();
+			let hashes = if from_fields {
+				(
+					LeafGadget::create_leaf_fields(&private_var, &public_var, &params_var).unwrap(),
+					LeafGadget::create_nullifier_fields(&private_var, &params_var).unwrap(),
+				)
+			} else {
+				(
+					LeafGadget::create_leaf(&private_var, &public_var, &params_var).unwrap(),
+					LeafGadget::create_nullifier(&private_var, &params_var).unwrap(),
+				)
+			};
+			assert_eq!(hashes.0.value().unwrap(), leaf_hash);
+			assert_eq!(hashes.1.value().unwrap(), nullifier);
+			assert!(cs.is_satisfied().unwrap());
+			cs.num_constraints()
+		};
+
+		assert!(synthesize(true) < synthesize(false));
+	}
 }
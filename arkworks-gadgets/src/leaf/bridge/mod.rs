@@ -1,3 +1,4 @@
+use crate::field_hasher::FieldHasher;
 use ark_crypto_primitives::{crh::CRH, Error};
 use ark_ff::{fields::PrimeField, to_bytes};
 use ark_std::{marker::PhantomData, rand::Rng};
@@ -57,6 +58,22 @@ impl<F: PrimeField, H: CRH> BridgeLeaf<F, H> {
 	}
 }
 
+impl<F: PrimeField, H: FieldHasher<F>> BridgeLeaf<F, H> {
+	/// Same as `create_leaf`, without serializing the field elements
+	pub fn create_leaf_fields(
+		private: &Private<F>,
+		public: &Public<F>,
+		h: &H::Parameters,
+	) -> Result<F, Error> {
+		H::hash(h, &[private.secret, private.nullifier, public.chain_id])
+	}
+
+	/// Same as `create_nullifier`, without serializing the field elements
+	pub fn create_nullifier_fields(private: &Private<F>, h: &H::Parameters) -> Result<F, Error> {
+		H::hash_two(h, &private.nullifier, &private.nullifier)
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
@@ -93,5 +110,10 @@ mod test {
 		let nullifier_hash = Leaf::create_nullifier(&private, &params).unwrap();
 		assert_eq!(leaf_res, leaf_hash);
 		assert_eq!(nullifier_res, nullifier_hash);
+
+		let leaf_hash = Leaf::create_leaf_fields(&private, &public, &params).unwrap();
+		let nullifier_hash = Leaf::create_nullifier_fields(&private, &params).unwrap();
+		assert_eq!(leaf_res, leaf_hash);
+		assert_eq!(nullifier_res, nullifier_hash);
 	}
 }
@@ -1,5 +1,8 @@
 use super::Private;
-use crate::Vec;
+use crate::{
+	field_hasher::{constraints::FieldHasherGadget, FieldHasher},
+	Vec,
+};
 use ark_crypto_primitives::{crh::CRHGadget, CRH};
 use ark_ff::fields::PrimeField;
 use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
@@ -48,6 +51,26 @@ impl<F: PrimeField, H: CRH, HG: CRHGadget<H, F>> MixerLeafGadget<F, H, HG> {
 	}
 }
 
+impl<F: PrimeField, H: FieldHasher<F>, HG: FieldHasherGadget<H, F>> MixerLeafGadget<F, H, HG> {
+	/// Same as `create_leaf`, without decomposing the field elements into
+	/// bytes
+	pub fn create_leaf_fields(
+		private: &PrivateVar<F>,
+		h: &HG::ParametersVar,
+	) -> Result<FpVar<F>, SynthesisError> {
+		HG::hash_two(h, &private.secret, &private.nullifier)
+	}
+
+	/// Same as `create_nullifier`, without decomposing the field elements
+	/// into bytes
+	pub fn create_nullifier_fields(
+		private: &PrivateVar<F>,
+		h: &HG::ParametersVar,
+	) -> Result<FpVar<F>, SynthesisError> {
+		HG::hash_two(h, &private.nullifier, &private.nullifier)
+	}
+}
+
 impl<F: PrimeField> AllocVar<Private<F>, F> for PrivateVar<F> {
 	fn new_variable<T: Borrow<Private<F>>>(
 		into_ns: impl Into<Namespace<F>>,
@@ -125,4 +148,36 @@ mod test {
 		assert!(nullifier_res.value().unwrap());
 		assert!(nullifier_res.cs().is_satisfied().unwrap());
 	}
+
+	#[test]
+	fn should_create_mixer_leaf_from_fields_with_fewer_constraints() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_5(Curve::Bls381);
+		let private = Private::generate(rng);
+		let leaf_hash = Leaf::create_leaf(&private, &params).unwrap();
+		let nullifier_hash = Leaf::create_nullifier(&private, &params).unwrap();
+
+		let synthesize = |from_fields: bool| {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let params_var = PoseidonParametersVar::new_constant(cs.clone(), &params).unwrap();
+			let private_var = PrivateVar::new_witness(cs.clone(), || Ok(&private)).unwrap();
+			let hashes = if from_fields {
+				(
+					LeafGadget::create_leaf_fields(&private_var, &params_var).unwrap(),
+					LeafGadget::create_nullifier_fields(&private_var, &params_var).unwrap(),
+				)
+			} else {
+				(
+					LeafGadget::create_leaf(&private_var, &params_var).unwrap(),
+					LeafGadget::create_nullifier(&private_var, &params_var).unwrap(),
+				)
+			};
+			assert_eq!(hashes.0.value().unwrap(), leaf_hash);
+			assert_eq!(hashes.1.value().unwrap(), nullifier_hash);
+			assert!(cs.is_satisfied().unwrap());
+			cs.num_constraints()
+		};
+
+		assert!(synthesize(true) < synthesize(false));
+	}
 }
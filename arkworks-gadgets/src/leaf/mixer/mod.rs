@@ -1,3 +1,4 @@
+use crate::field_hasher::FieldHasher;
 use ark_crypto_primitives::{crh::CRH, Error};
 use ark_ff::{fields::PrimeField, to_bytes};
 use ark_std::{marker::PhantomData, rand::Rng};
@@ -50,6 +51,18 @@ impl<F: PrimeField, H: CRH> MixerLeaf<F, H> {
 	}
 }
 
+impl<F: PrimeField, H: FieldHasher<F>> MixerLeaf<F, H> {
+	/// Same as `create_leaf`, without serializing the field elements
+	pub fn create_leaf_fields(private: &Private<F>, h: &H::Parameters) -> Result<F, Error> {
+		H::hash_two(h, &private.secret, &private.nullifier)
+	}
+
+	/// Same as `create_nullifier`, without serializing the field elements
+	pub fn create_nullifier_fields(private: &Private<F>, h: &H::Parameters) -> Result<F, Error> {
+		H::hash_two(h, &private.nullifier, &private.nullifier)
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use ark_bls12_381::Fq;
@@ -83,5 +96,10 @@ mod test {
 		let nullifier_hash = Leaf::create_nullifier(&private, &params).unwrap();
 		assert_eq!(leaf_res, leaf_hash);
 		assert_eq!(nullifier_res, nullifier_hash);
+
+		let leaf_hash = Leaf::create_leaf_fields(&private, &params).unwrap();
+		let nullifier_hash = Leaf::create_nullifier_fields(&private, &params).unwrap();
+		assert_eq!(leaf_res, leaf_hash);
+		assert_eq!(nullifier_res, nullifier_hash);
 	}
 }
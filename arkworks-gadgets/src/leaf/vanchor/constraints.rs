@@ -1,5 +1,8 @@
 use super::{Private, Public};
-use crate::Vec;
+use crate::{
+	field_hasher::{constraints::FieldHasherGadget, FieldHasher},
+	Vec,
+};
 use ark_crypto_primitives::{crh::CRHGadget, CRH};
 use ark_ff::fields::PrimeField;
 use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
@@ -75,6 +78,40 @@ impl<F: PrimeField, H: CRH, HG: CRHGadget<H, F>> VAnchorLeafGadget<F, H, HG> {
 	}
 }
 
+impl<F: PrimeField, H: FieldHasher<F>, HG: FieldHasherGadget<H, F>> VAnchorLeafGadget<F, H, HG> {
+	/// Same as `create_leaf`, without decomposing the field elements into
+	/// bytes
+	pub fn create_leaf_fields(
+		private: &PrivateVar<F>,
+		public: &PublicVar<F>,
+		public_key: &FpVar<F>,
+		h_w5: &HG::ParametersVar,
+	) -> Result<FpVar<F>, SynthesisError> {
+		let inputs = [
+			public.chain_id.clone(),
+			private.amount.clone(),
+			public_key.clone(),
+			private.blinding.clone(),
+		];
+		HG::hash(h_w5, &inputs)
+	}
+
+	/// Same as `create_nullifier`, without decomposing the field elements
+	/// into bytes
+	pub fn create_nullifier_fields(
+		signature: &FpVar<F>,
+		commitment: &FpVar<F>,
+		h_w4: &HG::ParametersVar,
+		index: &FpVar<F>,
+	) -> Result<FpVar<F>, SynthesisError> {
+		HG::hash(h_w4, &[
+			commitment.clone(),
+			index.clone(),
+			signature.clone(),
+		])
+	}
+}
+
 impl<F: PrimeField> AllocVar<Private<F>, F> for PrivateVar<F> {
 	fn new_variable<T: Borrow<Private<F>>>(
 		into_ns: impl Into<Namespace<F>>,
@@ -214,4 +251,73 @@ mod test {
 		assert!(res_nul.value().unwrap());
 		assert!(res_nul.cs().is_satisfied().unwrap());
 	}
+
+	#[test]
+	fn should_create_leaf_from_fields_with_fewer_constraints() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bn254;
+		let params5_4 = setup_params_x5_4(curve);
+		let params5_5 = setup_params_x5_5(curve);
+
+		let index = Fq::one();
+		let public = Public::new(Fq::one());
+		let secrets = Private::generate(rng);
+		let public_key = Fq::rand(rng);
+		let signature = Fq::rand(rng);
+		let leaf = Leaf::create_leaf(&secrets, &public, &public_key, &params5_5).unwrap();
+		let nullifier = Leaf::create_nullifier(&signature, &leaf, &params5_4, &index).unwrap();
+
+		let synthesize = |from_fields: bool| {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let index_var = FpVar::<Fq>::new_witness(cs.clone(), || Ok(index)).unwrap();
+			let public_var = PublicVar::new_input(cs.clone(), || Ok(&public)).unwrap();
+			let secrets_var = PrivateVar::new_witness(cs.clone(), || Ok(&secrets)).unwrap();
+			let public_key_var = FpVar::<Fq>::new_witness(cs.clone(), || Ok(public_key)).unwrap();
+			let signature_var = FpVar::<Fq>::new_witness(cs.clone(), || Ok(signature)).unwrap();
+			let params_var5_4 =
+				PoseidonParametersVar::new_constant(cs.clone(), &params5_4).unwrap();
+			let params_var5_5 =
+				PoseidonParametersVar::new_constant(cs.clone(), &params5_5).unwrap();
+
+			let (leaf_var, nullifier_var) = if from_fields {
+				let leaf_var = LeafGadget::create_leaf_fields(
+					&secrets_var,
+					&public_var,
+					&public_key_var,
+					&params_var5_5,
+				)
+				.unwrap();
+				let nullifier_var = LeafGadget::create_nullifier_fields(
+					&signature_var,
+					&leaf_var,
+					&params_var5_4,
+					&index_var,
+				)
+				.unwrap();
+				(leaf_var, nullifier_var)
+			} else {
+				let leaf_var = LeafGadget::create_leaf(
+					&secrets_var,
+					&public_var,
+					&public_key_var,
+					&params_var5_5,
+				)
+				.unwrap();
+				let nullifier_var = LeafGadget::create_nullifier(
+					&signature_var,
+					&leaf_var,
+					&params_var5_4,
+					&index_var,
+				)
+				.unwrap();
+				(leaf_var, nullifier_var)
+			};
+			assert_eq!(leaf_var.value().unwrap(), leaf);
+			assert_eq!(nullifier_var.value().unwrap(), nullifier);
+			assert!(cs.is_satisfied().unwrap());
+			cs.num_constraints()
+		};
+
+		assert!(synthesize(true) < synthesize(false));
+	}
 }
@@ -1,3 +1,4 @@
+use crate::field_hasher::FieldHasher;
 use ark_crypto_primitives::{crh::CRH, Error};
 use ark_ff::{fields::PrimeField, to_bytes, ToBytes};
 
@@ -70,6 +71,33 @@ impl<F: PrimeField, H: CRH> VAnchorLeaf<F, H> {
 	}
 }
 
+impl<F: PrimeField, H: FieldHasher<F>> VAnchorLeaf<F, H> {
+	/// Same as `create_leaf`, without serializing the field elements
+	pub fn create_leaf_fields(
+		private: &Private<F>,
+		public: &Public<F>,
+		public_key: &F,
+		h_w5: &H::Parameters,
+	) -> Result<F, Error> {
+		H::hash(h_w5, &[
+			public.chain_id,
+			private.amount,
+			*public_key,
+			private.blinding,
+		])
+	}
+
+	/// Same as `create_nullifier`, without serializing the field elements
+	pub fn create_nullifier_fields(
+		signature: &F,
+		commitment: &F,
+		h_w4: &H::Parameters,
+		index: &F,
+	) -> Result<F, Error> {
+		H::hash(h_w4, &[*commitment, *index, *signature])
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
@@ -146,6 +174,12 @@ mod test {
 		let ev_res = PoseidonCRH::evaluate(&params4, &inputs_null).unwrap();
 		let nullifier = Leaf::create_nullifier(&signature, &commitment, &params4, &index).unwrap();
 		assert_eq!(ev_res, nullifier);
+
+		let leaf = Leaf::create_leaf_fields(&secrets, &publics, &pubkey, &params5).unwrap();
+		assert_eq!(leaf, commitment);
+		let nullifier =
+			Leaf::create_nullifier_fields(&signature, &commitment, &params4, &index).unwrap();
+		assert_eq!(ev_res, nullifier);
 	}
 
 	type LeafCircom = VAnchorLeaf<Fq, PoseidonCRH>;
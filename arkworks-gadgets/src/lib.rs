@@ -8,12 +8,14 @@ pub(crate) use ark_std::vec::Vec;
 
 pub mod arbitrary;
 
+pub mod field_hasher;
 pub mod identity;
 pub mod keypair;
 pub mod leaf;
 pub mod merkle_tree;
 pub mod mimc;
 pub mod poseidon;
+pub mod poseidon2;
 pub mod set;
 
 pub type Error = Box<dyn ark_std::error::Error>;
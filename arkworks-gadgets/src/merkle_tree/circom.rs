@@ -0,0 +1,380 @@
+use super::{
+	compact::CompactPath, Config, InnerNode, InnerParameters, LeafNode, LeafParameters,
+	MerkleError, Node, Path,
+};
+#[cfg(feature = "std")]
+use super::{storage::MerkleStorage, SparseMerkleTree};
+use ark_ff::{to_bytes, BigInteger, PrimeField, ToBytes};
+use ark_serialize::CanonicalDeserialize;
+#[cfg(feature = "std")]
+use ark_std::collections::BTreeMap;
+use ark_std::{rc::Rc, string::String, vec::Vec};
+use core::convert::TryInto;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+/// Merkle proof in the `pathElements` / `pathIndices` representation of
+/// circomlib's `fixed-merkle-tree`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CircomPath<F: PrimeField> {
+	/// sibling of the node on every level, from the leaves up
+	pub path_elements: Vec<F>,
+	/// 1 where the node on the level is a right child, 0 otherwise
+	pub path_indices: Vec<u8>,
+}
+
+impl<F: PrimeField> CircomPath<F> {
+	/// convert the `Path` of `leaf`
+	pub fn from_path<P: Config, L: ToBytes, const N: usize>(
+		path: &Path<P, N>,
+		leaf: &L,
+	) -> Result<Self, MerkleError> {
+		let compact = CompactPath::from_path(path, leaf)?;
+		let path_elements = compact
+			.siblings
+			.iter()
+			.map(node_to_field::<P, F>)
+			.collect::<Result<Vec<F>, MerkleError>>()?;
+		let path_indices = (0..N)
+			.map(|level| ((compact.index >> level) & 1) as u8)
+			.collect();
+
+		Ok(CircomPath {
+			path_elements,
+			path_indices,
+		})
+	}
+
+	/// convert into the `Path` of `leaf`
+	pub fn to_path<P: Config, L: ToBytes, const N: usize>(
+		&self,
+		leaf: &L,
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+	) -> Result<Path<P, N>, MerkleError> {
+		if self.path_indices.len() != N {
+			return Err(MerkleError::InvalidPathLength {
+				expected: N,
+				found: self.path_indices.len(),
+			});
+		}
+
+		let mut index = 0u64;
+		for (level, bit) in self.path_indices.iter().enumerate() {
+			match bit {
+				0 => {}
+				1 => index |= 1u64 << level,
+				_ => return Err(MerkleError::InvalidEncoding),
+			}
+		}
+		let siblings = self
+			.path_elements
+			.iter()
+			.enumerate()
+			.map(|(level, element)| field_to_node::<P, F>(level, element))
+			.collect::<Result<Vec<Node<P>>, MerkleError>>()?;
+
+		let compact = CompactPath {
+			siblings: siblings.try_into().map_err(|v: Vec<Node<P>>| {
+				MerkleError::InvalidPathLength {
+					expected: N,
+					found: v.len(),
+				}
+			})?,
+			index,
+			leaf_params,
+			inner_params,
+		};
+		compact.to_path(leaf)
+	}
+
+	/// encode as the JSON object returned by `fixed-merkle-tree`'s `path()`
+	#[cfg(feature = "std")]
+	pub fn to_json(&self) -> Result<String, MerkleError> {
+		let path = PathJson {
+			path_elements: self.path_elements.iter().map(field_to_hex).collect(),
+			path_indices: self.path_indices.clone(),
+		};
+		serde_json::to_string(&path).map_err(|_| MerkleError::InvalidEncoding)
+	}
+
+	/// decode the JSON object returned by `fixed-merkle-tree`'s `path()`
+	#[cfg(feature = "std")]
+	pub fn from_json(json: &str) -> Result<Self, MerkleError> {
+		let path: PathJson =
+			serde_json::from_str(json).map_err(|_| MerkleError::InvalidEncoding)?;
+		let path_elements = path
+			.path_elements
+			.iter()
+			.map(|element| parse_field(element))
+			.collect::<Result<Vec<F>, MerkleError>>()?;
+
+		Ok(CircomPath {
+			path_elements,
+			path_indices: path.path_indices,
+		})
+	}
+}
+
+#[cfg(feature = "std")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PathJson {
+	path_elements: Vec<String>,
+	path_indices: Vec<u8>,
+}
+
+/// Tree as passed to the `fixed-merkle-tree` constructor. Its leaves are not
+/// hashed, so `elements` hold the leaf hashes of a `SparseMerkleTree`.
+#[cfg(feature = "std")]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TreeJson {
+	levels: u8,
+	zero_element: String,
+	elements: Vec<String>,
+}
+
+#[cfg(feature = "std")]
+impl<P: Config> SparseMerkleTree<P> {
+	/// load a tree exported from `fixed-merkle-tree` as
+	/// `{ levels, zeroElement, elements }`
+	pub fn from_circom_json<F: PrimeField>(
+		json: &str,
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+	) -> Result<Self, MerkleError> {
+		let tree_json: TreeJson =
+			serde_json::from_str(json).map_err(|_| MerkleError::InvalidEncoding)?;
+		if tree_json.levels != P::HEIGHT {
+			return Err(MerkleError::InvalidSnapshot);
+		}
+
+		let mut tree = Self::blank(inner_params, leaf_params)?;
+		let zero_element = parse_field::<F>(&tree_json.zero_element)?;
+		if zero_element != node_to_field::<P, F>(&tree.empty_hashes[0])? {
+			return Err(MerkleError::InvalidSnapshot);
+		}
+
+		let mut leaf_hashes = BTreeMap::new();
+		for (i, element) in tree_json.elements.iter().enumerate() {
+			let index: u32 = i
+				.try_into()
+				.map_err(|_| MerkleError::IndexOutOfRange(i as u64))?;
+			leaf_hashes.insert(index, field_to_node::<P, F>(0, &parse_field(element)?)?);
+		}
+		let batch = tree.compute_hashed_batch(leaf_hashes)?;
+		tree.storage.commit(batch)?;
+
+		Ok(tree)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<P: Config, S: MerkleStorage<P>> SparseMerkleTree<P, S> {
+	/// export the tree as `{ levels, zeroElement, elements }`, the leaves up to
+	/// the last non-empty one being the elements
+	pub fn to_circom_json<F: PrimeField>(&self) -> Result<String, MerkleError> {
+		let first_leaf = (1u64 << P::HEIGHT) - 1;
+		let leaves = self.storage.nodes()?.split_off(&first_leaf);
+		let zero_element = &self.empty_hashes[0];
+
+		let count = leaves
+			.keys()
+			.next_back()
+			.map_or(0, |last| last - first_leaf + 1);
+		let elements = (0..count)
+			.map(|i| {
+				let leaf = leaves.get(&(first_leaf + i)).unwrap_or(zero_element);
+				Ok(field_to_hex(&node_to_field::<P, F>(leaf)?))
+			})
+			.collect::<Result<Vec<String>, MerkleError>>()?;
+
+		let tree = TreeJson {
+			levels: P::HEIGHT,
+			zero_element: field_to_hex(&node_to_field::<P, F>(zero_element)?),
+			elements,
+		};
+		serde_json::to_string(&tree).map_err(|_| MerkleError::InvalidEncoding)
+	}
+}
+
+/// Returns `element` as a `0x`-prefixed big-endian hex string.
+pub(crate) fn field_to_hex<F: PrimeField>(element: &F) -> String {
+	let hex: String = element
+		.into_repr()
+		.to_bytes_be()
+		.iter()
+		.map(|b| format!("{:02x}", b))
+		.collect();
+	format!("0x{}", hex)
+}
+
+/// Parses a decimal or `0x`-prefixed hex string into a field element,
+/// rejecting values that are not below the modulus.
+fn parse_field<F: PrimeField>(element: &str) -> Result<F, MerkleError> {
+	let (digits, radix) = match element.strip_prefix("0x") {
+		Some(hex) => (hex, 16),
+		None => (element, 10),
+	};
+	if digits.is_empty() {
+		return Err(MerkleError::InvalidEncoding);
+	}
+
+	let mut repr = F::BigInt::from(0u64);
+	for c in digits.chars() {
+		let digit = c.to_digit(radix).ok_or(MerkleError::InvalidEncoding)?;
+		// repr = repr * radix + digit, limb by limb from the least significant
+		let mut carry = digit as u128;
+		for limb in repr.as_mut() {
+			let value = *limb as u128 * radix as u128 + carry;
+			*limb = value as u64;
+			carry = value >> 64;
+		}
+		if carry != 0 {
+			return Err(MerkleError::InvalidEncoding);
+		}
+	}
+
+	F::from_repr(repr).ok_or(MerkleError::InvalidEncoding)
+}
+
+fn node_to_field<P: Config, F: PrimeField>(node: &Node<P>) -> Result<F, MerkleError> {
+	Ok(F::from_le_bytes_mod_order(&to_bytes![node]?))
+}
+
+/// Returns the node holding `element` on `level` (0 for leaves).
+fn field_to_node<P: Config, F: PrimeField>(
+	level: usize,
+	element: &F,
+) -> Result<Node<P>, MerkleError> {
+	let bytes = to_bytes![element]?;
+	if level == 0 {
+		Ok(Node::Leaf(LeafNode::<P>::deserialize(&bytes[..])?))
+	} else {
+		Ok(Node::Inner(InnerNode::<P>::deserialize(&bytes[..])?))
+	}
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+	use super::CircomPath;
+	use crate::{
+		identity::CRH as IdentityCRH,
+		merkle_tree::{Config, SparseMerkleTree},
+		poseidon::CRH as PoseidonCRH,
+	};
+	use ark_bls12_381::Fq;
+	use ark_ff::UniformRand;
+	use ark_std::{rc::Rc, test_rng};
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	type SMTCRH = PoseidonCRH<Fq>;
+
+	/// leaves are not hashed, like in `fixed-merkle-tree`
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct CircomConfig;
+	impl Config for CircomConfig {
+		type H = SMTCRH;
+		type LeafH = IdentityCRH<Fq>;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	#[test]
+	fn should_convert_path_both_ways() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = Rc::new(());
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = SparseMerkleTree::<CircomConfig>::new_sequential(
+			inner_params.clone(),
+			leaf_params.clone(),
+			&leaves,
+		)
+		.unwrap();
+		let root = smt.root().unwrap();
+		let path = smt
+			.generate_membership_proof::<{ CircomConfig::HEIGHT as usize }>(1)
+			.unwrap();
+
+		let circom = CircomPath::<Fq>::from_path(&path, &leaves[1]).unwrap();
+		assert_eq!(circom.path_indices, vec![1, 0, 0]);
+		assert_eq!(circom.path_elements[0], leaves[0]);
+
+		let decoded = CircomPath::<Fq>::from_json(&circom.to_json().unwrap()).unwrap();
+		assert_eq!(decoded, circom);
+
+		let restored = decoded
+			.to_path::<CircomConfig, _, { CircomConfig::HEIGHT as usize }>(
+				&leaves[1],
+				inner_params,
+				leaf_params,
+			)
+			.unwrap();
+		assert!(restored.check_membership(&root, &leaves[1]).unwrap());
+	}
+
+	#[test]
+	fn should_load_tree_with_identical_root() {
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = Rc::new(());
+
+		// as exported by a JS wallet, with decimal elements
+		let json = r#"{"levels":3,"zeroElement":"0","elements":["1","2","0x03"]}"#;
+		let loaded = SparseMerkleTree::<CircomConfig>::from_circom_json::<Fq>(
+			json,
+			inner_params.clone(),
+			leaf_params.clone(),
+		)
+		.unwrap();
+
+		let leaves = vec![Fq::from(1u64), Fq::from(2u64), Fq::from(3u64)];
+		let smt = SparseMerkleTree::<CircomConfig>::new_sequential(
+			inner_params.clone(),
+			leaf_params.clone(),
+			&leaves,
+		)
+		.unwrap();
+		assert_eq!(loaded.root().unwrap(), smt.root().unwrap());
+
+		let exported = smt.to_circom_json::<Fq>().unwrap();
+		let reloaded = SparseMerkleTree::<CircomConfig>::from_circom_json::<Fq>(
+			&exported,
+			inner_params,
+			leaf_params,
+		)
+		.unwrap();
+		assert_eq!(reloaded.root().unwrap(), smt.root().unwrap());
+
+		// the BLS12-381 base field modulus is not a canonical element
+		let modulus = "0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab";
+		let modulus_decimal = "4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787";
+		let too_long = format!("{}00", modulus);
+		for element in [modulus, modulus_decimal, too_long.as_str(), "1a"] {
+			let json = format!(
+				r#"{{"levels":3,"zeroElement":"0","elements":["{}"]}}"#,
+				element
+			);
+			assert!(SparseMerkleTree::<CircomConfig>::from_circom_json::<Fq>(
+				&json,
+				Rc::new(setup_params_x5_3(curve)),
+				Rc::new(()),
+			)
+			.is_err());
+		}
+
+		let wrong_levels = r#"{"levels":4,"zeroElement":"0","elements":[]}"#;
+		assert!(SparseMerkleTree::<CircomConfig>::from_circom_json::<Fq>(
+			wrong_levels,
+			Rc::new(setup_params_x5_3(curve)),
+			Rc::new(()),
+		)
+		.is_err());
+	}
+}
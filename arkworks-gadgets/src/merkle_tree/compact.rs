@@ -0,0 +1,214 @@
+use super::{
+	hash_inner_node, hash_leaf, storage::MerkleStorage, Config, InnerParameters, LeafParameters,
+	MerkleError, Node, Path, SparseMerkleTree,
+};
+use ark_ff::{PrimeField, ToBytes};
+use ark_std::{borrow::Borrow, rc::Rc, vec::Vec};
+use core::convert::TryInto;
+
+/// Membership proof holding one sibling per level and the leaf index.
+///
+/// Bit `i` of `index` tells whether the node on level `i` of the path is a
+/// right child, so verifying needs no comparison between the computed hash
+/// and the stored nodes.
+#[derive(Clone)]
+pub struct CompactPath<P: Config, const N: usize> {
+	/// sibling of the node on every level, from the leaves up
+	pub(crate) siblings: [Node<P>; N],
+	pub(crate) index: u64,
+	pub(crate) leaf_params: Rc<LeafParameters<P>>,
+	pub(crate) inner_params: Rc<InnerParameters<P>>,
+}
+
+impl<P: Config, const N: usize> CompactPath<P, N> {
+	/// compact a `Path` of `leaf`
+	pub fn from_path<L: ToBytes>(path: &Path<P, N>, leaf: &L) -> Result<Self, MerkleError> {
+		let mut prev = hash_leaf::<P, L>(path.leaf_params.borrow(), leaf)?;
+		if prev != path.path[0].0 && prev != path.path[0].1 {
+			return Err(MerkleError::LeafNotOnPath);
+		}
+
+		let mut siblings = Vec::with_capacity(N);
+		let mut index = 0u64;
+		for (level, (left_hash, right_hash)) in path.path.iter().enumerate() {
+			if &prev == left_hash {
+				siblings.push(right_hash.clone());
+			} else if &prev == right_hash {
+				index |= 1u64 << level;
+				siblings.push(left_hash.clone());
+			} else {
+				return Err(MerkleError::InconsistentPath);
+			}
+			prev = hash_inner_node::<P>(path.inner_params.borrow(), left_hash, right_hash)?;
+		}
+
+		Ok(CompactPath {
+			siblings: siblings.try_into().map_err(|v: Vec<Node<P>>| {
+				MerkleError::InvalidPathLength {
+					expected: N,
+					found: v.len(),
+				}
+			})?,
+			index,
+			leaf_params: Rc::clone(&path.leaf_params),
+			inner_params: Rc::clone(&path.inner_params),
+		})
+	}
+
+	/// expand into a `Path` of `leaf`
+	pub fn to_path<L: ToBytes>(&self, leaf: &L) -> Result<Path<P, N>, MerkleError> {
+		let mut prev = hash_leaf::<P, L>(self.leaf_params.borrow(), leaf)?;
+
+		let mut path = Vec::with_capacity(N);
+		for (level, sibling) in self.siblings.iter().enumerate() {
+			let (left_hash, right_hash) = if self.is_right(level) {
+				(sibling.clone(), prev)
+			} else {
+				(prev, sibling.clone())
+			};
+			prev = hash_inner_node::<P>(self.inner_params.borrow(), &left_hash, &right_hash)?;
+			path.push((left_hash, right_hash));
+		}
+
+		Ok(Path {
+			path: path.try_into().map_err(|v: Vec<(Node<P>, Node<P>)>| {
+				MerkleError::InvalidPathLength {
+					expected: N,
+					found: v.len(),
+				}
+			})?,
+			leaf_params: Rc::clone(&self.leaf_params),
+			inner_params: Rc::clone(&self.inner_params),
+		})
+	}
+
+	/// verify the lookup proof, just checking the membership
+	pub fn check_membership<L: ToBytes>(
+		&self,
+		root_hash: &Node<P>,
+		leaf: &L,
+	) -> Result<bool, MerkleError> {
+		let prev = self.root_hash(leaf)?;
+		Ok(root_hash == &prev)
+	}
+
+	/// index of the leaf, as a field element
+	pub fn get_index<F: PrimeField>(&self) -> F {
+		F::from(self.index)
+	}
+
+	/// Return hash of root computed by the path
+	pub fn root_hash<L: ToBytes>(&self, leaf: &L) -> Result<Node<P>, MerkleError> {
+		if N != P::HEIGHT as usize {
+			return Err(MerkleError::InvalidPathLength {
+				expected: P::HEIGHT as usize,
+				found: N,
+			});
+		}
+
+		let mut prev = hash_leaf::<P, L>(self.leaf_params.borrow(), leaf)?;
+		for (level, sibling) in self.siblings.iter().enumerate() {
+			prev = if self.is_right(level) {
+				hash_inner_node::<P>(self.inner_params.borrow(), sibling, &prev)?
+			} else {
+				hash_inner_node::<P>(self.inner_params.borrow(), &prev, sibling)?
+			};
+		}
+
+		Ok(prev)
+	}
+
+	#[inline]
+	fn is_right(&self, level: usize) -> bool {
+		(self.index >> level) & 1 == 1
+	}
+}
+
+impl<P: Config, S: MerkleStorage<P>> SparseMerkleTree<P, S> {
+	/// generate a compact proof of the position `index`, which may be empty
+	pub fn generate_compact_membership_proof<const N: usize>(
+		&self,
+		index: u64,
+	) -> Result<CompactPath<P, N>, MerkleError> {
+		let path = self.build_path::<_, N>(index, |i| self.storage.get(i))?;
+		let siblings: Vec<Node<P>> = path
+			.path
+			.iter()
+			.enumerate()
+			.map(|(level, (left_hash, right_hash))| {
+				if (index >> level) & 1 == 1 {
+					left_hash.clone()
+				} else {
+					right_hash.clone()
+				}
+			})
+			.collect();
+
+		Ok(CompactPath {
+			siblings: siblings.try_into().map_err(|v: Vec<Node<P>>| {
+				MerkleError::InvalidPathLength {
+					expected: N,
+					found: v.len(),
+				}
+			})?,
+			index,
+			leaf_params: Rc::clone(&self.leaf_params),
+			inner_params: Rc::clone(&self.inner_params),
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::CompactPath;
+	use crate::{
+		merkle_tree::{Config, SparseMerkleTree},
+		poseidon::CRH as PoseidonCRH,
+	};
+	use ark_bls12_381::Fq;
+	use ark_ff::UniformRand;
+	use ark_std::{rc::Rc, test_rng};
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	type SMTCRH = PoseidonCRH<Fq>;
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct SMTConfig;
+	impl Config for SMTConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	#[test]
+	fn should_convert_from_and_to_path() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = SparseMerkleTree::<SMTConfig>::new_sequential(inner_params, leaf_params, &leaves)
+			.unwrap();
+		let root = smt.root().unwrap();
+		let path = smt
+			.generate_membership_proof::<{ SMTConfig::HEIGHT as usize }>(2)
+			.unwrap();
+
+		let compact = CompactPath::from_path(&path, &leaves[2]).unwrap();
+		assert_eq!(compact.get_index::<Fq>(), Fq::from(2u64));
+		assert!(compact.check_membership(&root, &leaves[2]).unwrap());
+		assert!(!compact.check_membership(&root, &leaves[1]).unwrap());
+
+		let expanded = compact.to_path(&leaves[2]).unwrap();
+		assert!(expanded.path == path.path);
+
+		let generated = smt
+			.generate_compact_membership_proof::<{ SMTConfig::HEIGHT as usize }>(2)
+			.unwrap();
+		assert!(generated.siblings == compact.siblings);
+		assert_eq!(generated.index, compact.index);
+	}
+}
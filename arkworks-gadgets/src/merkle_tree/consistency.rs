@@ -0,0 +1,358 @@
+use super::{
+	gen_empty_hashes, hash_inner_node, left_child, right_child, storage::MerkleStorage, Config,
+	InnerParameters, LeafParameters, MerkleError, Node, Path, SparseMerkleTree,
+};
+use ark_ff::PrimeField;
+use ark_std::{borrow::Borrow, rc::Rc, vec::Vec};
+use core::convert::TryInto;
+
+/// Proof that a tree filled with `new_size` leaves is an append-only extension
+/// of the same tree when it held its first `old_size` leaves.
+///
+/// The left siblings on the path of the last old leaf are shared by both
+/// trees, while its right siblings are empty in the old tree and taken from
+/// the proof in the new one. The path of the last new leaf shows that nothing
+/// was written past it, so the proof binds both sizes. The empty subtree
+/// hashes are derived from the parameters, never taken from the prover.
+#[derive(Clone)]
+pub struct ConsistencyProof<P: Config, const N: usize> {
+	pub(crate) old_size: u64,
+	pub(crate) new_size: u64,
+	/// hash of the last leaf of the old tree
+	pub(crate) old_leaf: Node<P>,
+	/// siblings of the last old leaf in the new tree, from the leaves up
+	pub(crate) old_siblings: [Node<P>; N],
+	/// hash of the last leaf of the new tree
+	pub(crate) new_leaf: Node<P>,
+	/// siblings of the last new leaf in the new tree, from the leaves up
+	pub(crate) new_siblings: [Node<P>; N],
+	pub(crate) leaf_params: Rc<LeafParameters<P>>,
+	pub(crate) inner_params: Rc<InnerParameters<P>>,
+}
+
+impl<P: Config, const N: usize> ConsistencyProof<P, N> {
+	/// number of leaves of the old tree
+	pub fn old_size(&self) -> u64 {
+		self.old_size
+	}
+
+	/// number of leaves of the new tree
+	pub fn new_size(&self) -> u64 {
+		self.new_size
+	}
+
+	/// sizes of the trees, as field elements
+	pub fn get_sizes<F: PrimeField>(&self) -> (F, F) {
+		(F::from(self.old_size), F::from(self.new_size))
+	}
+
+	/// verify that `new_root` only appends leaves to the tree of `old_root`
+	pub fn check_consistency(
+		&self,
+		old_root: &Node<P>,
+		new_root: &Node<P>,
+	) -> Result<bool, MerkleError> {
+		let (old, extended, new) = self.root_hashes()?;
+		Ok(&old == old_root && &extended == new_root && &new == new_root)
+	}
+
+	/// Return the root of the old tree, the root of the old tree extended with
+	/// the appended subtrees and the root of the new tree
+	pub fn root_hashes(&self) -> Result<(Node<P>, Node<P>, Node<P>), MerkleError> {
+		if N != P::HEIGHT as usize {
+			return Err(MerkleError::InvalidPathLength {
+				expected: P::HEIGHT as usize,
+				found: N,
+			});
+		}
+		check_sizes::<P>(self.old_size, self.new_size)?;
+
+		let inner_params = self.inner_params.borrow();
+		let empty_hashes = gen_empty_hashes::<P>(self.leaf_params.borrow(), inner_params)?;
+		let old_index = self.old_size - 1;
+		let mut old_root = self.old_leaf.clone();
+		let mut extended_root = self.old_leaf.clone();
+		for (level, (sibling, empty_hash)) in
+			self.old_siblings.iter().zip(&empty_hashes).enumerate()
+		{
+			if (old_index >> level) & 1 == 1 {
+				old_root = hash_inner_node::<P>(inner_params, sibling, &old_root)?;
+				extended_root = hash_inner_node::<P>(inner_params, sibling, &extended_root)?;
+			} else {
+				old_root = hash_inner_node::<P>(inner_params, &old_root, empty_hash)?;
+				extended_root = hash_inner_node::<P>(inner_params, &extended_root, sibling)?;
+			}
+		}
+
+		let new_index = self.new_size - 1;
+		let mut new_root = self.new_leaf.clone();
+		for (level, (sibling, empty_hash)) in
+			self.new_siblings.iter().zip(&empty_hashes).enumerate()
+		{
+			new_root = if (new_index >> level) & 1 == 1 {
+				hash_inner_node::<P>(inner_params, sibling, &new_root)?
+			} else {
+				hash_inner_node::<P>(inner_params, &new_root, empty_hash)?
+			};
+		}
+
+		Ok((old_root, extended_root, new_root))
+	}
+}
+
+impl<P: Config, S: MerkleStorage<P>> SparseMerkleTree<P, S> {
+	/// generate a proof that the tree holding its first `new_size` leaves
+	/// extends the tree holding its first `old_size` leaves
+	///
+	/// Leaves past `new_size` are ignored, so the tree may already hold more.
+	pub fn generate_consistency_proof<const N: usize>(
+		&self,
+		old_size: u64,
+		new_size: u64,
+	) -> Result<ConsistencyProof<P, N>, MerkleError> {
+		if self.storage.is_empty() {
+			return Err(MerkleError::EmptyTree);
+		}
+		check_sizes::<P>(old_size, new_size)?;
+
+		let node = |i: u64| self.node_up_to(new_size, i);
+		let old_path = self.build_path::<_, N>(old_size - 1, node)?;
+		let (old_leaf, old_siblings) = split_path(&old_path, old_size - 1)?;
+		let new_path = self.build_path::<_, N>(new_size - 1, node)?;
+		let (new_leaf, new_siblings) = split_path(&new_path, new_size - 1)?;
+
+		Ok(ConsistencyProof {
+			old_size,
+			new_size,
+			old_leaf,
+			old_siblings,
+			new_leaf,
+			new_siblings,
+			leaf_params: Rc::clone(&self.leaf_params),
+			inner_params: Rc::clone(&self.inner_params),
+		})
+	}
+
+	/// value of the node at `index` in the tree holding only its first `size`
+	/// leaves: empty past them, and hashed again from its children if it
+	/// covers leaf `size`
+	fn node_up_to(&self, size: u64, index: u64) -> Result<Option<Node<P>>, MerkleError> {
+		let depth = 63 - (index + 1).leading_zeros();
+		let level = P::HEIGHT as u32 - depth;
+		let first_leaf = (index + 1 - (1u64 << depth)) << level;
+		if first_leaf >= size {
+			return Ok(None);
+		}
+		if first_leaf + (1u64 << level) <= size {
+			return self.storage.get(index);
+		}
+
+		let empty_hash = &self.empty_hashes[level as usize - 1];
+		let left = self
+			.node_up_to(size, left_child(index))?
+			.unwrap_or_else(|| empty_hash.clone());
+		let right = self
+			.node_up_to(size, right_child(index))?
+			.unwrap_or_else(|| empty_hash.clone());
+
+		Ok(Some(hash_inner_node::<P>(
+			self.inner_params.borrow(),
+			&left,
+			&right,
+		)?))
+	}
+}
+
+/// Returns an error unless a non-empty tree of `old_size` leaves can grow into
+/// one of `new_size` leaves, or keep its size.
+pub(crate) fn check_sizes<P: Config>(old_size: u64, new_size: u64) -> Result<(), MerkleError> {
+	if old_size == 0 || old_size > new_size || new_size > (1u64 << P::HEIGHT) {
+		return Err(MerkleError::InvalidTreeSize { old_size, new_size });
+	}
+	Ok(())
+}
+
+/// Returns the leaf of the path of `index` and the sibling on every level.
+fn split_path<P: Config, const N: usize>(
+	path: &Path<P, N>,
+	index: u64,
+) -> Result<(Node<P>, [Node<P>; N]), MerkleError> {
+	let (left_leaf, right_leaf) = path.path.first().ok_or(MerkleError::InvalidPathLength {
+		expected: P::HEIGHT as usize,
+		found: N,
+	})?;
+	let leaf = if index & 1 == 1 {
+		right_leaf.clone()
+	} else {
+		left_leaf.clone()
+	};
+
+	let siblings: Vec<Node<P>> = path
+		.path
+		.iter()
+		.enumerate()
+		.map(|(level, (left_hash, right_hash))| {
+			if (index >> level) & 1 == 1 {
+				left_hash.clone()
+			} else {
+				right_hash.clone()
+			}
+		})
+		.collect();
+
+	Ok((
+		leaf,
+		siblings
+			.try_into()
+			.map_err(|v: Vec<Node<P>>| MerkleError::InvalidPathLength {
+				expected: N,
+				found: v.len(),
+			})?,
+	))
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		merkle_tree::{Config, MerkleError, SparseMerkleTree},
+		poseidon::CRH as PoseidonCRH,
+	};
+	use ark_bls12_381::Fq;
+	use ark_ff::UniformRand;
+	use ark_std::{collections::BTreeMap, rc::Rc, test_rng};
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	type SMTCRH = PoseidonCRH<Fq>;
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct SMTConfig;
+	impl Config for SMTConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	#[test]
+	fn should_prove_append_only_growth() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let leaves: Vec<Fq> = (0..5).map(|_| Fq::rand(rng)).collect();
+		let mut smt = SparseMerkleTree::<SMTConfig>::new_sequential(
+			inner_params.clone(),
+			leaf_params.clone(),
+			&leaves[..3],
+		)
+		.unwrap();
+		let old_root = smt.root().unwrap();
+
+		let appended: BTreeMap<u32, Fq> =
+			vec![(3, leaves[3]), (4, leaves[4])].into_iter().collect();
+		smt.insert_batch(&appended).unwrap();
+		let new_root = smt.root().unwrap();
+
+		let proof = smt
+			.generate_consistency_proof::<{ SMTConfig::HEIGHT as usize }>(3, 5)
+			.unwrap();
+		assert_eq!(proof.get_sizes::<Fq>(), (Fq::from(3u64), Fq::from(5u64)));
+		assert!(proof.check_consistency(&old_root, &new_root).unwrap());
+		assert!(!proof.check_consistency(&new_root, &old_root).unwrap());
+
+		// rewriting an old leaf breaks the proof against the old root
+		let mut rewritten = leaves.clone();
+		rewritten[1] = Fq::rand(rng);
+		let forked =
+			SparseMerkleTree::<SMTConfig>::new_sequential(inner_params, leaf_params, &rewritten)
+				.unwrap();
+		let forked_proof = forked
+			.generate_consistency_proof::<{ SMTConfig::HEIGHT as usize }>(3, 5)
+			.unwrap();
+		assert!(!forked_proof
+			.check_consistency(&old_root, &forked.root().unwrap())
+			.unwrap());
+
+		assert!(matches!(
+			smt.generate_consistency_proof::<{ SMTConfig::HEIGHT as usize }>(5, 3),
+			Err(MerkleError::InvalidTreeSize { .. })
+		));
+		assert!(matches!(
+			smt.generate_consistency_proof::<{ SMTConfig::HEIGHT as usize }>(0, 3),
+			Err(MerkleError::InvalidTreeSize { .. })
+		));
+	}
+
+	#[test]
+	fn should_prove_growth_to_an_earlier_size() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let leaves: Vec<Fq> = (0..7).map(|_| Fq::rand(rng)).collect();
+		let root_of = |size: usize| {
+			SparseMerkleTree::<SMTConfig>::new_sequential(
+				inner_params.clone(),
+				leaf_params.clone(),
+				&leaves[..size],
+			)
+			.unwrap()
+			.root()
+			.unwrap()
+		};
+		let smt = SparseMerkleTree::<SMTConfig>::new_sequential(
+			inner_params.clone(),
+			leaf_params.clone(),
+			&leaves,
+		)
+		.unwrap();
+
+		for (old_size, new_size) in [(1, 3), (2, 5), (3, 3), (5, 6)].iter() {
+			let proof = smt
+				.generate_consistency_proof::<{ SMTConfig::HEIGHT as usize }>(*old_size, *new_size)
+				.unwrap();
+			let old_root = root_of(*old_size as usize);
+			let new_root = root_of(*new_size as usize);
+			assert!(proof.check_consistency(&old_root, &new_root).unwrap());
+			assert!(!proof
+				.check_consistency(&old_root, &smt.root().unwrap())
+				.unwrap());
+		}
+	}
+
+	#[test]
+	fn should_reject_old_root_with_non_empty_padding() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let leaves: Vec<Fq> = (0..5).map(|_| Fq::rand(rng)).collect();
+		let smt = SparseMerkleTree::<SMTConfig>::new_sequential(
+			inner_params.clone(),
+			leaf_params.clone(),
+			&leaves,
+		)
+		.unwrap();
+		let proof = smt
+			.generate_consistency_proof::<{ SMTConfig::HEIGHT as usize }>(3, 5)
+			.unwrap();
+
+		// the root of the first 3 leaves with the empty leaf next to the last
+		// one replaced, i.e. a tree of 4 leaves whose 4th leaf is later rewritten
+		let padded = vec![leaves[0], leaves[1], leaves[2], Fq::rand(rng)];
+		let padded_root =
+			SparseMerkleTree::<SMTConfig>::new_sequential(inner_params, leaf_params, &padded)
+				.unwrap()
+				.root()
+				.unwrap();
+		assert!(!proof
+			.check_consistency(&padded_root, &smt.root().unwrap())
+			.unwrap());
+	}
+}
@@ -1,7 +1,20 @@
 use core::convert::TryInto;
 
-use super::{Config, Node, Path};
-use crate::Vec;
+use super::{
+	compact::CompactPath,
+	consistency::{check_sizes, ConsistencyProof},
+	convert_index_to_last_level, gen_empty_hashes, hash_empty, is_left_child,
+	keyed::KeyedPath,
+	mmr::MmrPath,
+	multi::MultiPath,
+	parent,
+	quad::{Path4, ARITY},
+	sibling, with_domain_fields, Config, MerkleError, Node, Path,
+};
+use crate::{
+	field_hasher::{constraints::FieldHasherGadget, FieldHasher},
+	Vec,
+};
 use ark_crypto_primitives::CRHGadget;
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
@@ -9,7 +22,7 @@ use ark_r1cs_std::{
 	ToBytesGadget,
 };
 use ark_relations::r1cs::{Namespace, SynthesisError};
-use ark_std::{borrow::Borrow, rc::Rc};
+use ark_std::{borrow::Borrow, cmp::Ordering, collections::BTreeMap, rc::Rc};
 
 #[derive(Debug)]
 pub enum NodeVar<F, P, HG, LHG>
@@ -143,20 +156,42 @@ where
 		&self,
 		root: &NodeVar<F, P, HG, LHG>,
 		leaf: L,
-	) -> Result<Boolean<F>, SynthesisError> {
+	) -> Result<Boolean<F>, MerkleError> {
 		let computed_root = self.root_hash(&leaf)?;
 
-		root.is_eq(&computed_root)
+		Ok(root.is_eq(&computed_root)?)
 	}
 
 	pub fn root_hash<L: ToBytesGadget<F>>(
 		&self,
 		leaf: &L,
-	) -> Result<NodeVar<F, P, HG, LHG>, SynthesisError> {
-		assert_eq!(self.path.len(), P::HEIGHT as usize);
+	) -> Result<NodeVar<F, P, HG, LHG>, MerkleError> {
 		// Check that the hash of the given leaf matches the leaf hash in the membership
 		// proof.
 		let leaf_hash = hash_leaf_gadget::<F, P, HG, LHG, L>(self.leaf_params.borrow(), leaf)?;
+		self.root_hash_from_leaf_hash(leaf_hash, |left, right| {
+			Ok(hash_inner_node_gadget::<F, P, HG, LHG>(
+				self.inner_params.borrow(),
+				left,
+				right,
+			)?)
+		})
+	}
+
+	fn root_hash_from_leaf_hash(
+		&self,
+		leaf_hash: NodeVar<F, P, HG, LHG>,
+		hash_inner_node: impl Fn(
+			&NodeVar<F, P, HG, LHG>,
+			&NodeVar<F, P, HG, LHG>,
+		) -> Result<NodeVar<F, P, HG, LHG>, MerkleError>,
+	) -> Result<NodeVar<F, P, HG, LHG>, MerkleError> {
+		if self.path.len() != P::HEIGHT as usize {
+			return Err(MerkleError::InvalidPathLength {
+				expected: P::HEIGHT as usize,
+				found: self.path.len(),
+			});
+		}
 
 		// Check if leaf is one of the bottom-most siblings.
 		let leaf_is_left = leaf_hash.is_eq(&self.path[0].0)?;
@@ -179,11 +214,7 @@ where
 				right_hash,
 			)?)?;
 
-			previous_hash = hash_inner_node_gadget::<F, P, HG, LHG>(
-				self.inner_params.borrow(),
-				left_hash,
-				right_hash,
-			)?;
+			previous_hash = hash_inner_node(left_hash, right_hash)?;
 		}
 
 		Ok(previous_hash)
@@ -193,10 +224,10 @@ where
 		&self,
 		root: &NodeVar<F, P, HG, LHG>,
 		leaf: L,
-	) -> Result<FpVar<F>, SynthesisError> {
+	) -> Result<FpVar<F>, MerkleError> {
 		// First, check if the provided leaf is on the path
-		let isonpath = self.check_membership(root, &leaf);
-		isonpath.unwrap().enforce_equal(&Boolean::TRUE)?;
+		let isonpath = self.check_membership(root, &leaf)?;
+		isonpath.enforce_equal(&Boolean::TRUE)?;
 
 		let mut index = FpVar::<F>::zero();
 		let mut twopower = FpVar::<F>::one();
@@ -226,49 +257,129 @@ where
 	}
 }
 
-pub(crate) fn hash_leaf_gadget<F, P, HG, LHG, L>(
-	leaf_params: &LHG::ParametersVar,
-	leaf: &L,
-) -> Result<NodeVar<F, P, HG, LHG>, SynthesisError>
+impl<F, P, HG, LHG, const N: usize> PathVar<F, P, HG, LHG, N>
+where
+	F: PrimeField,
+	P: Config,
+	P::H: FieldHasher<F>,
+	P::LeafH: FieldHasher<F>,
+	HG: FieldHasherGadget<P::H, F>,
+	LHG: FieldHasherGadget<P::LeafH, F>,
+{
+	/// Same as `check_membership`, hashing the leaf and the nodes as field
+	/// elements instead of bytes
+	pub fn check_membership_fields(
+		&self,
+		root: &NodeVar<F, P, HG, LHG>,
+		leaf: &FpVar<F>,
+	) -> Result<Boolean<F>, MerkleError> {
+		let computed_root = self.root_hash_fields(leaf)?;
+
+		Ok(root.is_eq(&computed_root)?)
+	}
+
+	/// Same as `root_hash`, hashing the leaf and the nodes as field elements
+	/// instead of bytes
+	pub fn root_hash_fields(&self, leaf: &FpVar<F>) -> Result<NodeVar<F, P, HG, LHG>, MerkleError> {
+		let leaf_hash = hash_leaf_fields_gadget::<F, P, HG, LHG>(self.leaf_params.borrow(), leaf)?;
+		self.root_hash_from_leaf_hash(leaf_hash, |left, right| {
+			hash_inner_node_fields_gadget::<F, P, HG, LHG>(self.inner_params.borrow(), left, right)
+		})
+	}
+}
+
+/// Gadgets for the path of one leaf of a `KeyedMerkleTree`, proving that a key
+/// is absent from (or present in) the committed set
+#[derive(Debug)]
+pub struct NonMembershipPathVar<F, P, HG, LHG, const N: usize>
 where
 	F: PrimeField,
 	P: Config,
-	L: ToBytesGadget<F>,
 	HG: CRHGadget<P::H, F>,
 	LHG: CRHGadget<P::LeafH, F>,
 {
-	Ok(NodeVar::Leaf(LHG::evaluate(
-		leaf_params,
-		&leaf.to_bytes()?,
-	)?))
+	siblings: [NodeVar<F, P, HG, LHG>; N],
+	empty_leaf: NodeVar<F, P, HG, LHG>,
+	inner_params: Rc<HG::ParametersVar>,
+	leaf_params: Rc<LHG::ParametersVar>,
 }
 
-pub(crate) fn hash_inner_node_gadget<F, P, HG, LHG>(
-	inner_params: &HG::ParametersVar,
-	left_child: &NodeVar<F, P, HG, LHG>,
-	right_child: &NodeVar<F, P, HG, LHG>,
-) -> Result<NodeVar<F, P, HG, LHG>, SynthesisError>
+impl<F, P, HG, LHG, const N: usize> NonMembershipPathVar<F, P, HG, LHG, N>
 where
 	F: PrimeField,
 	P: Config,
 	HG: CRHGadget<P::H, F>,
 	LHG: CRHGadget<P::LeafH, F>,
 {
-	let mut bytes = Vec::new();
-	bytes.extend(left_child.to_bytes()?);
-	bytes.extend(right_child.to_bytes()?);
-	let res = HG::evaluate(inner_params, &bytes)?;
-	Ok(NodeVar::Inner(res))
+	/// check that the leaf selected by `key` is empty
+	pub fn check_non_membership(
+		&self,
+		root: &NodeVar<F, P, HG, LHG>,
+		key: &FpVar<F>,
+	) -> Result<Boolean<F>, MerkleError> {
+		let computed_root = self.root_hash(key, &self.empty_leaf)?;
+
+		Ok(root.is_eq(&computed_root)?)
+	}
+
+	/// check that the leaf selected by `key` holds `key`
+	pub fn check_membership(
+		&self,
+		root: &NodeVar<F, P, HG, LHG>,
+		key: &FpVar<F>,
+	) -> Result<Boolean<F>, MerkleError> {
+		let leaf_hash = hash_leaf_gadget::<F, P, HG, LHG, _>(self.leaf_params.borrow(), key)?;
+		let computed_root = self.root_hash(key, &leaf_hash)?;
+
+		Ok(root.is_eq(&computed_root)?)
+	}
+
+	/// root of the tree with `leaf` stored at the position of `key`
+	pub fn root_hash(
+		&self,
+		key: &FpVar<F>,
+		leaf: &NodeVar<F, P, HG, LHG>,
+	) -> Result<NodeVar<F, P, HG, LHG>, MerkleError> {
+		if N != P::HEIGHT as usize {
+			return Err(MerkleError::InvalidPathLength {
+				expected: P::HEIGHT as usize,
+				found: N,
+			});
+		}
+		let bits = key.to_bits_le()?;
+		if bits.len() < N {
+			return Err(MerkleError::KeyTooShort {
+				height: N,
+				key_bits: bits.len(),
+			});
+		}
+
+		// Each key bit tells whether the current node is a right child.
+		let mut previous_hash = leaf.clone();
+		for (sibling, is_right) in self.siblings.iter().zip(bits.iter()) {
+			let left_hash = NodeVar::conditionally_select(is_right, sibling, &previous_hash)?;
+			let right_hash = NodeVar::conditionally_select(is_right, &previous_hash, sibling)?;
+
+			previous_hash = hash_inner_node_gadget::<F, P, HG, LHG>(
+				self.inner_params.borrow(),
+				&left_hash,
+				&right_hash,
+			)?;
+		}
+
+		Ok(previous_hash)
+	}
 }
 
-impl<F, P, HG, LHG, const N: usize> AllocVar<Path<P, N>, F> for PathVar<F, P, HG, LHG, N>
+impl<F, P, HG, LHG, const N: usize> AllocVar<KeyedPath<P, N>, F>
+	for NonMembershipPathVar<F, P, HG, LHG, N>
 where
 	F: PrimeField,
 	P: Config,
 	HG: CRHGadget<P::H, F>,
 	LHG: CRHGadget<P::LeafH, F>,
 {
-	fn new_variable<T: Borrow<Path<P, N>>>(
+	fn new_variable<T: Borrow<KeyedPath<P, N>>>(
 		cs: impl Into<Namespace<F>>,
 		f: impl FnOnce() -> Result<T, SynthesisError>,
 		mode: AllocationMode,
@@ -276,15 +387,19 @@ where
 		let ns = cs.into();
 		let cs = ns.cs();
 
-		let mut path = Vec::new();
+		let mut siblings = Vec::new();
 		let path_obj = f()?;
-		for &(ref l, ref r) in &path_obj.borrow().path {
-			let l_hash =
-				NodeVar::new_variable(ark_relations::ns!(cs, "l_child"), || Ok(l.clone()), mode)?;
-			let r_hash =
-				NodeVar::new_variable(ark_relations::ns!(cs, "r_child"), || Ok(r.clone()), mode)?;
-			path.push((l_hash, r_hash));
+		for sibling in &path_obj.borrow().siblings {
+			siblings.push(NodeVar::new_variable(
+				ark_relations::ns!(cs, "sibling"),
+				|| Ok(sibling.clone()),
+				mode,
+			)?);
 		}
+		let empty_leaf = NodeVar::new_constant(
+			ark_relations::ns!(cs, "empty_leaf"),
+			hash_empty::<P>(path_obj.borrow().leaf_params.borrow())?,
+		)?;
 
 		let inner_params_var = HG::ParametersVar::new_input(cs.clone(), || {
 			Ok(path_obj.borrow().inner_params.borrow())
@@ -292,149 +407,1296 @@ where
 		let leaf_params_var =
 			LHG::ParametersVar::new_input(cs, || Ok(path_obj.borrow().leaf_params.borrow()))?;
 
-		Ok(PathVar {
-			path: path.try_into().unwrap_or_else(
-				#[allow(clippy::type_complexity)]
-				|v: Vec<(NodeVar<F, P, HG, LHG>, NodeVar<F, P, HG, LHG>)>| {
-					panic!("Expected a Vec of length {} but it was {}", N, v.len())
-				},
-			),
+		Ok(NonMembershipPathVar {
+			siblings: siblings
+				.try_into()
+				.map_err(|_| SynthesisError::Unsatisfiable)?,
+			empty_leaf,
 			inner_params: Rc::new(inner_params_var),
 			leaf_params: Rc::new(leaf_params_var),
 		})
 	}
 }
 
-#[cfg(test)]
-mod test {
-	use super::{NodeVar, PathVar};
-	use crate::{
-		ark_std::UniformRand,
-		merkle_tree::{Config, Path, SparseMerkleTree},
-		poseidon::{constraints::CRHGadget as PoseidonCRHGadget, CRH as PoseidonCRH},
-	};
-
-	use ark_bls12_381::Fq;
-	use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar, R1CSVar};
-	use ark_relations::r1cs::ConstraintSystem;
-	use ark_std::{rc::Rc, test_rng};
-	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
-
-	type FieldVar = FpVar<Fq>;
-
-	type SMTCRH = PoseidonCRH<Fq>;
-	type SMTCRHGadget = PoseidonCRHGadget<Fq>;
+/// Gadgets for a membership proof of several leaves of one tree.
+///
+/// The leaf positions are taken from the native `MultiPath` as constants, so
+/// they shape the circuit, while every auxiliary node is a witness. Inner
+/// nodes shared between the proven leaves are hashed once.
+#[derive(Debug)]
+pub struct MultiPathVar<F, P, HG, LHG>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	indices: Vec<u64>,
+	auxiliary: Vec<NodeVar<F, P, HG, LHG>>,
+	inner_params: Rc<HG::ParametersVar>,
+	leaf_params: Rc<LHG::ParametersVar>,
+}
 
-	#[derive(Clone, Debug, Eq, PartialEq)]
-	struct SMTConfig;
-	impl Config for SMTConfig {
-		type H = SMTCRH;
-		type LeafH = SMTCRH;
+impl<F, P, HG, LHG> MultiPathVar<F, P, HG, LHG>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	/// check that all `leaves`, ordered like the native proof's indices, are
+	/// under `root`
+	pub fn check_membership<L: ToBytesGadget<F>>(
+		&self,
+		root: &NodeVar<F, P, HG, LHG>,
+		leaves: &[L],
+	) -> Result<Boolean<F>, MerkleError> {
+		let computed_root = self.root_hash(leaves)?;
 
-		const HEIGHT: u8 = 3;
+		Ok(root.is_eq(&computed_root)?)
 	}
 
-	type SMTNode = NodeVar<Fq, SMTConfig, SMTCRHGadget, SMTCRHGadget>;
-	type SMT = SparseMerkleTree<SMTConfig>;
+	pub fn root_hash<L: ToBytesGadget<F>>(
+		&self,
+		leaves: &[L],
+	) -> Result<NodeVar<F, P, HG, LHG>, MerkleError> {
+		if leaves.len() != self.indices.len() {
+			return Err(MerkleError::InvalidPathLength {
+				expected: self.indices.len(),
+				found: leaves.len(),
+			});
+		}
+		if leaves.is_empty() {
+			return Err(MerkleError::EmptyTree);
+		}
 
-	#[test]
-	fn should_verify_path() {
-		let rng = &mut test_rng();
-		let curve = Curve::Bls381;
+		let mut level_nodes = BTreeMap::new();
+		for (index, leaf) in self.indices.iter().zip(leaves) {
+			let leaf_hash = hash_leaf_gadget::<F, P, HG, LHG, L>(self.leaf_params.borrow(), leaf)?;
+			level_nodes.insert(convert_index_to_last_level::<P>(*index), leaf_hash);
+		}
 
-		let params3 = setup_params_x5_3(curve);
+		let mut auxiliary = self.auxiliary.iter();
+		for _ in 0..P::HEIGHT {
+			let mut parents = BTreeMap::new();
+			for (&index, node) in &level_nodes {
+				let sibling_index = sibling(index).ok_or(MerkleError::InconsistentPath)?;
+				let sibling_node = match level_nodes.get(&sibling_index) {
+					// the pair was already hashed when visiting the left child
+					Some(_) if !is_left_child(index) => continue,
+					Some(sibling_node) => sibling_node,
+					None => auxiliary.next().ok_or(MerkleError::InconsistentPath)?,
+				};
+
+				let parent_node = if is_left_child(index) {
+					hash_inner_node_gadget::<F, P, HG, LHG>(
+						self.inner_params.borrow(),
+						node,
+						sibling_node,
+					)?
+				} else {
+					hash_inner_node_gadget::<F, P, HG, LHG>(
+						self.inner_params.borrow(),
+						sibling_node,
+						node,
+					)?
+				};
+				parents.insert(
+					parent(index).ok_or(MerkleError::InconsistentPath)?,
+					parent_node,
+				);
+			}
+			level_nodes = parents;
+		}
 
-		let inner_params = Rc::new(params3);
-		let leaf_params = inner_params.clone();
+		if auxiliary.next().is_some() {
+			return Err(MerkleError::InconsistentPath);
+		}
+		level_nodes.remove(&0).ok_or(MerkleError::InconsistentPath)
+	}
+}
 
-		let cs = ConstraintSystem::<Fq>::new_ref();
+impl<F, P, HG, LHG> AllocVar<MultiPath<P>, F> for MultiPathVar<F, P, HG, LHG>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	fn new_variable<T: Borrow<MultiPath<P>>>(
+		cs: impl Into<Namespace<F>>,
+		f: impl FnOnce() -> Result<T, SynthesisError>,
+		mode: AllocationMode,
+	) -> Result<Self, SynthesisError> {
+		let ns = cs.into();
+		let cs = ns.cs();
 
-		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
-		let smt = SMT::new_sequential(inner_params, leaf_params, &leaves).unwrap();
-		let root = smt.root();
-		let path = smt.generate_membership_proof(0);
+		let mut auxiliary = Vec::new();
+		let path_obj = f()?;
+		for node in &path_obj.borrow().auxiliary {
+			auxiliary.push(NodeVar::new_variable(
+				ark_relations::ns!(cs, "auxiliary"),
+				|| Ok(node.clone()),
+				mode,
+			)?);
+		}
 
-		let path_var =
-			PathVar::<_, _, _, _, { SMTConfig::HEIGHT as usize }>::new_witness(cs.clone(), || {
-				Ok(path)
-			})
-			.unwrap();
-		let root_var = SMTNode::new_witness(cs.clone(), || Ok(root)).unwrap();
-		let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[0])).unwrap();
+		let inner_params_var = HG::ParametersVar::new_input(cs.clone(), || {
+			Ok(path_obj.borrow().inner_params.borrow())
+		})?;
+		let leaf_params_var =
+			LHG::ParametersVar::new_input(cs, || Ok(path_obj.borrow().leaf_params.borrow()))?;
 
-		let res = path_var.check_membership(&root_var, &leaf_var).unwrap();
-		assert!(res.cs().is_satisfied().unwrap());
-		assert!(res.value().unwrap());
+		Ok(MultiPathVar {
+			indices: path_obj.borrow().indices.clone(),
+			auxiliary,
+			inner_params: Rc::new(inner_params_var),
+			leaf_params: Rc::new(leaf_params_var),
+		})
 	}
+}
 
-	#[test]
-	fn should_verify_index() {
-		let rng = &mut test_rng();
-		let curve = Curve::Bls381;
+/// Gadgets for one `MmrPath`.
+///
+/// The height of the leaf's mountain and the number of peaks around it are
+/// taken from the native proof, so they shape the circuit, while the position
+/// of the leaf within its mountain is a witness.
+#[derive(Debug)]
+pub struct MmrPathVar<F, P, HG, LHG>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	siblings: Vec<NodeVar<F, P, HG, LHG>>,
+	/// little-endian bits of the leaf index within its mountain
+	index_bits: Vec<Boolean<F>>,
+	left_peaks: Vec<NodeVar<F, P, HG, LHG>>,
+	right_peaks: Vec<NodeVar<F, P, HG, LHG>>,
+	inner_params: Rc<HG::ParametersVar>,
+	leaf_params: Rc<LHG::ParametersVar>,
+}
 
-		let params3 = setup_params_x5_3(curve);
+impl<F, P, HG, LHG> MmrPathVar<F, P, HG, LHG>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	/// conditionally check a lookup proof against the bagged root
+	pub fn check_membership<L: ToBytesGadget<F>>(
+		&self,
+		root: &NodeVar<F, P, HG, LHG>,
+		leaf: &L,
+	) -> Result<Boolean<F>, MerkleError> {
+		let computed_root = self.root_hash(leaf)?;
 
-		let inner_params = Rc::new(params3);
-		let leaf_params = inner_params.clone();
-		let index = 2;
-		let cs = ConstraintSystem::<Fq>::new_ref();
+		Ok(root.is_eq(&computed_root)?)
+	}
 
-		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
-		let smt = SMT::new_sequential(inner_params, leaf_params, &leaves).unwrap();
-		let root = smt.root();
-		let path = smt.generate_membership_proof(index);
+	pub fn root_hash<L: ToBytesGadget<F>>(
+		&self,
+		leaf: &L,
+	) -> Result<NodeVar<F, P, HG, LHG>, MerkleError> {
+		let mut peak = hash_leaf_gadget::<F, P, HG, LHG, L>(self.leaf_params.borrow(), leaf)?;
+		for (sibling, is_right) in self.siblings.iter().zip(self.index_bits.iter()) {
+			let left_hash = NodeVar::conditionally_select(is_right, sibling, &peak)?;
+			let right_hash = NodeVar::conditionally_select(is_right, &peak, sibling)?;
 
-		let path_var =
-			PathVar::<_, _, _, _, { SMTConfig::HEIGHT as usize }>::new_witness(cs.clone(), || {
-				Ok(path)
-			})
-			.unwrap();
-		let root_var = SMTNode::new_witness(cs.clone(), || Ok(root)).unwrap();
-		let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[index as usize])).unwrap();
+			peak = hash_inner_node_gadget::<F, P, HG, LHG>(
+				self.inner_params.borrow(),
+				&left_hash,
+				&right_hash,
+			)?;
+		}
 
-		let res = path_var.get_index(&root_var, &leaf_var).unwrap();
-		let desired_res = Fq::from(index);
+		// Bag the peaks from the right, like `mmr::bag_peaks`.
+		let mut peaks = self
+			.left_peaks
+			.iter()
+			.chain(Some(&peak))
+			.chain(self.right_peaks.iter())
+			.rev();
+		let mut root = peaks.next().ok_or(MerkleError::EmptyTree)?.clone();
+		for peak in peaks {
+			root =
+				hash_inner_node_gadget::<F, P, HG, LHG>(self.inner_params.borrow(), peak, &root)?;
+		}
 
-		assert!(res.cs().is_satisfied().unwrap());
-		assert_eq!(res.value().unwrap(), desired_res);
+		Ok(root)
 	}
+}
 
-	#[should_panic(expected = "Expected a Vec of length 2 but it was 3")]
-	#[test]
-	fn should_fail_path_creation_with_invalid_size() {
-		let rng = &mut test_rng();
-		let curve = Curve::Bls381;
+impl<F, P, HG, LHG> AllocVar<MmrPath<P>, F> for MmrPathVar<F, P, HG, LHG>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	fn new_variable<T: Borrow<MmrPath<P>>>(
+		cs: impl Into<Namespace<F>>,
+		f: impl FnOnce() -> Result<T, SynthesisError>,
+		mode: AllocationMode,
+	) -> Result<Self, SynthesisError> {
+		let ns = cs.into();
+		let cs = ns.cs();
 
-		let params3 = setup_params_x5_3(curve);
+		let mut siblings = Vec::new();
+		let mut index_bits = Vec::new();
+		let path_obj = f()?;
+		let index = path_obj.borrow().index;
+		for (level, sibling) in path_obj.borrow().siblings.iter().enumerate() {
+			siblings.push(NodeVar::new_variable(
+				ark_relations::ns!(cs, "sibling"),
+				|| Ok(sibling.clone()),
+				mode,
+			)?);
+			index_bits.push(Boolean::new_variable(
+				ark_relations::ns!(cs, "index_bit"),
+				|| Ok((index >> level) & 1 == 1),
+				mode,
+			)?);
+		}
+		let mut left_peaks = Vec::new();
+		for peak in &path_obj.borrow().left_peaks {
+			left_peaks.push(NodeVar::new_variable(
+				ark_relations::ns!(cs, "left_peak"),
+				|| Ok(peak.clone()),
+				mode,
+			)?);
+		}
+		let mut right_peaks = Vec::new();
+		for peak in &path_obj.borrow().right_peaks {
+			right_peaks.push(NodeVar::new_variable(
+				ark_relations::ns!(cs, "right_peak"),
+				|| Ok(peak.clone()),
+				mode,
+			)?);
+		}
 
-		let inner_params = Rc::new(params3);
-		let leaf_params = inner_params.clone();
+		let inner_params_var = HG::ParametersVar::new_input(cs.clone(), || {
+			Ok(path_obj.borrow().inner_params.borrow())
+		})?;
+		let leaf_params_var =
+			LHG::ParametersVar::new_input(cs, || Ok(path_obj.borrow().leaf_params.borrow()))?;
 
-		let cs = ConstraintSystem::<Fq>::new_ref();
+		Ok(MmrPathVar {
+			siblings,
+			index_bits,
+			left_peaks,
+			right_peaks,
+			inner_params: Rc::new(inner_params_var),
+			leaf_params: Rc::new(leaf_params_var),
+		})
+	}
+}
+
+/// Gadgets for one `CompactPath`, ordering every level with the index bits
+/// instead of comparing the computed hash against both children
+#[derive(Debug)]
+pub struct CompactPathVar<F, P, HG, LHG, const N: usize>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	siblings: [NodeVar<F, P, HG, LHG>; N],
+	/// little-endian bits of the leaf index
+	index_bits: [Boolean<F>; N],
+	inner_params: Rc<HG::ParametersVar>,
+	leaf_params: Rc<LHG::ParametersVar>,
+}
+
+impl<F, P, HG, LHG, const N: usize> CompactPathVar<F, P, HG, LHG, N>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	/// conditionally check a lookup proof
+	pub fn check_membership<L: ToBytesGadget<F>>(
+		&self,
+		root: &NodeVar<F, P, HG, LHG>,
+		leaf: L,
+	) -> Result<Boolean<F>, MerkleError> {
+		let computed_root = self.root_hash(&leaf)?;
+
+		Ok(root.is_eq(&computed_root)?)
+	}
+
+	pub fn root_hash<L: ToBytesGadget<F>>(
+		&self,
+		leaf: &L,
+	) -> Result<NodeVar<F, P, HG, LHG>, MerkleError> {
+		if N != P::HEIGHT as usize {
+			return Err(MerkleError::InvalidPathLength {
+				expected: P::HEIGHT as usize,
+				found: N,
+			});
+		}
+
+		let mut previous_hash =
+			hash_leaf_gadget::<F, P, HG, LHG, L>(self.leaf_params.borrow(), leaf)?;
+		for (sibling, is_right) in self.siblings.iter().zip(self.index_bits.iter()) {
+			let left_hash = NodeVar::conditionally_select(is_right, sibling, &previous_hash)?;
+			let right_hash = NodeVar::conditionally_select(is_right, &previous_hash, sibling)?;
+
+			previous_hash = hash_inner_node_gadget::<F, P, HG, LHG>(
+				self.inner_params.borrow(),
+				&left_hash,
+				&right_hash,
+			)?;
+		}
+
+		Ok(previous_hash)
+	}
+
+	/// index of the leaf, composed from its bits
+	pub fn get_index(&self) -> Result<FpVar<F>, MerkleError> {
+		Ok(Boolean::le_bits_to_fp_var(&self.index_bits)?)
+	}
+}
+
+impl<F, P, HG, LHG, const N: usize> AllocVar<CompactPath<P, N>, F>
+	for CompactPathVar<F, P, HG, LHG, N>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	fn new_variable<T: Borrow<CompactPath<P, N>>>(
+		cs: impl Into<Namespace<F>>,
+		f: impl FnOnce() -> Result<T, SynthesisError>,
+		mode: AllocationMode,
+	) -> Result<Self, SynthesisError> {
+		let ns = cs.into();
+		let cs = ns.cs();
+
+		let mut siblings = Vec::new();
+		let mut index_bits = Vec::new();
+		let path_obj = f()?;
+		let index = path_obj.borrow().index;
+		for (level, sibling) in path_obj.borrow().siblings.iter().enumerate() {
+			siblings.push(NodeVar::new_variable(
+				ark_relations::ns!(cs, "sibling"),
+				|| Ok(sibling.clone()),
+				mode,
+			)?);
+			index_bits.push(Boolean::new_variable(
+				ark_relations::ns!(cs, "index_bit"),
+				|| Ok((index >> level) & 1 == 1),
+				mode,
+			)?);
+		}
+
+		let inner_params_var = HG::ParametersVar::new_input(cs.clone(), || {
+			Ok(path_obj.borrow().inner_params.borrow())
+		})?;
+		let leaf_params_var =
+			LHG::ParametersVar::new_input(cs, || Ok(path_obj.borrow().leaf_params.borrow()))?;
+
+		Ok(CompactPathVar {
+			siblings: siblings
+				.try_into()
+				.map_err(|_| SynthesisError::Unsatisfiable)?,
+			index_bits: index_bits
+				.try_into()
+				.map_err(|_| SynthesisError::Unsatisfiable)?,
+			inner_params: Rc::new(inner_params_var),
+			leaf_params: Rc::new(leaf_params_var),
+		})
+	}
+}
+
+/// Gadgets for storing a leaf at an empty position of a tree.
+///
+/// The siblings of a position do not change when its leaf is written, so one
+/// `CompactPath` proves the position empty under the old root and yields the
+/// root after the write.
+#[derive(Debug)]
+pub struct UpdatePathVar<F, P, HG, LHG, const N: usize>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	siblings: [NodeVar<F, P, HG, LHG>; N],
+	/// little-endian bits of the leaf index
+	index_bits: [Boolean<F>; N],
+	empty_leaf: NodeVar<F, P, HG, LHG>,
+	inner_params: Rc<HG::ParametersVar>,
+	leaf_params: Rc<LHG::ParametersVar>,
+}
+
+impl<F, P, HG, LHG, const N: usize> UpdatePathVar<F, P, HG, LHG, N>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	/// conditionally check that writing `leaf` to the empty position moves the
+	/// tree from `old_root` to `new_root`
+	pub fn check_update<L: ToBytesGadget<F>>(
+		&self,
+		old_root: &NodeVar<F, P, HG, LHG>,
+		new_root: &NodeVar<F, P, HG, LHG>,
+		leaf: &L,
+	) -> Result<Boolean<F>, MerkleError> {
+		let (is_empty, computed_root) = self.update_root(old_root, leaf)?;
+
+		Ok(is_empty.and(&new_root.is_eq(&computed_root)?)?)
+	}
+
+	/// whether the position is empty under `old_root`, and the root once
+	/// `leaf` is written to it
+	pub fn update_root<L: ToBytesGadget<F>>(
+		&self,
+		old_root: &NodeVar<F, P, HG, LHG>,
+		leaf: &L,
+	) -> Result<(Boolean<F>, NodeVar<F, P, HG, LHG>), MerkleError> {
+		let empty_root = self.root_hash(&self.empty_leaf)?;
+		let leaf_hash = hash_leaf_gadget::<F, P, HG, LHG, L>(self.leaf_params.borrow(), leaf)?;
+		let new_root = self.root_hash(&leaf_hash)?;
+
+		Ok((old_root.is_eq(&empty_root)?, new_root))
+	}
+
+	/// root of the tree holding the hashed leaf `leaf_hash` at the position
+	pub fn root_hash(
+		&self,
+		leaf_hash: &NodeVar<F, P, HG, LHG>,
+	) -> Result<NodeVar<F, P, HG, LHG>, MerkleError> {
+		if N != P::HEIGHT as usize {
+			return Err(MerkleError::InvalidPathLength {
+				expected: P::HEIGHT as usize,
+				found: N,
+			});
+		}
+
+		let mut previous_hash = leaf_hash.clone();
+		for (sibling, is_right) in self.siblings.iter().zip(self.index_bits.iter()) {
+			let left_hash = NodeVar::conditionally_select(is_right, sibling, &previous_hash)?;
+			let right_hash = NodeVar::conditionally_select(is_right, &previous_hash, sibling)?;
+
+			previous_hash = hash_inner_node_gadget::<F, P, HG, LHG>(
+				self.inner_params.borrow(),
+				&left_hash,
+				&right_hash,
+			)?;
+		}
+
+		Ok(previous_hash)
+	}
+
+	/// index of the written leaf, composed from its bits
+	pub fn get_index(&self) -> Result<FpVar<F>, MerkleError> {
+		Ok(Boolean::le_bits_to_fp_var(&self.index_bits)?)
+	}
+}
+
+impl<F, P, HG, LHG, const N: usize> AllocVar<CompactPath<P, N>, F>
+	for UpdatePathVar<F, P, HG, LHG, N>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	fn new_variable<T: Borrow<CompactPath<P, N>>>(
+		cs: impl Into<Namespace<F>>,
+		f: impl FnOnce() -> Result<T, SynthesisError>,
+		mode: AllocationMode,
+	) -> Result<Self, SynthesisError> {
+		let ns = cs.into();
+		let cs = ns.cs();
+
+		let mut siblings = Vec::new();
+		let mut index_bits = Vec::new();
+		let path_obj = f()?;
+		let index = path_obj.borrow().index;
+		for (level, sibling) in path_obj.borrow().siblings.iter().enumerate() {
+			siblings.push(NodeVar::new_variable(
+				ark_relations::ns!(cs, "sibling"),
+				|| Ok(sibling.clone()),
+				mode,
+			)?);
+			index_bits.push(Boolean::new_variable(
+				ark_relations::ns!(cs, "index_bit"),
+				|| Ok((index >> level) & 1 == 1),
+				mode,
+			)?);
+		}
+		let empty_leaf = NodeVar::new_constant(
+			ark_relations::ns!(cs, "empty_leaf"),
+			hash_empty::<P>(path_obj.borrow().leaf_params.borrow())?,
+		)?;
+
+		let inner_params_var = HG::ParametersVar::new_input(cs.clone(), || {
+			Ok(path_obj.borrow().inner_params.borrow())
+		})?;
+		let leaf_params_var =
+			LHG::ParametersVar::new_input(cs, || Ok(path_obj.borrow().leaf_params.borrow()))?;
+
+		Ok(UpdatePathVar {
+			siblings: siblings
+				.try_into()
+				.map_err(|_| SynthesisError::Unsatisfiable)?,
+			index_bits: index_bits
+				.try_into()
+				.map_err(|_| SynthesisError::Unsatisfiable)?,
+			empty_leaf,
+			inner_params: Rc::new(inner_params_var),
+			leaf_params: Rc::new(leaf_params_var),
+		})
+	}
+}
+
+/// Gadgets for one path of a `QuadMerkleTree`, with the three siblings of the
+/// path node and the two bits of its position on every level as witnesses
+#[derive(Debug)]
+pub struct Path4Var<F, P, HG, LHG, const N: usize>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	/// children of every level except the path node, in order
+	siblings: [[NodeVar<F, P, HG, LHG>; ARITY - 1]; N],
+	/// little-endian bits of the position of the path node on every level
+	index_bits: [[Boolean<F>; 2]; N],
+	inner_params: Rc<HG::ParametersVar>,
+	leaf_params: Rc<LHG::ParametersVar>,
+}
+
+impl<F, P, HG, LHG, const N: usize> Path4Var<F, P, HG, LHG, N>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	/// conditionally check a lookup proof, the leaf position is given by
+	/// `get_index`
+	pub fn check_membership<L: ToBytesGadget<F>>(
+		&self,
+		root: &NodeVar<F, P, HG, LHG>,
+		leaf: L,
+	) -> Result<Boolean<F>, MerkleError> {
+		let computed_root = self.root_hash(&leaf)?;
+
+		Ok(root.is_eq(&computed_root)?)
+	}
+
+	pub fn root_hash<L: ToBytesGadget<F>>(
+		&self,
+		leaf: &L,
+	) -> Result<NodeVar<F, P, HG, LHG>, MerkleError> {
+		if N != P::HEIGHT as usize {
+			return Err(MerkleError::InvalidPathLength {
+				expected: P::HEIGHT as usize,
+				found: N,
+			});
+		}
+
+		let mut previous_hash =
+			hash_leaf_gadget::<F, P, HG, LHG, L>(self.leaf_params.borrow(), leaf)?;
+		for (siblings, bits) in self.siblings.iter().zip(self.index_bits.iter()) {
+			// Insert the previous hash among the siblings at the position given
+			// by the two index bits.
+			let [s0, s1, s2] = siblings;
+			let [low, high] = bits;
+			let children = [
+				NodeVar::conditionally_select(
+					high,
+					s0,
+					&NodeVar::conditionally_select(low, s0, &previous_hash)?,
+				)?,
+				NodeVar::conditionally_select(
+					high,
+					s1,
+					&NodeVar::conditionally_select(low, &previous_hash, s0)?,
+				)?,
+				NodeVar::conditionally_select(
+					high,
+					&NodeVar::conditionally_select(low, s2, &previous_hash)?,
+					s1,
+				)?,
+				NodeVar::conditionally_select(&low.and(high)?, &previous_hash, s2)?,
+			];
+
+			previous_hash =
+				hash_inner_node4_gadget::<F, P, HG, LHG>(self.inner_params.borrow(), &children)?;
+		}
+
+		Ok(previous_hash)
+	}
+
+	/// index of the leaf, composed from its bits
+	pub fn get_index(&self) -> Result<FpVar<F>, MerkleError> {
+		let bits: Vec<Boolean<F>> = self.index_bits.iter().flatten().cloned().collect();
+		Ok(Boolean::le_bits_to_fp_var(&bits)?)
+	}
+}
+
+impl<F, P, HG, LHG, const N: usize> AllocVar<Path4<P, N>, F> for Path4Var<F, P, HG, LHG, N>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	fn new_variable<T: Borrow<Path4<P, N>>>(
+		cs: impl Into<Namespace<F>>,
+		f: impl FnOnce() -> Result<T, SynthesisError>,
+		mode: AllocationMode,
+	) -> Result<Self, SynthesisError> {
+		let ns = cs.into();
+		let cs = ns.cs();
+
+		let mut siblings = Vec::new();
+		let mut index_bits = Vec::new();
+		let path_obj = f()?;
+		for (level, children) in path_obj.borrow().path.iter().enumerate() {
+			let position = path_obj.borrow().position(level);
+			let mut sibling_vars = Vec::with_capacity(ARITY - 1);
+			for (_, child) in children.iter().enumerate().filter(|(k, _)| *k != position) {
+				sibling_vars.push(NodeVar::new_variable(
+					ark_relations::ns!(cs, "sibling"),
+					|| Ok(child.clone()),
+					mode,
+				)?);
+			}
+			let sibling_vars: [NodeVar<F, P, HG, LHG>; ARITY - 1] = sibling_vars
+				.try_into()
+				.map_err(|_| SynthesisError::Unsatisfiable)?;
+			siblings.push(sibling_vars);
+
+			let low = Boolean::new_variable(
+				ark_relations::ns!(cs, "index_bit"),
+				|| Ok(position & 1 == 1),
+				mode,
+			)?;
+			let high = Boolean::new_variable(
+				ark_relations::ns!(cs, "index_bit"),
+				|| Ok(position & 2 == 2),
+				mode,
+			)?;
+			index_bits.push([low, high]);
+		}
+
+		let inner_params_var = HG::ParametersVar::new_input(cs.clone(), || {
+			Ok(path_obj.borrow().inner_params.borrow())
+		})?;
+		let leaf_params_var =
+			LHG::ParametersVar::new_input(cs, || Ok(path_obj.borrow().leaf_params.borrow()))?;
+
+		Ok(Path4Var {
+			siblings: siblings
+				.try_into()
+				.map_err(|_| SynthesisError::Unsatisfiable)?,
+			index_bits: index_bits
+				.try_into()
+				.map_err(|_| SynthesisError::Unsatisfiable)?,
+			inner_params: Rc::new(inner_params_var),
+			leaf_params: Rc::new(leaf_params_var),
+		})
+	}
+}
+
+/// Gadgets for a `ConsistencyProof`, with the bits of the last old and the
+/// last new leaf index as witnesses
+#[derive(Debug)]
+pub struct ConsistencyProofVar<F, P, HG, LHG, const N: usize>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	old_leaf: NodeVar<F, P, HG, LHG>,
+	old_siblings: [NodeVar<F, P, HG, LHG>; N],
+	/// little-endian bits of `old_size - 1`
+	old_bits: [Boolean<F>; N],
+	new_leaf: NodeVar<F, P, HG, LHG>,
+	new_siblings: [NodeVar<F, P, HG, LHG>; N],
+	/// little-endian bits of `new_size - 1`
+	new_bits: [Boolean<F>; N],
+	/// hash of an empty subtree on every level below the root, derived from
+	/// the parameters
+	empty_hashes: [NodeVar<F, P, HG, LHG>; N],
+	inner_params: Rc<HG::ParametersVar>,
+}
+
+impl<F, P, HG, LHG, const N: usize> ConsistencyProofVar<F, P, HG, LHG, N>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	/// conditionally check that `new_root` only appends leaves to the tree of
+	/// `old_root`
+	pub fn check_consistency(
+		&self,
+		old_root: &NodeVar<F, P, HG, LHG>,
+		new_root: &NodeVar<F, P, HG, LHG>,
+	) -> Result<Boolean<F>, MerkleError> {
+		let (old, extended, new) = self.root_hashes()?;
+		// `old_size <= new_size`, equal sizes are allowed as in `check_sizes`
+		let not_shrunk = self
+			.old_size()?
+			.is_cmp(&self.new_size()?, Ordering::Less, true)?;
+
+		Ok(old_root
+			.is_eq(&old)?
+			.and(&new_root.is_eq(&extended)?)?
+			.and(&new_root.is_eq(&new)?)?
+			.and(&not_shrunk)?)
+	}
+
+	/// roots of the old tree, of the old tree extended with the appended
+	/// subtrees and of the new tree
+	#[allow(clippy::type_complexity)]
+	pub fn root_hashes(
+		&self,
+	) -> Result<
+		(
+			NodeVar<F, P, HG, LHG>,
+			NodeVar<F, P, HG, LHG>,
+			NodeVar<F, P, HG, LHG>,
+		),
+		MerkleError,
+	> {
+		if N != P::HEIGHT as usize {
+			return Err(MerkleError::InvalidPathLength {
+				expected: P::HEIGHT as usize,
+				found: N,
+			});
+		}
+
+		let inner_params = self.inner_params.borrow();
+		let mut old_root = self.old_leaf.clone();
+		let mut extended_root = self.old_leaf.clone();
+		for ((sibling, is_right), empty_hash) in self
+			.old_siblings
+			.iter()
+			.zip(self.old_bits.iter())
+			.zip(self.empty_hashes.iter())
+		{
+			let left_hash = NodeVar::conditionally_select(is_right, sibling, &old_root)?;
+			let right_hash = NodeVar::conditionally_select(is_right, &old_root, empty_hash)?;
+			old_root =
+				hash_inner_node_gadget::<F, P, HG, LHG>(inner_params, &left_hash, &right_hash)?;
+
+			let left_hash = NodeVar::conditionally_select(is_right, sibling, &extended_root)?;
+			let right_hash = NodeVar::conditionally_select(is_right, &extended_root, sibling)?;
+			extended_root =
+				hash_inner_node_gadget::<F, P, HG, LHG>(inner_params, &left_hash, &right_hash)?;
+		}
+
+		let mut new_root = self.new_leaf.clone();
+		for ((sibling, is_right), empty_hash) in self
+			.new_siblings
+			.iter()
+			.zip(self.new_bits.iter())
+			.zip(self.empty_hashes.iter())
+		{
+			let left_hash = NodeVar::conditionally_select(is_right, sibling, &new_root)?;
+			let right_hash = NodeVar::conditionally_select(is_right, &new_root, empty_hash)?;
+			new_root =
+				hash_inner_node_gadget::<F, P, HG, LHG>(inner_params, &left_hash, &right_hash)?;
+		}
+
+		Ok((old_root, extended_root, new_root))
+	}
+
+	/// number of leaves of the old tree
+	pub fn old_size(&self) -> Result<FpVar<F>, MerkleError> {
+		Ok(Boolean::le_bits_to_fp_var(&self.old_bits)? + FpVar::one())
+	}
+
+	/// number of leaves of the new tree
+	pub fn new_size(&self) -> Result<FpVar<F>, MerkleError> {
+		Ok(Boolean::le_bits_to_fp_var(&self.new_bits)? + FpVar::one())
+	}
+}
+
+impl<F, P, HG, LHG, const N: usize> AllocVar<ConsistencyProof<P, N>, F>
+	for ConsistencyProofVar<F, P, HG, LHG, N>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	fn new_variable<T: Borrow<ConsistencyProof<P, N>>>(
+		cs: impl Into<Namespace<F>>,
+		f: impl FnOnce() -> Result<T, SynthesisError>,
+		mode: AllocationMode,
+	) -> Result<Self, SynthesisError> {
+		let ns = cs.into();
+		let cs = ns.cs();
+
+		let proof_obj = f()?;
+		let proof = proof_obj.borrow();
+		// rejects an empty old tree, whose last leaf index would underflow
+		check_sizes::<P>(proof.old_size, proof.new_size)?;
+		let empty_hashes =
+			gen_empty_hashes::<P>(proof.leaf_params.borrow(), proof.inner_params.borrow())?;
+
+		let old_leaf = NodeVar::new_variable(
+			ark_relations::ns!(cs, "old_leaf"),
+			|| Ok(proof.old_leaf.clone()),
+			mode,
+		)?;
+		let new_leaf = NodeVar::new_variable(
+			ark_relations::ns!(cs, "new_leaf"),
+			|| Ok(proof.new_leaf.clone()),
+			mode,
+		)?;
+
+		let mut old_siblings = Vec::new();
+		let mut old_bits = Vec::new();
+		let mut new_siblings = Vec::new();
+		let mut new_bits = Vec::new();
+		let mut empty_hash_vars = Vec::new();
+		let old_index = proof.old_size - 1;
+		let new_index = proof.new_size - 1;
+		for level in 0..N {
+			old_siblings.push(NodeVar::new_variable(
+				ark_relations::ns!(cs, "old_sibling"),
+				|| Ok(proof.old_siblings[level].clone()),
+				mode,
+			)?);
+			old_bits.push(Boolean::new_variable(
+				ark_relations::ns!(cs, "old_index_bit"),
+				|| Ok((old_index >> level) & 1 == 1),
+				mode,
+			)?);
+			new_siblings.push(NodeVar::new_variable(
+				ark_relations::ns!(cs, "new_sibling"),
+				|| Ok(proof.new_siblings[level].clone()),
+				mode,
+			)?);
+			new_bits.push(Boolean::new_variable(
+				ark_relations::ns!(cs, "new_index_bit"),
+				|| Ok((new_index >> level) & 1 == 1),
+				mode,
+			)?);
+			empty_hash_vars.push(NodeVar::new_constant(
+				ark_relations::ns!(cs, "empty_hash"),
+				empty_hashes[level].clone(),
+			)?);
+		}
+
+		let inner_params_var =
+			HG::ParametersVar::new_input(cs, || Ok(proof.inner_params.borrow()))?;
+
+		Ok(ConsistencyProofVar {
+			old_leaf,
+			old_siblings: old_siblings
+				.try_into()
+				.map_err(|_| SynthesisError::Unsatisfiable)?,
+			old_bits: old_bits
+				.try_into()
+				.map_err(|_| SynthesisError::Unsatisfiable)?,
+			new_leaf,
+			new_siblings: new_siblings
+				.try_into()
+				.map_err(|_| SynthesisError::Unsatisfiable)?,
+			new_bits: new_bits
+				.try_into()
+				.map_err(|_| SynthesisError::Unsatisfiable)?,
+			empty_hashes: empty_hash_vars
+				.try_into()
+				.map_err(|_| SynthesisError::Unsatisfiable)?,
+			inner_params: Rc::new(inner_params_var),
+		})
+	}
+}
+
+pub(crate) fn hash_leaf_gadget<F, P, HG, LHG, L>(
+	leaf_params: &LHG::ParametersVar,
+	leaf: &L,
+) -> Result<NodeVar<F, P, HG, LHG>, SynthesisError>
+where
+	F: PrimeField,
+	P: Config,
+	L: ToBytesGadget<F>,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	let bytes = with_domain_gadget(P::LEAF_DOMAIN, leaf.to_bytes()?);
+	Ok(NodeVar::Leaf(LHG::evaluate(leaf_params, &bytes)?))
+}
+
+pub(crate) fn hash_inner_node_gadget<F, P, HG, LHG>(
+	inner_params: &HG::ParametersVar,
+	left_child: &NodeVar<F, P, HG, LHG>,
+	right_child: &NodeVar<F, P, HG, LHG>,
+) -> Result<NodeVar<F, P, HG, LHG>, SynthesisError>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	let mut bytes = Vec::new();
+	bytes.extend(left_child.to_bytes()?);
+	bytes.extend(right_child.to_bytes()?);
+	let bytes = with_domain_gadget(P::NODE_DOMAIN, bytes);
+	let res = HG::evaluate(inner_params, &bytes)?;
+	Ok(NodeVar::Inner(res))
+}
+
+pub(crate) fn hash_inner_node4_gadget<F, P, HG, LHG>(
+	inner_params: &HG::ParametersVar,
+	children: &[NodeVar<F, P, HG, LHG>; ARITY],
+) -> Result<NodeVar<F, P, HG, LHG>, SynthesisError>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	let mut bytes = Vec::new();
+	for child in children.iter() {
+		bytes.extend(child.to_bytes()?);
+	}
+	let bytes = with_domain_gadget(P::NODE_DOMAIN, bytes);
+	let res = HG::evaluate(inner_params, &bytes)?;
+	Ok(NodeVar::Inner(res))
+}
+
+pub(crate) fn hash_leaf_fields_gadget<F, P, HG, LHG>(
+	leaf_params: &LHG::ParametersVar,
+	leaf: &FpVar<F>,
+) -> Result<NodeVar<F, P, HG, LHG>, MerkleError>
+where
+	F: PrimeField,
+	P: Config,
+	P::H: FieldHasher<F>,
+	P::LeafH: FieldHasher<F>,
+	HG: FieldHasherGadget<P::H, F>,
+	LHG: FieldHasherGadget<P::LeafH, F>,
+{
+	let inputs = with_domain_fields_gadget(P::LEAF_DOMAIN, vec![leaf.clone()])?;
+	Ok(NodeVar::Leaf(LHG::hash(leaf_params, &inputs)?))
+}
+
+pub(crate) fn hash_inner_node_fields_gadget<F, P, HG, LHG>(
+	inner_params: &HG::ParametersVar,
+	left_child: &NodeVar<F, P, HG, LHG>,
+	right_child: &NodeVar<F, P, HG, LHG>,
+) -> Result<NodeVar<F, P, HG, LHG>, MerkleError>
+where
+	F: PrimeField,
+	P: Config,
+	P::H: FieldHasher<F>,
+	P::LeafH: FieldHasher<F>,
+	HG: FieldHasherGadget<P::H, F>,
+	LHG: FieldHasherGadget<P::LeafH, F>,
+{
+	let children = [left_child, right_child]
+		.iter()
+		.map(|child| match child {
+			NodeVar::Inner(inner) => inner.clone(),
+			NodeVar::Leaf(leaf) => leaf.clone(),
+		})
+		.collect();
+	let inputs = with_domain_fields_gadget(P::NODE_DOMAIN, children)?;
+	Ok(NodeVar::Inner(HG::hash(inner_params, &inputs)?))
+}
+
+/// Returns `inputs` prefixed with the domain tag read as constant field
+/// elements, if there is one.
+fn with_domain_fields_gadget<F: PrimeField>(
+	domain: Option<&[u8]>,
+	inputs: Vec<FpVar<F>>,
+) -> Result<Vec<FpVar<F>>, MerkleError> {
+	let tag = with_domain_fields::<F>(domain, Vec::new())?;
+	Ok(tag.into_iter().map(FpVar::Constant).chain(inputs).collect())
+}
+
+/// Returns `bytes` prefixed with the domain tag as constants, if there is one.
+fn with_domain_gadget<F: PrimeField>(domain: Option<&[u8]>, bytes: Vec<UInt8<F>>) -> Vec<UInt8<F>> {
+	match domain {
+		Some(tag) => UInt8::constant_vec(tag).into_iter().chain(bytes).collect(),
+		None => bytes,
+	}
+}
+
+impl<F, P, HG, LHG, const N: usize> AllocVar<Path<P, N>, F> for PathVar<F, P, HG, LHG, N>
+where
+	F: PrimeField,
+	P: Config,
+	HG: CRHGadget<P::H, F>,
+	LHG: CRHGadget<P::LeafH, F>,
+{
+	fn new_variable<T: Borrow<Path<P, N>>>(
+		cs: impl Into<Namespace<F>>,
+		f: impl FnOnce() -> Result<T, SynthesisError>,
+		mode: AllocationMode,
+	) -> Result<Self, SynthesisError> {
+		let ns = cs.into();
+		let cs = ns.cs();
+
+		let mut path = Vec::new();
+		let path_obj = f()?;
+		for &(ref l, ref r) in &path_obj.borrow().path {
+			let l_hash =
+				NodeVar::new_variable(ark_relations::ns!(cs, "l_child"), || Ok(l.clone()), mode)?;
+			let r_hash =
+				NodeVar::new_variable(ark_relations::ns!(cs, "r_child"), || Ok(r.clone()), mode)?;
+			path.push((l_hash, r_hash));
+		}
+
+		let inner_params_var = HG::ParametersVar::new_input(cs.clone(), || {
+			Ok(path_obj.borrow().inner_params.borrow())
+		})?;
+		let leaf_params_var =
+			LHG::ParametersVar::new_input(cs, || Ok(path_obj.borrow().leaf_params.borrow()))?;
+
+		Ok(PathVar {
+			path: path.try_into().map_err(|_| SynthesisError::Unsatisfiable)?,
+			inner_params: Rc::new(inner_params_var),
+			leaf_params: Rc::new(leaf_params_var),
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::{
+		CompactPathVar, ConsistencyProofVar, MultiPathVar, NodeVar, NonMembershipPathVar, Path4Var,
+		PathVar,
+	};
+	use crate::{
+		ark_std::UniformRand,
+		merkle_tree::{
+			compact::CompactPath, hash_leaf, keyed::KeyedMerkleTree, mmr::MerkleMountainRange,
+			quad::QuadMerkleTree, Config, MerkleError, Path, SparseMerkleTree,
+		},
+		poseidon::{constraints::CRHGadget as PoseidonCRHGadget, CRH as PoseidonCRH},
+	};
+
+	use ark_bls12_381::Fq;
+	use ark_r1cs_std::{
+		alloc::AllocVar, boolean::Boolean, eq::EqGadget, fields::fp::FpVar, R1CSVar,
+	};
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::{collections::BTreeMap, rc::Rc, test_rng};
+	use arkworks_utils::utils::common::{setup_params_x5_3, setup_params_x5_5, Curve};
+
+	type FieldVar = FpVar<Fq>;
+
+	type SMTCRH = PoseidonCRH<Fq>;
+	type SMTCRHGadget = PoseidonCRHGadget<Fq>;
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct SMTConfig;
+	impl Config for SMTConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	type SMTNode = NodeVar<Fq, SMTConfig, SMTCRHGadget, SMTCRHGadget>;
+	type SMT = SparseMerkleTree<SMTConfig>;
+
+	#[test]
+	fn should_verify_path() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params3 = setup_params_x5_3(curve);
+
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = SMT::new_sequential(inner_params, leaf_params, &leaves).unwrap();
+		let root = smt.root().unwrap();
+		let path = smt.generate_membership_proof(0).unwrap();
+
+		let path_var =
+			PathVar::<_, _, _, _, { SMTConfig::HEIGHT as usize }>::new_witness(cs.clone(), || {
+				Ok(path)
+			})
+			.unwrap();
+		let root_var = SMTNode::new_witness(cs.clone(), || Ok(root)).unwrap();
+		let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[0])).unwrap();
+
+		let res = path_var.check_membership(&root_var, &leaf_var).unwrap();
+		assert!(res.cs().is_satisfied().unwrap());
+		assert!(res.value().unwrap());
+	}
+
+	#[test]
+	fn should_verify_index() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params3 = setup_params_x5_3(curve);
+
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+		let index = 2;
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = SMT::new_sequential(inner_params, leaf_params, &leaves).unwrap();
+		let root = smt.root().unwrap();
+		let path = smt.generate_membership_proof(index).unwrap();
+
+		let path_var =
+			PathVar::<_, _, _, _, { SMTConfig::HEIGHT as usize }>::new_witness(cs.clone(), || {
+				Ok(path)
+			})
+			.unwrap();
+		let root_var = SMTNode::new_witness(cs.clone(), || Ok(root)).unwrap();
+		let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[index as usize])).unwrap();
+
+		let res = path_var.get_index(&root_var, &leaf_var).unwrap();
+		let desired_res = Fq::from(index);
+
+		assert!(res.cs().is_satisfied().unwrap());
+		assert_eq!(res.value().unwrap(), desired_res);
+	}
+
+	#[test]
+	fn should_verify_path_from_fields_with_fewer_constraints() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params3 = setup_params_x5_3(curve);
+
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
 
 		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
 		let smt = SMT::new_sequential(inner_params, leaf_params, &leaves).unwrap();
-		let root = smt.root();
-		let path = smt.generate_membership_proof(0);
+		let root = smt.root().unwrap();
+		let path = smt.generate_membership_proof(1).unwrap();
+
+		let synthesize = |from_fields: bool| {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let path_var = PathVar::<_, _, _, _, { SMTConfig::HEIGHT as usize }>::new_witness(
+				cs.clone(),
+				|| Ok(path.clone()),
+			)
+			.unwrap();
+			let root_var = SMTNode::new_witness(cs.clone(), || Ok(root.clone())).unwrap();
+			let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[1])).unwrap();
 
-		// pass a size one less than tree HEIGHT
-		// should panic here
-		let path_var = PathVar::<_, _, _, _, { (SMTConfig::HEIGHT - 1) as usize }>::new_witness(
-			cs.clone(),
-			|| Ok(path),
-		)
-		.unwrap();
-		let root_var = SMTNode::new_witness(cs.clone(), || Ok(root)).unwrap();
-		let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[0])).unwrap();
+			let res = if from_fields {
+				path_var.check_membership_fields(&root_var, &leaf_var)
+			} else {
+				path_var.check_membership(&root_var, &leaf_var)
+			}
+			.unwrap();
+			assert!(res.value().unwrap());
+			assert!(cs.is_satisfied().unwrap());
+			cs.num_constraints()
+		};
 
-		let res = path_var.check_membership(&root_var, &leaf_var).unwrap();
-		assert!(res.cs().is_satisfied().unwrap());
-		assert!(res.value().unwrap());
+		assert!(synthesize(true) < synthesize(false));
+	}
+
+	/// serialized Bls381 `Fq` element `tag`, filling one Poseidon input
+	const fn domain_tag(tag: u8) -> [u8; 48] {
+		let mut bytes = [0u8; 48];
+		bytes[0] = tag;
+		bytes
+	}
+	const LEAF_TAG: [u8; 48] = domain_tag(1);
+	const NODE_TAG: [u8; 48] = domain_tag(2);
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct DomainConfig;
+	impl Config for DomainConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 3;
+		const LEAF_DOMAIN: Option<&'static [u8]> = Some(&LEAF_TAG);
+		const NODE_DOMAIN: Option<&'static [u8]> = Some(&NODE_TAG);
+	}
+
+	#[test]
+	fn should_match_native_root_with_domain_tags() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params = Rc::new(setup_params_x5_5(curve));
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = SparseMerkleTree::<DomainConfig>::new_sequential(params.clone(), params, &leaves)
+			.unwrap();
+		let root = smt.root().unwrap();
+		let path = smt.generate_membership_proof(2).unwrap();
+
+		for from_fields in [false, true] {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let path_var = PathVar::<_, _, _, _, { DomainConfig::HEIGHT as usize }>::new_witness(
+				cs.clone(),
+				|| Ok(path.clone()),
+			)
+			.unwrap();
+			let root_var = NodeVar::<Fq, DomainConfig, SMTCRHGadget, SMTCRHGadget>::new_witness(
+				cs.clone(),
+				|| Ok(root.clone()),
+			)
+			.unwrap();
+			let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[2])).unwrap();
+
+			let res = if from_fields {
+				path_var.check_membership_fields(&root_var, &leaf_var)
+			} else {
+				path_var.check_membership(&root_var, &leaf_var)
+			}
+			.unwrap();
+			assert!(res.value().unwrap());
+			assert!(cs.is_satisfied().unwrap());
+		}
+	}
+
+	#[test]
+	fn should_fail_path_creation_with_invalid_size() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params3 = setup_params_x5_3(curve);
+
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = SMT::new_sequential(inner_params, leaf_params, &leaves).unwrap();
+
+		// pass a size one less than tree HEIGHT
+		let res = smt.generate_membership_proof::<{ (SMTConfig::HEIGHT - 1) as usize }>(0);
+		assert!(matches!(
+			res,
+			Err(MerkleError::InvalidPathLength {
+				expected: 2,
+				found: 3
+			})
+		));
 	}
 
-	#[should_panic(expected = "assertion failed: `(left == right)`
-  left: `2`,
- right: `3`")]
 	#[test]
 	fn should_fail_membership_with_invalid_size() {
 		let rng = &mut test_rng();
@@ -449,9 +1711,9 @@ mod test {
 
 		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
 		let smt = SMT::new_sequential(inner_params.clone(), leaf_params.clone(), &leaves).unwrap();
-		let root = smt.root();
+		let root = smt.root().unwrap();
 		let path: Path<SMTConfig, { (SMTConfig::HEIGHT) as usize }> =
-			smt.generate_membership_proof(0);
+			smt.generate_membership_proof(0).unwrap();
 
 		let new_path = Path::<SMTConfig, { (SMTConfig::HEIGHT - 1) as usize }> {
 			path: [path.path[0].clone(), path.path[1].clone()],
@@ -467,8 +1729,373 @@ mod test {
 		let root_var = SMTNode::new_witness(cs.clone(), || Ok(root)).unwrap();
 		let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[0])).unwrap();
 
+		let res = path_var.check_membership(&root_var, &leaf_var);
+		assert!(matches!(
+			res,
+			Err(MerkleError::InvalidPathLength {
+				expected: 3,
+				found: 2
+			})
+		));
+	}
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct KeyedConfig;
+	impl Config for KeyedConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 254;
+	}
+
+	#[test]
+	fn should_verify_non_membership() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params3 = setup_params_x5_3(curve);
+
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let keys = vec![Fq::rand(rng), Fq::rand(rng)];
+		let absent = Fq::rand(rng);
+		let tree =
+			KeyedMerkleTree::<KeyedConfig, Fq>::new(inner_params, leaf_params, &keys).unwrap();
+		let root = tree.root();
+		let path = tree
+			.generate_proof::<{ KeyedConfig::HEIGHT as usize }>(&absent)
+			.unwrap();
+
+		let path_var = NonMembershipPathVar::<
+			_,
+			_,
+			SMTCRHGadget,
+			SMTCRHGadget,
+			{ KeyedConfig::HEIGHT as usize },
+		>::new_witness(cs.clone(), || Ok(path))
+		.unwrap();
+		let root_var = NodeVar::new_witness(cs.clone(), || Ok(root)).unwrap();
+		let key_var = FieldVar::new_witness(cs.clone(), || Ok(absent)).unwrap();
+
+		let res = path_var.check_non_membership(&root_var, &key_var).unwrap();
+		assert!(res.cs().is_satisfied().unwrap());
+		assert!(res.value().unwrap());
+
+		let res = path_var.check_membership(&root_var, &key_var).unwrap();
+		assert!(!res.value().unwrap());
+	}
+
+	#[test]
+	fn should_not_verify_non_membership_with_forged_empty_leaf() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params3 = setup_params_x5_3(curve);
+
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let keys = vec![Fq::rand(rng), Fq::rand(rng)];
+		let tree =
+			KeyedMerkleTree::<KeyedConfig, Fq>::new(inner_params, leaf_params.clone(), &keys)
+				.unwrap();
+		let root = tree.root();
+		// Claim the leaf of a present key as the empty leaf
+		let mut path = tree
+			.generate_proof::<{ KeyedConfig::HEIGHT as usize }>(&keys[0])
+			.unwrap();
+		path.empty_leaf = hash_leaf::<KeyedConfig, Fq>(leaf_params.as_ref(), &keys[0]).unwrap();
+
+		let path_var = NonMembershipPathVar::<
+			_,
+			_,
+			SMTCRHGadget,
+			SMTCRHGadget,
+			{ KeyedConfig::HEIGHT as usize },
+		>::new_witness(cs.clone(), || Ok(path))
+		.unwrap();
+		let root_var = NodeVar::new_witness(cs.clone(), || Ok(root)).unwrap();
+		let key_var = FieldVar::new_witness(cs.clone(), || Ok(keys[0])).unwrap();
+
+		let res = path_var.check_non_membership(&root_var, &key_var).unwrap();
+		assert!(!res.value().unwrap());
+
+		let res = path_var.check_membership(&root_var, &key_var).unwrap();
+		assert!(res.value().unwrap());
+	}
+
+	#[test]
+	fn should_verify_multi_path() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params3 = setup_params_x5_3(curve);
+
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = SMT::new_sequential(inner_params, leaf_params, &leaves).unwrap();
+		let root = smt.root().unwrap();
+		let proof = smt.generate_multi_membership_proof(&[0, 1, 3]).unwrap();
+
+		let path_var =
+			MultiPathVar::<_, _, SMTCRHGadget, SMTCRHGadget>::new_witness(cs.clone(), || Ok(proof))
+				.unwrap();
+		let root_var = SMTNode::new_witness(cs.clone(), || Ok(root)).unwrap();
+		let leaf_vars: Vec<FieldVar> = [leaves[0], leaves[1], leaves[3]]
+			.iter()
+			.map(|leaf| FieldVar::new_witness(cs.clone(), || Ok(*leaf)).unwrap())
+			.collect();
+
+		let res = path_var.check_membership(&root_var, &leaf_vars).unwrap();
+		assert!(res.cs().is_satisfied().unwrap());
+		assert!(res.value().unwrap());
+	}
+
+	#[test]
+	fn should_verify_quad_path() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params = Rc::new(setup_params_x5_5(curve));
+
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let leaves = vec![
+			Fq::rand(rng),
+			Fq::rand(rng),
+			Fq::rand(rng),
+			Fq::rand(rng),
+			Fq::rand(rng),
+		];
+		let tree =
+			QuadMerkleTree::<SMTConfig>::new_sequential(params.clone(), params, &leaves).unwrap();
+		let root = tree.root().unwrap();
+		let path = tree.generate_membership_proof(4).unwrap();
+
+		let path_var =
+			Path4Var::<_, _, _, _, { SMTConfig::HEIGHT as usize }>::new_witness(cs.clone(), || {
+				Ok(path)
+			})
+			.unwrap();
+		let root_var = SMTNode::new_witness(cs.clone(), || Ok(root)).unwrap();
+		let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[4])).unwrap();
+
+		let index_var = FieldVar::new_input(cs.clone(), || Ok(Fq::from(4u64))).unwrap();
+
+		let res = path_var.check_membership(&root_var, &leaf_var).unwrap();
+		res.enforce_equal(&Boolean::TRUE).unwrap();
+		path_var
+			.get_index()
+			.unwrap()
+			.enforce_equal(&index_var)
+			.unwrap();
+		assert!(cs.is_satisfied().unwrap());
+		assert!(res.value().unwrap());
+
+		// the same path does not open the leaf at another index
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let path = tree.generate_membership_proof(4).unwrap();
+		let path_var =
+			Path4Var::<_, _, _, _, { SMTConfig::HEIGHT as usize }>::new_witness(cs.clone(), || {
+				Ok(path)
+			})
+			.unwrap();
+		let index_var = FieldVar::new_input(cs.clone(), || Ok(Fq::from(5u64))).unwrap();
+		path_var
+			.get_index()
+			.unwrap()
+			.enforce_equal(&index_var)
+			.unwrap();
+		assert!(!cs.is_satisfied().unwrap());
+	}
+
+	#[test]
+	fn should_verify_compact_path() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params3 = setup_params_x5_3(curve);
+
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+		let index = 2;
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = SMT::new_sequential(inner_params, leaf_params, &leaves).unwrap();
+		let root = smt.root().unwrap();
+		let path = smt.generate_membership_proof(index).unwrap();
+		let compact = CompactPath::from_path(&path, &leaves[index as usize]).unwrap();
+
+		let path_var = CompactPathVar::<_, _, _, _, { SMTConfig::HEIGHT as usize }>::new_witness(
+			cs.clone(),
+			|| Ok(compact),
+		)
+		.unwrap();
+		let root_var = SMTNode::new_witness(cs.clone(), || Ok(root)).unwrap();
+		let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[index as usize])).unwrap();
+
+		let res = path_var.check_membership(&root_var, &leaf_var).unwrap();
+		let index_var = path_var.get_index().unwrap();
+		assert!(res.cs().is_satisfied().unwrap());
+		assert!(res.value().unwrap());
+		assert_eq!(index_var.value().unwrap(), Fq::from(index));
+	}
+
+	#[test]
+	fn should_verify_consistency_proof() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params = Rc::new(setup_params_x5_3(curve));
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let mut smt = SMT::new_sequential(params.clone(), params.clone(), &leaves[..1]).unwrap();
+		let old_root = smt.root().unwrap();
+		let appended: BTreeMap<u32, Fq> =
+			vec![(1, leaves[1]), (2, leaves[2])].into_iter().collect();
+		smt.insert_batch(&appended).unwrap();
+		let new_root = smt.root().unwrap();
+		let proof = smt
+			.generate_consistency_proof::<{ SMTConfig::HEIGHT as usize }>(1, 3)
+			.unwrap();
+
+		let proof_var =
+			ConsistencyProofVar::<_, _, _, _, { SMTConfig::HEIGHT as usize }>::new_witness(
+				cs.clone(),
+				|| Ok(proof),
+			)
+			.unwrap();
+		let old_root_var = SMTNode::new_input(cs.clone(), || Ok(old_root)).unwrap();
+		let new_root_var = SMTNode::new_input(cs.clone(), || Ok(new_root)).unwrap();
+
+		let res = proof_var
+			.check_consistency(&old_root_var, &new_root_var)
+			.unwrap();
+		assert!(res.cs().is_satisfied().unwrap());
+		assert!(res.value().unwrap());
+		assert_eq!(
+			proof_var.old_size().unwrap().value().unwrap(),
+			Fq::from(1u64)
+		);
+		assert_eq!(
+			proof_var.new_size().unwrap().value().unwrap(),
+			Fq::from(3u64)
+		);
+
+		let swapped = proof_var
+			.check_consistency(&new_root_var, &old_root_var)
+			.unwrap();
+		assert!(!swapped.value().unwrap());
+
+		// an old tree whose empty leaf next to the last one was replaced
+		let padded = vec![leaves[0], Fq::rand(rng)];
+		let padded_root = SMT::new_sequential(params.clone(), params.clone(), &padded)
+			.unwrap()
+			.root()
+			.unwrap();
+		let padded_root_var = SMTNode::new_input(cs.clone(), || Ok(padded_root)).unwrap();
+		let padded = proof_var
+			.check_consistency(&padded_root_var, &new_root_var)
+			.unwrap();
+		assert!(!padded.value().unwrap());
+
+		let mut empty_old = smt
+			.generate_consistency_proof::<{ SMTConfig::HEIGHT as usize }>(1, 3)
+			.unwrap();
+		empty_old.old_size = 0;
+		let res: Result<
+			ConsistencyProofVar<_, _, SMTCRHGadget, SMTCRHGadget, { SMTConfig::HEIGHT as usize }>,
+			_,
+		> = ConsistencyProofVar::new_witness(cs, || Ok(empty_old));
+		assert!(res.is_err());
+	}
+
+	#[test]
+	fn should_verify_update_path() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params3 = setup_params_x5_3(curve);
+
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let mut smt = SMT::new_sequential(inner_params, leaf_params, &leaves[..2]).unwrap();
+		let old_root = smt.root().unwrap();
+		let path = smt
+			.generate_compact_membership_proof::<{ SMTConfig::HEIGHT as usize }>(2)
+			.unwrap();
+		let appended: BTreeMap<u32, Fq> = vec![(2, leaves[2])].into_iter().collect();
+		smt.insert_batch(&appended).unwrap();
+		let new_root = smt.root().unwrap();
+
+		let path_var = UpdatePathVar::<_, _, _, _, { SMTConfig::HEIGHT as usize }>::new_witness(
+			cs.clone(),
+			|| Ok(path),
+		)
+		.unwrap();
+		let old_root_var = SMTNode::new_witness(cs.clone(), || Ok(old_root)).unwrap();
+		let new_root_var = SMTNode::new_witness(cs.clone(), || Ok(new_root)).unwrap();
+		let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[2])).unwrap();
+
+		let res = path_var
+			.check_update(&old_root_var, &new_root_var, &leaf_var)
+			.unwrap();
+		assert!(res.cs().is_satisfied().unwrap());
+		assert!(res.value().unwrap());
+		assert_eq!(
+			path_var.get_index().unwrap().value().unwrap(),
+			Fq::from(2u64)
+		);
+
+		// the position is no longer empty under the new root
+		let (is_empty, _) = path_var.update_root(&new_root_var, &leaf_var).unwrap();
+		assert!(!is_empty.value().unwrap());
+	}
+
+	#[test]
+	fn should_verify_mmr_path() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params3 = setup_params_x5_3(curve);
+
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+		let index = 5;
+		let cs = ConstraintSystem::<Fq>::new_ref();
+
+		let leaves: Vec<Fq> = (0..7).map(|_| Fq::rand(rng)).collect();
+		let mmr =
+			MerkleMountainRange::<SMTConfig>::new_sequential(inner_params, leaf_params, &leaves)
+				.unwrap();
+		let root = mmr.root().unwrap();
+		let path = mmr.generate_membership_proof(index).unwrap();
+
+		let path_var = MmrPathVar::new_witness(cs.clone(), || Ok(path)).unwrap();
+		let root_var = SMTNode::new_witness(cs.clone(), || Ok(root)).unwrap();
+		let leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[index as usize])).unwrap();
+		let wrong_leaf_var = FieldVar::new_witness(cs.clone(), || Ok(leaves[4])).unwrap();
+
 		let res = path_var.check_membership(&root_var, &leaf_var).unwrap();
+		let wrong = path_var
+			.check_membership(&root_var, &wrong_leaf_var)
+			.unwrap();
 		assert!(res.cs().is_satisfied().unwrap());
 		assert!(res.value().unwrap());
+		assert!(!wrong.value().unwrap());
 	}
 }
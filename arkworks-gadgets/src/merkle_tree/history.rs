@@ -0,0 +1,155 @@
+use super::{storage::MerkleStorage, Config, MerkleError, Node, SparseMerkleTree};
+use ark_ff::{to_bytes, PrimeField};
+use ark_std::vec::Vec;
+
+/// Window of the last `N` roots of a tree, the way anchors keep them to accept
+/// proofs made against a root that was replaced recently.
+#[derive(Clone)]
+pub struct RootHistory<P: Config, const N: usize> {
+	roots: Vec<Node<P>>,
+	/// position of the latest root in `roots`
+	current: usize,
+}
+
+impl<P: Config, const N: usize> Default for RootHistory<P, N> {
+	fn default() -> Self {
+		Self {
+			roots: Vec::with_capacity(N),
+			current: 0,
+		}
+	}
+}
+
+impl<P: Config, const N: usize> RootHistory<P, N> {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// add `root` as the latest root, evicting the oldest one once the window
+	/// is full
+	pub fn insert(&mut self, root: Node<P>) {
+		if N == 0 {
+			return;
+		}
+		if self.roots.len() < N {
+			self.roots.push(root);
+			self.current = self.roots.len() - 1;
+		} else {
+			self.current = (self.current + 1) % N;
+			self.roots[self.current] = root;
+		}
+	}
+
+	/// add the current root of `tree`
+	pub fn record<S: MerkleStorage<P>>(
+		&mut self,
+		tree: &SparseMerkleTree<P, S>,
+	) -> Result<(), MerkleError> {
+		self.insert(tree.root()?);
+		Ok(())
+	}
+
+	/// obtain the latest root, if any was recorded
+	pub fn latest(&self) -> Option<&Node<P>> {
+		self.roots.get(self.current)
+	}
+
+	/// true iff `root` is one of the roots in the window
+	pub fn is_known_root(&self, root: &Node<P>) -> bool {
+		self.roots.iter().any(|r| r == root)
+	}
+
+	/// roots in the window, from the latest to the oldest
+	pub fn iter(&self) -> impl Iterator<Item = &Node<P>> {
+		let split = if self.roots.is_empty() {
+			0
+		} else {
+			self.current + 1
+		};
+		let (newer, older) = self.roots.split_at(split);
+		newer.iter().rev().chain(older.iter().rev())
+	}
+
+	/// latest `M` roots in the window as the root set of the `set::membership`
+	/// gadgets, starting with the latest root. Fails if fewer than `M` roots
+	/// were recorded, as padding slots would be accepted by the gadgets.
+	pub fn root_set<F: PrimeField, const M: usize>(&self) -> Result<[F; M], MerkleError> {
+		if self.roots.len() < M {
+			return Err(MerkleError::NotEnoughRoots {
+				expected: M,
+				found: self.roots.len(),
+			});
+		}
+		let mut root_set = [F::default(); M];
+		for (slot, root) in root_set.iter_mut().zip(self.iter()) {
+			*slot = F::from_le_bytes_mod_order(&to_bytes![root]?);
+		}
+
+		Ok(root_set)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::RootHistory;
+	use crate::{
+		merkle_tree::{Config, MerkleError, SparseMerkleTree},
+		poseidon::CRH as PoseidonCRH,
+		set::membership::SetMembership,
+	};
+	use ark_bls12_381::Fq;
+	use ark_ff::UniformRand;
+	use ark_std::{collections::BTreeMap, rc::Rc, test_rng};
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	type SMTCRH = PoseidonCRH<Fq>;
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct SMTConfig;
+	impl Config for SMTConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	#[test]
+	fn should_keep_last_roots() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let mut smt = SparseMerkleTree::<SMTConfig>::blank(inner_params, leaf_params).unwrap();
+		let mut history = RootHistory::<SMTConfig, 2>::new();
+		let mut roots = Vec::new();
+		for i in 0..3 {
+			let mut leaf = BTreeMap::new();
+			leaf.insert(i, Fq::rand(rng));
+			smt.insert_batch(&leaf).unwrap();
+			history.record(&smt).unwrap();
+			roots.push(smt.root().unwrap());
+		}
+
+		assert!(!history.is_known_root(&roots[0]));
+		assert!(history.is_known_root(&roots[1]));
+		assert!(history.is_known_root(&roots[2]));
+		assert_eq!(history.latest(), Some(&roots[2]));
+
+		assert!(matches!(
+			history.root_set::<Fq, 3>(),
+			Err(MerkleError::NotEnoughRoots {
+				expected: 3,
+				found: 2
+			})
+		));
+		let root_set = history.root_set::<Fq, 2>().unwrap();
+		let latest = roots[2].clone().inner().unwrap();
+		assert_eq!(root_set[0], latest);
+		assert_eq!(root_set[1], roots[1].clone().inner().unwrap());
+
+		let set_private = SetMembership::generate_secrets(&latest, &root_set).unwrap();
+		assert!(SetMembership::check(&latest, &root_set, &set_private).unwrap());
+	}
+}
@@ -0,0 +1,230 @@
+use super::{
+	gen_empty_hashes, hash_inner_node, hash_leaf, Config, InnerParameters, LeafParameters,
+	MerkleError, Node, Path,
+};
+use ark_ff::ToBytes;
+use ark_std::{borrow::Borrow, rc::Rc, vec::Vec};
+use core::convert::TryInto;
+
+/// Append-only Merkle tree that only keeps the frontier of filled subtrees.
+///
+/// Leaves are inserted from left to right, the same way the on-chain anchors
+/// do it, so every insertion costs `P::HEIGHT` inner hashes and the tree
+/// stores `O(P::HEIGHT)` nodes regardless of how many leaves were added.
+/// Roots are identical to the ones of a `SparseMerkleTree` filled with the
+/// same leaves.
+pub struct IncrementalMerkleTree<P: Config> {
+	/// left-most non-empty node on every level, from the leaves up
+	filled_subtrees: Vec<Node<P>>,
+	/// path of the most recently inserted leaf
+	latest_path: Vec<(Node<P>, Node<P>)>,
+	empty_hashes: Vec<Node<P>>,
+	root: Node<P>,
+	next_index: u64,
+	leaf_params: Rc<LeafParameters<P>>,
+	inner_params: Rc<InnerParameters<P>>,
+}
+
+impl<P: Config> IncrementalMerkleTree<P> {
+	/// obtain an empty tree
+	pub fn blank(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+	) -> Result<Self, MerkleError> {
+		let empty_hashes = gen_empty_hashes::<P>(leaf_params.borrow(), inner_params.borrow())?;
+
+		let filled_subtrees = empty_hashes[..P::HEIGHT as usize].to_vec();
+		let root = empty_hashes[P::HEIGHT as usize].clone();
+
+		Ok(IncrementalMerkleTree {
+			filled_subtrees,
+			latest_path: Vec::new(),
+			empty_hashes,
+			root,
+			next_index: 0,
+			leaf_params,
+			inner_params,
+		})
+	}
+
+	/// initialize a tree with the given leaves, inserted in order
+	pub fn new_sequential<L: ToBytes>(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+		leaves: &[L],
+	) -> Result<Self, MerkleError> {
+		let mut tree = Self::blank(inner_params, leaf_params)?;
+		for leaf in leaves {
+			tree.insert(leaf)?;
+		}
+
+		Ok(tree)
+	}
+
+	/// append a leaf at the next free index and return that index
+	pub fn insert<L: ToBytes>(&mut self, leaf: &L) -> Result<u64, MerkleError> {
+		if self.next_index >= (1u64 << P::HEIGHT) {
+			return Err(MerkleError::TreeFull);
+		}
+
+		let index = self.next_index;
+		let mut current_index = index;
+		let mut current = hash_leaf::<P, L>(self.leaf_params.borrow(), leaf)?;
+		let mut path = Vec::with_capacity(P::HEIGHT as usize);
+
+		for level in 0..P::HEIGHT as usize {
+			let (left, right) = if current_index % 2 == 0 {
+				self.filled_subtrees[level] = current.clone();
+				(current, self.empty_hashes[level].clone())
+			} else {
+				(self.filled_subtrees[level].clone(), current)
+			};
+
+			current = hash_inner_node::<P>(self.inner_params.borrow(), &left, &right)?;
+			path.push((left, right));
+			current_index >>= 1;
+		}
+
+		self.root = current;
+		self.latest_path = path;
+		self.next_index += 1;
+
+		Ok(index)
+	}
+
+	#[inline]
+	/// obtain the root hash
+	pub fn root(&self) -> Node<P> {
+		self.root.clone()
+	}
+
+	#[inline]
+	/// index the next inserted leaf will be stored at
+	pub fn next_index(&self) -> u64 {
+		self.next_index
+	}
+
+	/// generate a membership proof for the most recently inserted leaf
+	pub fn latest_membership_proof<const N: usize>(&self) -> Result<Path<P, N>, MerkleError> {
+		if self.next_index == 0 {
+			return Err(MerkleError::EmptyTree);
+		}
+
+		Ok(Path {
+			path: self
+				.latest_path
+				.clone()
+				.try_into()
+				.map_err(
+					|v: Vec<(Node<P>, Node<P>)>| MerkleError::InvalidPathLength {
+						expected: N,
+						found: v.len(),
+					},
+				)?,
+			inner_params: Rc::clone(&self.inner_params),
+			leaf_params: Rc::clone(&self.leaf_params),
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::IncrementalMerkleTree;
+	use crate::{
+		merkle_tree::{Config, MerkleError, SparseMerkleTree},
+		poseidon::CRH as PoseidonCRH,
+	};
+	use ark_bls12_381::Fq;
+	use ark_ff::UniformRand;
+	use ark_std::{rc::Rc, test_rng};
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	type SMTCRH = PoseidonCRH<Fq>;
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct SMTConfig;
+	impl Config for SMTConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	#[test]
+	fn should_match_sparse_tree_roots() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let mut imt =
+			IncrementalMerkleTree::<SMTConfig>::blank(inner_params.clone(), leaf_params.clone())
+				.unwrap();
+
+		for (i, leaf) in leaves.iter().enumerate() {
+			let index = imt.insert(leaf).unwrap();
+			assert_eq!(index, i as u64);
+
+			let smt = SparseMerkleTree::<SMTConfig>::new_sequential(
+				inner_params.clone(),
+				leaf_params.clone(),
+				&leaves[..=i],
+			)
+			.unwrap();
+			assert_eq!(imt.root(), smt.root().unwrap());
+		}
+		assert_eq!(imt.next_index(), leaves.len() as u64);
+	}
+
+	#[test]
+	fn should_generate_path_for_latest_leaf() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let imt = IncrementalMerkleTree::<SMTConfig>::new_sequential(
+			inner_params.clone(),
+			leaf_params.clone(),
+			&leaves,
+		)
+		.unwrap();
+		let smt = SparseMerkleTree::<SMTConfig>::new_sequential(inner_params, leaf_params, &leaves)
+			.unwrap();
+
+		let path = imt
+			.latest_membership_proof::<{ SMTConfig::HEIGHT as usize }>()
+			.unwrap();
+		let smt_path = smt
+			.generate_membership_proof::<{ SMTConfig::HEIGHT as usize }>(2)
+			.unwrap();
+
+		assert!(path.path == smt_path.path);
+		assert!(path.check_membership(&imt.root(), &leaves[2]).unwrap());
+	}
+
+	#[test]
+	fn should_fail_to_insert_into_full_tree() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let mut imt = IncrementalMerkleTree::<SMTConfig>::blank(inner_params, leaf_params).unwrap();
+		assert!(matches!(
+			imt.latest_membership_proof::<{ SMTConfig::HEIGHT as usize }>(),
+			Err(MerkleError::EmptyTree)
+		));
+
+		for _ in 0..(1 << SMTConfig::HEIGHT) {
+			imt.insert(&Fq::rand(rng)).unwrap();
+		}
+		let res = imt.insert(&Fq::rand(rng));
+		assert!(matches!(res, Err(MerkleError::TreeFull)));
+	}
+}
@@ -0,0 +1,347 @@
+use super::{
+	gen_empty_hashes, hash_inner_node, hash_leaf, Config, InnerParameters, LeafParameters,
+	MerkleError, Node,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_std::{borrow::Borrow, collections::BTreeMap, marker::PhantomData, rc::Rc, vec::Vec};
+use core::convert::TryInto;
+
+/// Sparse Merkle tree holding a set of field elements, where every element is
+/// stored at the leaf selected by its own little-endian bits.
+///
+/// The tree covers the lowest `P::HEIGHT` bits of a key, so a config with
+/// `HEIGHT` equal to the bit size of `F` gives every key its own leaf. A
+/// present key is stored as the hash of the key, an absent one as the empty
+/// leaf, which makes both membership and non-membership provable with the
+/// same kind of path.
+///
+/// With a lower `HEIGHT` several keys share a leaf. Only the first of them can
+/// be inserted, and the absence of the others cannot be proven while it is in
+/// the set.
+pub struct KeyedMerkleTree<P: Config, F: PrimeField> {
+	/// non-empty nodes, keyed by their level (0 for leaves) and the key bits
+	/// above that level
+	nodes: BTreeMap<(u8, F::BigInt), Node<P>>,
+	empty_hashes: Vec<Node<P>>,
+	leaf_params: Rc<LeafParameters<P>>,
+	inner_params: Rc<InnerParameters<P>>,
+	field: PhantomData<F>,
+}
+
+impl<P: Config, F: PrimeField> KeyedMerkleTree<P, F> {
+	/// obtain an empty tree
+	pub fn blank(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+	) -> Result<Self, MerkleError> {
+		if P::HEIGHT as usize > F::size_in_bits() {
+			return Err(MerkleError::KeyTooShort {
+				height: P::HEIGHT as usize,
+				key_bits: F::size_in_bits(),
+			});
+		}
+		let empty_hashes = gen_empty_hashes::<P>(leaf_params.borrow(), inner_params.borrow())?;
+
+		Ok(KeyedMerkleTree {
+			nodes: BTreeMap::new(),
+			empty_hashes,
+			leaf_params,
+			inner_params,
+			field: PhantomData,
+		})
+	}
+
+	/// initialize a tree holding the given keys
+	pub fn new(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+		keys: &[F],
+	) -> Result<Self, MerkleError> {
+		let mut tree = Self::blank(inner_params, leaf_params)?;
+		for key in keys {
+			tree.insert(key)?;
+		}
+
+		Ok(tree)
+	}
+
+	/// add `key` to the set, failing if its leaf already holds another key
+	pub fn insert(&mut self, key: &F) -> Result<(), MerkleError> {
+		let leaf = hash_leaf::<P, F>(self.leaf_params.borrow(), key)?;
+		if leaf == self.empty_hashes[0] {
+			return Err(MerkleError::InvalidKey);
+		}
+		let bits = key_bits::<P, F>(key)?;
+		let current = self.node(&bits, 0);
+		if current != self.empty_hashes[0] && current != leaf {
+			return Err(MerkleError::OccupiedLeaf);
+		}
+		self.update(&bits, leaf)
+	}
+
+	/// remove `key` from the set, returning whether it was present
+	pub fn delete(&mut self, key: &F) -> Result<bool, MerkleError> {
+		if !self.contains(key)? {
+			return Ok(false);
+		}
+		let empty_leaf = self.empty_hashes[0].clone();
+		self.update(&key_bits::<P, F>(key)?, empty_leaf)?;
+
+		Ok(true)
+	}
+
+	/// check whether `key` is in the set
+	pub fn contains(&self, key: &F) -> Result<bool, MerkleError> {
+		let bits = key_bits::<P, F>(key)?;
+		let leaf = hash_leaf::<P, F>(self.leaf_params.borrow(), key)?;
+
+		Ok(self.node(&bits, 0) == leaf)
+	}
+
+	#[inline]
+	/// obtain the root hash
+	pub fn root(&self) -> Node<P> {
+		self.node(&[], P::HEIGHT as usize)
+	}
+
+	/// generate the path of the leaf selected by `key`, which proves either
+	/// membership or non-membership of `key`
+	pub fn generate_proof<const N: usize>(&self, key: &F) -> Result<KeyedPath<P, N>, MerkleError> {
+		let bits = key_bits::<P, F>(key)?;
+
+		let mut siblings = Vec::with_capacity(N);
+		let mut sibling_bits = bits.clone();
+		for level in 0..P::HEIGHT as usize {
+			sibling_bits[level] = !bits[level];
+			siblings.push(self.node(&sibling_bits[level..], level));
+			sibling_bits[level] = bits[level];
+		}
+
+		Ok(KeyedPath {
+			siblings: siblings.try_into().map_err(|v: Vec<Node<P>>| {
+				MerkleError::InvalidPathLength {
+					expected: N,
+					found: v.len(),
+				}
+			})?,
+			empty_leaf: self.empty_hashes[0].clone(),
+			inner_params: Rc::clone(&self.inner_params),
+			leaf_params: Rc::clone(&self.leaf_params),
+		})
+	}
+
+	/// node at `level` whose position is given by the key bits from `level` up
+	fn node(&self, bits: &[bool], level: usize) -> Node<P> {
+		self.nodes
+			.get(&node_index::<F>(bits, level))
+			.cloned()
+			.unwrap_or_else(|| self.empty_hashes[level].clone())
+	}
+
+	/// store `leaf` at the position given by `bits` and rehash its path,
+	/// dropping every node that became empty
+	fn update(&mut self, bits: &[bool], leaf: Node<P>) -> Result<(), MerkleError> {
+		let mut current = leaf;
+		let mut sibling_bits = bits.to_vec();
+		for level in 0..=P::HEIGHT as usize {
+			let index = node_index::<F>(&bits[level..], level);
+			if current == self.empty_hashes[level] {
+				self.nodes.remove(&index);
+			} else {
+				self.nodes.insert(index, current.clone());
+			}
+
+			if level == P::HEIGHT as usize {
+				break;
+			}
+			sibling_bits[level] = !bits[level];
+			let sibling = self.node(&sibling_bits[level..], level);
+			sibling_bits[level] = bits[level];
+
+			current = if bits[level] {
+				hash_inner_node::<P>(self.inner_params.borrow(), &sibling, &current)?
+			} else {
+				hash_inner_node::<P>(self.inner_params.borrow(), &current, &sibling)?
+			};
+		}
+
+		Ok(())
+	}
+}
+
+/// Path of one leaf of a `KeyedMerkleTree`
+#[derive(Clone)]
+pub struct KeyedPath<P: Config, const N: usize> {
+	/// sibling of the node on every level, from the leaves up
+	pub(crate) siblings: [Node<P>; N],
+	pub(crate) empty_leaf: Node<P>,
+	pub(crate) leaf_params: Rc<LeafParameters<P>>,
+	pub(crate) inner_params: Rc<InnerParameters<P>>,
+}
+
+impl<P: Config, const N: usize> KeyedPath<P, N> {
+	/// verify that `key` is in the set committed to by `root_hash`
+	pub fn check_membership<F: PrimeField>(
+		&self,
+		root_hash: &Node<P>,
+		key: &F,
+	) -> Result<bool, MerkleError> {
+		let leaf = hash_leaf::<P, F>(self.leaf_params.borrow(), key)?;
+		let root = self.root_hash(key, leaf)?;
+
+		Ok(root_hash == &root)
+	}
+
+	/// verify that `key` is not in the set committed to by `root_hash`
+	pub fn check_non_membership<F: PrimeField>(
+		&self,
+		root_hash: &Node<P>,
+		key: &F,
+	) -> Result<bool, MerkleError> {
+		let root = self.root_hash(key, self.empty_leaf.clone())?;
+
+		Ok(root_hash == &root)
+	}
+
+	/// Return hash of root computed by the path, with `leaf` stored at `key`
+	pub fn root_hash<F: PrimeField>(&self, key: &F, leaf: Node<P>) -> Result<Node<P>, MerkleError> {
+		if N != P::HEIGHT as usize {
+			return Err(MerkleError::InvalidPathLength {
+				expected: P::HEIGHT as usize,
+				found: N,
+			});
+		}
+		let bits = key_bits::<P, F>(key)?;
+
+		let mut prev = leaf;
+		for (sibling, bit) in self.siblings.iter().zip(bits) {
+			prev = if bit {
+				hash_inner_node::<P>(self.inner_params.borrow(), sibling, &prev)?
+			} else {
+				hash_inner_node::<P>(self.inner_params.borrow(), &prev, sibling)?
+			};
+		}
+
+		Ok(prev)
+	}
+}
+
+/// Returns the lowest `P::HEIGHT` little-endian bits of `key`.
+fn key_bits<P: Config, F: PrimeField>(key: &F) -> Result<Vec<bool>, MerkleError> {
+	let height = P::HEIGHT as usize;
+	if height > F::size_in_bits() {
+		return Err(MerkleError::KeyTooShort {
+			height,
+			key_bits: F::size_in_bits(),
+		});
+	}
+	let mut bits = key.into_repr().to_bits_le();
+	bits.truncate(height);
+
+	Ok(bits)
+}
+
+/// Returns the storage index of a node, given its level and the key bits above
+/// it.
+#[inline]
+fn node_index<F: PrimeField>(bits: &[bool], level: usize) -> (u8, F::BigInt) {
+	(level as u8, F::BigInt::from_bits_le(bits))
+}
+
+#[cfg(test)]
+mod test {
+	use super::KeyedMerkleTree;
+	use crate::{
+		merkle_tree::{Config, MerkleError},
+		poseidon::CRH as PoseidonCRH,
+	};
+	use ark_bls12_381::Fq;
+	use ark_ff::{Field, UniformRand};
+	use ark_std::{rc::Rc, test_rng};
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	type SMTCRH = PoseidonCRH<Fq>;
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct KeyedConfig;
+	impl Config for KeyedConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 254;
+	}
+
+	const HEIGHT: usize = KeyedConfig::HEIGHT as usize;
+
+	#[test]
+	fn should_prove_membership_and_non_membership() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let keys = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let absent = Fq::rand(rng);
+		let tree =
+			KeyedMerkleTree::<KeyedConfig, Fq>::new(inner_params, leaf_params, &keys).unwrap();
+		let root = tree.root();
+
+		let proof = tree.generate_proof::<HEIGHT>(&keys[1]).unwrap();
+		assert!(proof.check_membership(&root, &keys[1]).unwrap());
+		assert!(!proof.check_non_membership(&root, &keys[1]).unwrap());
+
+		let proof = tree.generate_proof::<HEIGHT>(&absent).unwrap();
+		assert!(proof.check_non_membership(&root, &absent).unwrap());
+		assert!(!proof.check_membership(&root, &absent).unwrap());
+	}
+
+	#[test]
+	fn should_restore_root_after_delete() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let keys = vec![Fq::rand(rng), Fq::rand(rng)];
+		let mut tree =
+			KeyedMerkleTree::<KeyedConfig, Fq>::new(inner_params, leaf_params, &keys[..1]).unwrap();
+		let root = tree.root();
+
+		tree.insert(&keys[1]).unwrap();
+		assert!(tree.contains(&keys[1]).unwrap());
+		assert!(tree.delete(&keys[1]).unwrap());
+		assert!(!tree.delete(&keys[1]).unwrap());
+		assert_eq!(tree.root(), root);
+	}
+
+	#[test]
+	fn should_reject_keys_sharing_a_leaf() {
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		// both keys have the same lowest `HEIGHT` bits
+		let key = Fq::from(7u64);
+		let colliding = key + Fq::from(2u64).pow([HEIGHT as u64]);
+		let mut tree =
+			KeyedMerkleTree::<KeyedConfig, Fq>::new(inner_params, leaf_params, &[key]).unwrap();
+		let root = tree.root();
+
+		assert!(matches!(
+			tree.insert(&colliding),
+			Err(MerkleError::OccupiedLeaf)
+		));
+		assert_eq!(tree.root(), root);
+		assert!(tree.contains(&key).unwrap());
+		assert!(!tree.delete(&colliding).unwrap());
+
+		let proof = tree.generate_proof::<HEIGHT>(&key).unwrap();
+		assert!(proof.check_membership(&root, &key).unwrap());
+		assert!(!proof.check_non_membership(&root, &key).unwrap());
+		let proof = tree.generate_proof::<HEIGHT>(&colliding).unwrap();
+		assert!(!proof.check_non_membership(&root, &colliding).unwrap());
+	}
+}
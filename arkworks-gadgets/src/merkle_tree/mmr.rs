@@ -0,0 +1,299 @@
+use super::{
+	hash_inner_node, hash_leaf, Config, InnerParameters, LeafParameters, MerkleError, Node,
+};
+use ark_ff::ToBytes;
+use ark_std::{borrow::Borrow, rc::Rc, vec::Vec};
+
+/// Merkle Mountain Range: an append-only list of perfect binary trees whose
+/// heights follow the set bits of the number of leaves.
+///
+/// Nodes are stored in post-order, so appending a leaf only pushes the leaf
+/// and the roots of the mountains it completes. The root of the range is
+/// obtained by bagging the peaks from right to left with `hash_inner_node`,
+/// which lets any `Config` of a `SparseMerkleTree` be reused as it is.
+pub struct MerkleMountainRange<P: Config> {
+	/// every node, in the order it was appended
+	nodes: Vec<Node<P>>,
+	leaf_count: u64,
+	leaf_params: Rc<LeafParameters<P>>,
+	inner_params: Rc<InnerParameters<P>>,
+}
+
+impl<P: Config> MerkleMountainRange<P> {
+	/// obtain an empty range
+	pub fn new(inner_params: Rc<InnerParameters<P>>, leaf_params: Rc<LeafParameters<P>>) -> Self {
+		MerkleMountainRange {
+			nodes: Vec::new(),
+			leaf_count: 0,
+			leaf_params,
+			inner_params,
+		}
+	}
+
+	pub fn new_sequential<L: ToBytes>(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+		leaves: &[L],
+	) -> Result<Self, MerkleError> {
+		let mut mmr = Self::new(inner_params, leaf_params);
+		for leaf in leaves {
+			mmr.append(leaf)?;
+		}
+
+		Ok(mmr)
+	}
+
+	/// append `leaf`, returning its index
+	pub fn append<L: ToBytes>(&mut self, leaf: &L) -> Result<u64, MerkleError> {
+		let index = self.leaf_count;
+		let mut node = hash_leaf::<P, L>(self.leaf_params.borrow(), leaf)?;
+		self.nodes.push(node.clone());
+
+		// Every trailing one of the index closes a mountain of that height.
+		for height in 0..index.trailing_ones() {
+			let left_root = (self.nodes.len() - 1) - mountain_size(height) as usize;
+			node = hash_inner_node::<P>(self.inner_params.borrow(), &self.nodes[left_root], &node)?;
+			self.nodes.push(node.clone());
+		}
+		self.leaf_count += 1;
+
+		Ok(index)
+	}
+
+	#[inline]
+	/// obtain the number of appended leaves
+	pub fn leaf_count(&self) -> u64 {
+		self.leaf_count
+	}
+
+	/// obtain the root of every mountain, from the highest to the lowest
+	pub fn peaks(&self) -> Vec<Node<P>> {
+		self.mountains()
+			.map(|(offset, height)| self.nodes[peak_position(offset, height)].clone())
+			.collect()
+	}
+
+	/// obtain the root, bagging the peaks
+	pub fn root(&self) -> Result<Node<P>, MerkleError> {
+		bag_peaks::<P>(self.inner_params.borrow(), &self.peaks())
+	}
+
+	/// generate a membership proof (does not check the data point)
+	pub fn generate_membership_proof(&self, index: u64) -> Result<MmrPath<P>, MerkleError> {
+		if index >= self.leaf_count {
+			return Err(MerkleError::IndexOutOfRange(index));
+		}
+
+		let mut left_peaks = Vec::new();
+		let mut right_peaks = Vec::new();
+		let mut mountain = None;
+		let mut first_leaf = 0u64;
+		for (offset, height) in self.mountains() {
+			let peak = self.nodes[peak_position(offset, height)].clone();
+			let leaves = 1u64 << height;
+			if mountain.is_some() {
+				right_peaks.push(peak);
+			} else if index < first_leaf + leaves {
+				mountain = Some((offset, height, index - first_leaf));
+			} else {
+				left_peaks.push(peak);
+				first_leaf += leaves;
+			}
+		}
+		let (mut offset, height, local_index) =
+			mountain.ok_or(MerkleError::IndexOutOfRange(index))?;
+
+		// Walk down from the peak, keeping the root of the other subtree.
+		let mut siblings = Vec::with_capacity(height as usize);
+		for level in (1..=height).rev() {
+			let child_size = mountain_size(level - 1);
+			if (local_index >> (level - 1)) & 1 == 1 {
+				siblings.push(self.nodes[(offset + child_size - 1) as usize].clone());
+				offset += child_size;
+			} else {
+				siblings.push(self.nodes[(offset + 2 * child_size - 1) as usize].clone());
+			}
+		}
+		siblings.reverse();
+
+		Ok(MmrPath {
+			index: local_index,
+			siblings,
+			left_peaks,
+			right_peaks,
+			leaf_params: Rc::clone(&self.leaf_params),
+			inner_params: Rc::clone(&self.inner_params),
+		})
+	}
+
+	/// offset of the first node and height of every mountain, from the
+	/// highest to the lowest
+	fn mountains(&self) -> impl Iterator<Item = (u64, u32)> {
+		let leaf_count = self.leaf_count;
+		let mut offset = 0u64;
+		(0..64u32)
+			.rev()
+			.filter(move |height| (leaf_count >> height) & 1 == 1)
+			.map(move |height| {
+				let mountain = (offset, height);
+				offset += mountain_size(height);
+				mountain
+			})
+	}
+}
+
+/// Membership proof in a `MerkleMountainRange`, made of the path up to the
+/// peak of the leaf's mountain and of the other peaks
+#[derive(Clone)]
+pub struct MmrPath<P: Config> {
+	/// index of the leaf within its mountain
+	pub(crate) index: u64,
+	/// sibling of the node on every level of the mountain, from the leaves up
+	pub(crate) siblings: Vec<Node<P>>,
+	/// peaks of the higher mountains, on the left
+	pub(crate) left_peaks: Vec<Node<P>>,
+	/// peaks of the lower mountains, on the right
+	pub(crate) right_peaks: Vec<Node<P>>,
+	pub(crate) leaf_params: Rc<LeafParameters<P>>,
+	pub(crate) inner_params: Rc<InnerParameters<P>>,
+}
+
+impl<P: Config> MmrPath<P> {
+	/// verify the lookup proof, just checking the membership
+	pub fn check_membership<L: ToBytes>(
+		&self,
+		root_hash: &Node<P>,
+		leaf: &L,
+	) -> Result<bool, MerkleError> {
+		let prev = self.root_hash(leaf)?;
+		Ok(root_hash == &prev)
+	}
+
+	/// Return hash of root computed by the path
+	pub fn root_hash<L: ToBytes>(&self, leaf: &L) -> Result<Node<P>, MerkleError> {
+		let mut peak = hash_leaf::<P, L>(self.leaf_params.borrow(), leaf)?;
+		for (level, sibling) in self.siblings.iter().enumerate() {
+			peak = if (self.index >> level) & 1 == 1 {
+				hash_inner_node::<P>(self.inner_params.borrow(), sibling, &peak)?
+			} else {
+				hash_inner_node::<P>(self.inner_params.borrow(), &peak, sibling)?
+			};
+		}
+
+		let mut peaks = self.left_peaks.clone();
+		peaks.push(peak);
+		peaks.extend(self.right_peaks.iter().cloned());
+		bag_peaks::<P>(self.inner_params.borrow(), &peaks)
+	}
+}
+
+/// Returns the root of a range with the given peaks, folding them from the
+/// right.
+pub fn bag_peaks<P: Config>(
+	parameters: &InnerParameters<P>,
+	peaks: &[Node<P>],
+) -> Result<Node<P>, MerkleError> {
+	let (last, rest) = peaks.split_last().ok_or(MerkleError::EmptyTree)?;
+	let mut root = last.clone();
+	for peak in rest.iter().rev() {
+		root = hash_inner_node::<P>(parameters, peak, &root)?;
+	}
+
+	Ok(root)
+}
+
+/// Returns the number of nodes of a mountain, given its height.
+#[inline]
+fn mountain_size(height: u32) -> u64 {
+	(1u64 << (height + 1)) - 1
+}
+
+/// Returns the position of the peak, given the offset and height of a mountain.
+#[inline]
+fn peak_position(offset: u64, height: u32) -> usize {
+	(offset + mountain_size(height) - 1) as usize
+}
+
+#[cfg(test)]
+mod test {
+	use super::MerkleMountainRange;
+	use crate::{
+		merkle_tree::{Config, MerkleError},
+		poseidon::CRH as PoseidonCRH,
+	};
+	use ark_bls12_381::Fq;
+	use ark_crypto_primitives::CRH;
+	use ark_ff::{to_bytes, UniformRand};
+	use ark_std::{rc::Rc, test_rng};
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	type SMTCRH = PoseidonCRH<Fq>;
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct SMTConfig;
+	impl Config for SMTConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	#[test]
+	fn should_match_reference_root() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params = Rc::new(setup_params_x5_3(curve));
+
+		let leaves: Vec<Fq> = (0..7).map(|_| Fq::rand(rng)).collect();
+		let mmr = MerkleMountainRange::<SMTConfig>::new_sequential(
+			params.clone(),
+			params.clone(),
+			&leaves,
+		)
+		.unwrap();
+		assert_eq!(mmr.leaf_count(), 7);
+		assert_eq!(mmr.peaks().len(), 3);
+
+		// mountains of 4, 2 and 1 leaves, bagged from the right
+		let hash = |bytes: Vec<u8>| SMTCRH::evaluate(&params, &bytes).unwrap();
+		let l: Vec<Fq> = leaves.iter().map(|x| hash(to_bytes![x].unwrap())).collect();
+		let p01 = hash(to_bytes![l[0], l[1]].unwrap());
+		let p23 = hash(to_bytes![l[2], l[3]].unwrap());
+		let p0123 = hash(to_bytes![p01, p23].unwrap());
+		let p45 = hash(to_bytes![l[4], l[5]].unwrap());
+		let bagged = hash(to_bytes![p45, l[6]].unwrap());
+		let root = hash(to_bytes![p0123, bagged].unwrap());
+
+		let mmr_root = mmr.root().unwrap();
+		assert_eq!(to_bytes![mmr_root].unwrap(), to_bytes![root].unwrap());
+	}
+
+	#[test]
+	fn should_generate_and_validate_proofs() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params = Rc::new(setup_params_x5_3(curve));
+
+		let leaves: Vec<Fq> = (0..7).map(|_| Fq::rand(rng)).collect();
+		let mut mmr = MerkleMountainRange::<SMTConfig>::new(params.clone(), params);
+		assert!(matches!(mmr.root(), Err(MerkleError::EmptyTree)));
+		for (i, leaf) in leaves.iter().enumerate() {
+			assert_eq!(mmr.append(leaf).unwrap(), i as u64);
+		}
+		let root = mmr.root().unwrap();
+
+		for (i, leaf) in leaves.iter().enumerate() {
+			let proof = mmr.generate_membership_proof(i as u64).unwrap();
+			assert!(proof.check_membership(&root, leaf).unwrap());
+		}
+
+		let proof = mmr.generate_membership_proof(2).unwrap();
+		assert!(!proof.check_membership(&root, &leaves[3]).unwrap());
+		assert!(matches!(
+			mmr.generate_membership_proof(7),
+			Err(MerkleError::IndexOutOfRange(7))
+		));
+	}
+}
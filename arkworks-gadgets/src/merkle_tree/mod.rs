@@ -1,21 +1,200 @@
+use crate::{ark_std::string::ToString, field_hasher::FieldHasher};
 use ark_crypto_primitives::{Error, CRH};
 use ark_ff::{to_bytes, PrimeField, ToBytes};
+#[cfg(feature = "r1cs")]
+use ark_relations::r1cs::SynthesisError;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
 use ark_std::{
 	borrow::Borrow,
 	collections::{BTreeMap, BTreeSet},
-	io::{Result as IoResult, Write},
+	error::Error as ArkError,
+	io::{Error as IoError, Read, Result as IoResult, Write},
 	rc::Rc,
 	vec::Vec,
 };
+use arkworks_utils::utils::to_field_elements;
 use core::convert::TryInto;
 
+pub mod circom;
+pub mod compact;
+pub mod consistency;
 #[cfg(feature = "r1cs")]
 pub mod constraints;
+pub mod history;
+pub mod incremental;
+pub mod keyed;
+pub mod mmr;
+pub mod multi;
+pub mod quad;
+pub mod storage;
+pub mod versioned;
+pub mod zeros;
+
+pub use storage::{InMemoryStorage, MerkleStorage};
+
+#[derive(Debug)]
+pub enum MerkleError {
+	/// The path does not have one pair of nodes per tree level
+	InvalidPathLength { expected: usize, found: usize },
+	/// The hash of the leaf is not one of the bottom-most nodes of the path
+	LeafNotOnPath,
+	/// A level of the path does not contain the hash of the level below it
+	InconsistentPath,
+	/// The path does not lead to the given root
+	InvalidRoot,
+	/// The leaf index does not fit into the tree
+	IndexOutOfRange(u64),
+	/// The operation requires at least one leaf in the tree
+	EmptyTree,
+	/// There is no room left for new leaves
+	TreeFull,
+	/// Expected an inner node but got a leaf
+	NotInnerNode,
+	/// Expected a leaf but got an inner node
+	NotLeafNode,
+	/// Hashing or serializing a node failed
+	Hashing(Error),
+	/// The storage backend failed to read or write nodes
+	Storage(Error),
+	/// A snapshot does not describe a tree of this configuration
+	InvalidSnapshot,
+	/// The tree never reached the requested version
+	UnknownVersion(u64),
+	/// The tree is higher than the number of bits of its keys
+	KeyTooShort { height: usize, key_bits: usize },
+	/// The key hashes to the empty leaf
+	InvalidKey,
+	/// The leaf selected by the key already holds a different key
+	OccupiedLeaf,
+	/// The sizes do not describe a non-empty tree growing into another one
+	InvalidTreeSize { old_size: u64, new_size: u64 },
+	/// A proof or tree exported by another implementation could not be decoded
+	InvalidEncoding,
+	/// The root history holds fewer roots than the root set needs
+	NotEnoughRoots { expected: usize, found: usize },
+	#[cfg(feature = "r1cs")]
+	Synthesis(SynthesisError),
+}
+
+impl core::fmt::Display for MerkleError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		use MerkleError::*;
+		let msg = match self {
+			InvalidPathLength { expected, found } => format!(
+				"invalid path length: expected {} levels but found {}",
+				expected, found
+			),
+			LeafNotOnPath => "leaf is not on the path".to_string(),
+			InconsistentPath => "path nodes are not consistent".to_string(),
+			InvalidRoot => "path does not lead to the given root".to_string(),
+			IndexOutOfRange(i) => format!("leaf index {} is out of range", i),
+			EmptyTree => "tree has no leaves".to_string(),
+			TreeFull => "tree is full".to_string(),
+			NotInnerNode => "not an inner node".to_string(),
+			NotLeafNode => "not a leaf node".to_string(),
+			Hashing(e) => format!("failed to hash node: {}", e),
+			Storage(e) => format!("storage error: {}", e),
+			InvalidSnapshot => "snapshot does not match the tree config".to_string(),
+			UnknownVersion(v) => format!("unknown tree version {}", v),
+			KeyTooShort { height, key_bits } => format!(
+				"tree height {} exceeds the {} bits of a key",
+				height, key_bits
+			),
+			InvalidKey => "key hashes to the empty leaf".to_string(),
+			OccupiedLeaf => "leaf is occupied by another key".to_string(),
+			InvalidTreeSize { old_size, new_size } => format!(
+				"tree of {} leaves cannot grow into a tree of {} leaves",
+				old_size, new_size
+			),
+			InvalidEncoding => "invalid proof or tree encoding".to_string(),
+			NotEnoughRoots { expected, found } => format!(
+				"expected {} roots but the history holds {}",
+				expected, found
+			),
+			#[cfg(feature = "r1cs")]
+			Synthesis(e) => format!("synthesis error: {}", e),
+		};
+		write!(f, "{}", msg)
+	}
+}
+
+impl ArkError for MerkleError {}
+
+impl From<Error> for MerkleError {
+	fn from(e: Error) -> Self {
+		MerkleError::Hashing(e)
+	}
+}
+
+impl From<IoError> for MerkleError {
+	fn from(e: IoError) -> Self {
+		MerkleError::Hashing(e.into())
+	}
+}
+
+impl From<SerializationError> for MerkleError {
+	fn from(e: SerializationError) -> Self {
+		MerkleError::Storage(e.into())
+	}
+}
+
+#[cfg(feature = "r1cs")]
+impl From<SynthesisError> for MerkleError {
+	fn from(e: SynthesisError) -> Self {
+		MerkleError::Synthesis(e)
+	}
+}
+
+/// `SynthesisError` has no variant carrying a cause, so every error is mapped
+/// to the closest one: values the witness cannot be computed from become
+/// `AssignmentMissing` and proofs or inputs that cannot be satisfied become
+/// `Unsatisfiable`.
+#[cfg(feature = "r1cs")]
+impl From<MerkleError> for SynthesisError {
+	fn from(e: MerkleError) -> Self {
+		use MerkleError::*;
+		match e {
+			Synthesis(e) => e,
+			IndexOutOfRange(_)
+			| EmptyTree
+			| NotInnerNode
+			| NotLeafNode
+			| Hashing(_)
+			| Storage(_)
+			| UnknownVersion(_)
+			| NotEnoughRoots { .. } => SynthesisError::AssignmentMissing,
+			InvalidPathLength { .. }
+			| LeafNotOnPath
+			| InconsistentPath
+			| InvalidRoot
+			| TreeFull
+			| InvalidSnapshot
+			| KeyTooShort { .. }
+			| InvalidKey
+			| OccupiedLeaf
+			| InvalidTreeSize { .. }
+			| InvalidEncoding => SynthesisError::Unsatisfiable,
+		}
+	}
+}
 
 /// configuration of a Merkle tree
 pub trait Config: Clone + PartialEq {
 	/// Tree height
 	const HEIGHT: u8;
+	/// Tag hashed in front of every leaf, none by default.
+	///
+	/// Hashers that split their input into field elements (like Poseidon)
+	/// need the tag to fill whole elements, e.g. a canonically serialized
+	/// field element, and one more input slot than untagged hashing.
+	const LEAF_DOMAIN: Option<&'static [u8]> = None;
+	/// Tag hashed in front of the children of every inner node, none by
+	/// default. The same constraints as for `LEAF_DOMAIN` apply.
+	const NODE_DOMAIN: Option<&'static [u8]> = None;
+	/// Serialized value standing in for every empty leaf, which is hashed like
+	/// any other leaf. By default `LeafH::INPUT_SIZE_BITS / 8` zero bytes are
+	/// hashed instead.
+	const ZERO_LEAF: Option<&'static [u8]> = None;
 	/// The CRH
 	type H: CRH;
 	type LeafH: CRH;
@@ -32,19 +211,18 @@ pub enum Node<P: Config> {
 	Inner(InnerNode<P>),
 }
 
-// TODO: Improve error handling
 impl<P: Config> Node<P> {
-	pub fn inner(self) -> InnerNode<P> {
+	pub fn inner(self) -> Result<InnerNode<P>, MerkleError> {
 		match self {
-			Node::Inner(inner) => inner,
-			_ => panic!("Not inner node!"),
+			Node::Inner(inner) => Ok(inner),
+			_ => Err(MerkleError::NotInnerNode),
 		}
 	}
 
-	pub fn leaf(self) -> LeafNode<P> {
+	pub fn leaf(self) -> Result<LeafNode<P>, MerkleError> {
 		match self {
-			Node::Leaf(leaf) => leaf,
-			_ => panic!("Not leaf node!"),
+			Node::Leaf(leaf) => Ok(leaf),
+			_ => Err(MerkleError::NotLeafNode),
 		}
 	}
 }
@@ -58,6 +236,38 @@ impl<P: Config> ToBytes for Node<P> {
 	}
 }
 
+impl<P: Config> CanonicalSerialize for Node<P> {
+	fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+		match self {
+			Self::Leaf(leaf) => {
+				0u8.serialize(&mut writer)?;
+				leaf.serialize(&mut writer)
+			}
+			Self::Inner(inner) => {
+				1u8.serialize(&mut writer)?;
+				inner.serialize(&mut writer)
+			}
+		}
+	}
+
+	fn serialized_size(&self) -> usize {
+		1 + match self {
+			Self::Leaf(leaf) => leaf.serialized_size(),
+			Self::Inner(inner) => inner.serialized_size(),
+		}
+	}
+}
+
+impl<P: Config> CanonicalDeserialize for Node<P> {
+	fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+		match u8::deserialize(&mut reader)? {
+			0 => Ok(Self::Leaf(LeafNode::<P>::deserialize(&mut reader)?)),
+			1 => Ok(Self::Inner(InnerNode::<P>::deserialize(&mut reader)?)),
+			_ => Err(SerializationError::InvalidData),
+		}
+	}
+}
+
 #[derive(Clone)]
 pub struct Path<P: Config, const N: usize> {
 	pub(crate) path: [(Node<P>, Node<P>); N],
@@ -71,7 +281,7 @@ impl<P: Config + PartialEq, const N: usize> Path<P, N> {
 		&self,
 		root_hash: &Node<P>,
 		leaf: &L,
-	) -> Result<bool, Error> {
+	) -> Result<bool, MerkleError> {
 		let prev = self.root_hash(leaf)?;
 		Ok(root_hash == &prev)
 	}
@@ -80,9 +290,9 @@ impl<P: Config + PartialEq, const N: usize> Path<P, N> {
 		&self,
 		root_hash: &Node<P>,
 		leaf: &L,
-	) -> Result<F, Error> {
+	) -> Result<F, MerkleError> {
 		if !self.check_membership(root_hash, leaf)? {
-			panic!("Leaf is not in the path");
+			return Err(MerkleError::InvalidRoot);
 		}
 
 		let mut prev = hash_leaf::<P, L>(self.leaf_params.borrow(), leaf)?;
@@ -102,17 +312,57 @@ impl<P: Config + PartialEq, const N: usize> Path<P, N> {
 	}
 
 	/// Return hash of root computed by the path
-	pub fn root_hash<L: ToBytes>(&self, leaf: &L) -> Result<Node<P>, Error> {
+	pub fn root_hash<L: ToBytes>(&self, leaf: &L) -> Result<Node<P>, MerkleError> {
+		let claimed_leaf_hash = hash_leaf::<P, L>(self.leaf_params.borrow(), leaf)?;
+		self.root_hash_from_leaf_hash(claimed_leaf_hash, |left, right| {
+			hash_inner_node::<P>(self.inner_params.borrow(), left, right)
+		})
+	}
+
+	/// Same as `check_membership`, hashing the leaf and the nodes as field
+	/// elements with `FieldHasher::hash`
+	pub fn check_membership_fields<F: PrimeField>(
+		&self,
+		root_hash: &Node<P>,
+		leaf: &F,
+	) -> Result<bool, MerkleError>
+	where
+		P::H: FieldHasher<F>,
+		P::LeafH: FieldHasher<F>,
+	{
+		let prev = self.root_hash_fields(leaf)?;
+		Ok(root_hash == &prev)
+	}
+
+	/// Same as `root_hash`, hashing the leaf and the nodes as field elements
+	/// with `FieldHasher::hash`
+	pub fn root_hash_fields<F: PrimeField>(&self, leaf: &F) -> Result<Node<P>, MerkleError>
+	where
+		P::H: FieldHasher<F>,
+		P::LeafH: FieldHasher<F>,
+	{
+		let claimed_leaf_hash = hash_leaf_fields::<P, F>(self.leaf_params.borrow(), leaf)?;
+		self.root_hash_from_leaf_hash(claimed_leaf_hash, |left, right| {
+			hash_inner_node_fields::<P, F>(self.inner_params.borrow(), left, right)
+		})
+	}
+
+	fn root_hash_from_leaf_hash(
+		&self,
+		claimed_leaf_hash: Node<P>,
+		hash_inner_node: impl Fn(&Node<P>, &Node<P>) -> Result<Node<P>, MerkleError>,
+	) -> Result<Node<P>, MerkleError> {
 		if self.path.len() != P::HEIGHT as usize {
-			panic!("path.len !=  P::HEIGHT");
+			return Err(MerkleError::InvalidPathLength {
+				expected: P::HEIGHT as usize,
+				found: self.path.len(),
+			});
 		}
 
-		let claimed_leaf_hash = hash_leaf::<P, L>(self.leaf_params.borrow(), leaf)?;
-
 		// Check if claimed leaf hash is the same as one of
 		// the provided hashes on level 0
 		if claimed_leaf_hash != self.path[0].0 && claimed_leaf_hash != self.path[0].1 {
-			panic!("Leaf is not on Path");
+			return Err(MerkleError::LeafNotOnPath);
 		}
 
 		let mut prev = claimed_leaf_hash;
@@ -120,9 +370,9 @@ impl<P: Config + PartialEq, const N: usize> Path<P, N> {
 		for &(ref left_hash, ref right_hash) in &self.path {
 			// Check if the previous hash matches the correct current hash.
 			if &prev != left_hash && &prev != right_hash {
-				panic!("Path nodes are not consistent");
+				return Err(MerkleError::InconsistentPath);
 			}
-			prev = hash_inner_node::<P>(self.inner_params.borrow(), left_hash, right_hash)?;
+			prev = hash_inner_node(left_hash, right_hash)?;
 		}
 
 		Ok(prev)
@@ -130,56 +380,145 @@ impl<P: Config + PartialEq, const N: usize> Path<P, N> {
 }
 
 /// Merkle sparse tree
-pub struct SparseMerkleTree<P: Config> {
+pub struct SparseMerkleTree<P: Config, S: MerkleStorage<P> = InMemoryStorage<P>> {
 	/// data of the tree
-	pub tree: BTreeMap<u64, Node<P>>,
+	storage: S,
 	empty_hashes: Vec<Node<P>>,
 	leaf_params: Rc<<P::LeafH as CRH>::Parameters>,
 	inner_params: Rc<<P::H as CRH>::Parameters>,
 }
 
-// TODO: Improve error handling
 impl<P: Config> SparseMerkleTree<P> {
 	/// obtain an empty tree
-	pub fn blank(inner_params: Rc<InnerParameters<P>>, leaf_params: Rc<LeafParameters<P>>) -> Self {
-		let empty_hashes =
-			gen_empty_hashes::<P>(leaf_params.borrow(), inner_params.borrow()).unwrap();
+	pub fn blank(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+	) -> Result<Self, MerkleError> {
+		Self::from_storage(InMemoryStorage::default(), inner_params, leaf_params)
+	}
 
-		SparseMerkleTree {
-			tree: BTreeMap::new(),
+	/// initialize a tree (with optional data)
+	pub fn new<L: Default + ToBytes>(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+		leaves: &BTreeMap<u32, L>,
+	) -> Result<Self, MerkleError> {
+		let last_level_size = leaves.len().next_power_of_two();
+		let tree_size = 2 * last_level_size - 1;
+		let tree_height = tree_height(tree_size as u64);
+		if tree_height > P::HEIGHT as u32 {
+			return Err(MerkleError::TreeFull);
+		}
+
+		let mut smt = Self::blank(inner_params, leaf_params)?;
+		smt.insert_batch(leaves)?;
+
+		Ok(smt)
+	}
+
+	pub fn new_sequential<L: Default + ToBytes + Clone>(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+		leaves: &[L],
+	) -> Result<Self, MerkleError> {
+		let pairs: BTreeMap<u32, L> = leaves
+			.iter()
+			.enumerate()
+			.map(|(i, l)| (i as u32, l.clone()))
+			.collect();
+		let smt = Self::new(inner_params, leaf_params, &pairs)?;
+
+		Ok(smt)
+	}
+
+	/// restore an in-memory tree from a snapshot without rehashing any stored
+	/// node, failing if its empty hashes do not match the parameters
+	pub fn from_snapshot(
+		snapshot: SparseMerkleTreeSnapshot<P>,
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+	) -> Result<Self, MerkleError> {
+		let empty_hashes = gen_empty_hashes::<P>(leaf_params.borrow(), inner_params.borrow())?;
+		if snapshot.empty_hashes != empty_hashes {
+			return Err(MerkleError::InvalidSnapshot);
+		}
+
+		Ok(SparseMerkleTree {
+			storage: InMemoryStorage::new(snapshot.nodes),
 			empty_hashes,
 			inner_params,
 			leaf_params,
-		}
+		})
+	}
+}
+
+impl<P: Config, S: MerkleStorage<P>> SparseMerkleTree<P, S> {
+	/// obtain a tree backed by `storage`, which may already hold nodes
+	pub fn from_storage(
+		storage: S,
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+	) -> Result<Self, MerkleError> {
+		let empty_hashes = gen_empty_hashes::<P>(leaf_params.borrow(), inner_params.borrow())?;
+
+		Ok(SparseMerkleTree {
+			storage,
+			empty_hashes,
+			inner_params,
+			leaf_params,
+		})
 	}
 
 	pub fn insert_batch<L: Default + ToBytes>(
 		&mut self,
 		leaves: &BTreeMap<u32, L>,
-	) -> Result<(), Error> {
+	) -> Result<(), MerkleError> {
+		let batch = self.compute_batch(leaves)?;
+		self.storage.commit(batch)
+	}
+
+	/// hash the given leaves and every node above them, without storing them
+	pub(crate) fn compute_batch<L: Default + ToBytes>(
+		&self,
+		leaves: &BTreeMap<u32, L>,
+	) -> Result<BTreeMap<u64, Node<P>>, MerkleError> {
+		let mut leaf_hashes = BTreeMap::new();
+		for (i, leaf) in leaves {
+			leaf_hashes.insert(*i, hash_leaf::<P, _>(self.leaf_params.borrow(), leaf)?);
+		}
+
+		self.compute_hashed_batch(leaf_hashes)
+	}
+
+	/// store already hashed leaves and hash every node above them, without
+	/// storing them
+	pub(crate) fn compute_hashed_batch(
+		&self,
+		leaf_hashes: BTreeMap<u32, Node<P>>,
+	) -> Result<BTreeMap<u64, Node<P>>, MerkleError> {
 		let last_level_index: u64 = (1u64 << P::HEIGHT) - 1;
 
+		// Nodes touched by this batch, written to the storage in one commit.
+		let mut batch: BTreeMap<u64, Node<P>> = BTreeMap::new();
 		let mut level_idxs: BTreeSet<u64> = BTreeSet::new();
-		for (i, leaf) in leaves {
-			let true_index = last_level_index + (*i as u64);
-			let leaf_hash = hash_leaf::<P, _>(self.leaf_params.borrow(), leaf)?;
-			self.tree.insert(true_index, leaf_hash);
-			level_idxs.insert(parent(true_index).unwrap());
+		for (i, leaf_hash) in leaf_hashes {
+			if (i as u64) > last_level_index {
+				return Err(MerkleError::IndexOutOfRange(i as u64));
+			}
+			let true_index = last_level_index + (i as u64);
+			batch.insert(true_index, leaf_hash);
+			level_idxs.insert(parent(true_index).ok_or(MerkleError::IndexOutOfRange(i as u64))?);
 		}
 
 		for level in 0..P::HEIGHT {
 			let mut new_idxs: BTreeSet<u64> = BTreeSet::new();
 			for i in level_idxs {
-				let left_index = left_child(i);
-				let right_index = right_child(i);
-
-				let empty_hash = self.empty_hashes[level as usize].clone();
-				let left = self.tree.get(&left_index).unwrap_or(&empty_hash);
-				let right = self.tree.get(&right_index).unwrap_or(&empty_hash);
-				#[allow(mutable_borrow_reservation_conflict)]
-				self.tree.insert(
+				let empty_hash = &self.empty_hashes[level as usize];
+				let left = self.get_pending(&batch, left_child(i), empty_hash)?;
+				let right = self.get_pending(&batch, right_child(i), empty_hash)?;
+				batch.insert(
 					i,
-					hash_inner_node::<P>(self.inner_params.borrow(), left, right)?,
+					hash_inner_node::<P>(self.inner_params.borrow(), &left, &right)?,
 				);
 
 				let parent = match parent(i) {
@@ -191,58 +530,54 @@ impl<P: Config> SparseMerkleTree<P> {
 			level_idxs = new_idxs;
 		}
 
-		Ok(())
+		Ok(batch)
 	}
 
-	/// initialize a tree (with optional data)
-	pub fn new<L: Default + ToBytes>(
-		inner_params: Rc<InnerParameters<P>>,
-		leaf_params: Rc<LeafParameters<P>>,
-		leaves: &BTreeMap<u32, L>,
-	) -> Result<Self, Error> {
-		let last_level_size = leaves.len().next_power_of_two();
-		let tree_size = 2 * last_level_size - 1;
-		let tree_height = tree_height(tree_size as u64);
-		assert!(tree_height <= P::HEIGHT as u32);
-
-		// Initialize the merkle tree.
-		let tree: BTreeMap<u64, Node<P>> = BTreeMap::new();
-		let empty_hashes = gen_empty_hashes::<P>(leaf_params.borrow(), inner_params.borrow())?;
+	#[inline]
+	/// obtain the root hash
+	pub fn root(&self) -> Result<Node<P>, MerkleError> {
+		self.storage.get(0)?.ok_or(MerkleError::EmptyTree)
+	}
 
-		let mut smt = SparseMerkleTree {
-			tree,
-			empty_hashes,
-			inner_params,
-			leaf_params,
-		};
-		smt.insert_batch(leaves)?;
+	#[inline]
+	/// obtain the storage backend of the tree
+	pub fn storage(&self) -> &S {
+		&self.storage
+	}
 
-		Ok(smt)
+	/// capture every stored node, so the tree can be serialized
+	pub fn snapshot(&self) -> Result<SparseMerkleTreeSnapshot<P>, MerkleError> {
+		Ok(SparseMerkleTreeSnapshot {
+			empty_hashes: self.empty_hashes.clone(),
+			nodes: self.storage.nodes()?,
+		})
 	}
 
-	pub fn new_sequential<L: Default + ToBytes + Clone>(
-		inner_params: Rc<InnerParameters<P>>,
-		leaf_params: Rc<LeafParameters<P>>,
-		leaves: &[L],
-	) -> Result<Self, Error> {
-		let pairs: BTreeMap<u32, L> = leaves
-			.iter()
-			.enumerate()
-			.map(|(i, l)| (i as u32, l.clone()))
-			.collect();
-		let smt = Self::new(inner_params, leaf_params, &pairs)?;
+	/// generate a membership proof (does not check the data point)
+	pub fn generate_membership_proof<const N: usize>(
+		&self,
+		index: u64,
+	) -> Result<Path<P, N>, MerkleError> {
+		if self.storage.is_empty() {
+			return Err(MerkleError::EmptyTree);
+		}
 
-		Ok(smt)
+		self.build_path(index, |i| self.storage.get(i))
 	}
 
-	#[inline]
-	/// obtain the root hash
-	pub fn root(&self) -> Node<P> {
-		self.tree.get(&0).cloned().unwrap()
-	}
+	/// build the path of `index`, reading nodes through `get`
+	pub(crate) fn build_path<G, const N: usize>(
+		&self,
+		index: u64,
+		get: G,
+	) -> Result<Path<P, N>, MerkleError>
+	where
+		G: Fn(u64) -> Result<Option<Node<P>>, MerkleError>,
+	{
+		if index >= (1u64 << P::HEIGHT) {
+			return Err(MerkleError::IndexOutOfRange(index));
+		}
 
-	/// generate a membership proof (does not check the data point)
-	pub fn generate_membership_proof<const N: usize>(&self, index: u64) -> Path<P, N> {
 		let mut path = Vec::with_capacity(N);
 
 		let tree_index = convert_index_to_last_level::<P>(index);
@@ -251,39 +586,81 @@ impl<P: Config> SparseMerkleTree<P> {
 		let mut current_node = tree_index;
 		let mut level = 0;
 		while !is_root(current_node) {
-			let sibling_node = sibling(current_node).unwrap();
+			let sibling_node = sibling(current_node).ok_or(MerkleError::IndexOutOfRange(index))?;
 
 			let empty_hash = &self.empty_hashes[level];
 
-			let current = self
-				.tree
-				.get(&current_node)
-				.cloned()
-				.unwrap_or_else(|| empty_hash.clone());
-			let sibling = self
-				.tree
-				.get(&sibling_node)
-				.cloned()
-				.unwrap_or_else(|| empty_hash.clone());
+			let current = get(current_node)?.unwrap_or_else(|| empty_hash.clone());
+			let sibling = get(sibling_node)?.unwrap_or_else(|| empty_hash.clone());
 
 			if is_left_child(current_node) {
 				path.push((current, sibling));
 			} else {
 				path.push((sibling, current));
 			}
-			current_node = parent(current_node).unwrap();
+			current_node = parent(current_node).ok_or(MerkleError::IndexOutOfRange(index))?;
 			level += 1;
 		}
 
-		Path {
-			path: path
-				.try_into()
-				.unwrap_or_else(|v: Vec<(Node<P>, Node<P>)>| {
-					panic!("Expected a Vec of length {} but it was {}", N, v.len())
-				}),
+		Ok(Path {
+			path: path.try_into().map_err(|v: Vec<(Node<P>, Node<P>)>| {
+				MerkleError::InvalidPathLength {
+					expected: N,
+					found: v.len(),
+				}
+			})?,
 			inner_params: Rc::clone(&self.inner_params),
 			leaf_params: Rc::clone(&self.leaf_params),
+		})
+	}
+
+	/// look a node up in the pending batch first, then in the storage
+	fn get_pending(
+		&self,
+		batch: &BTreeMap<u64, Node<P>>,
+		index: u64,
+		empty_hash: &Node<P>,
+	) -> Result<Node<P>, MerkleError> {
+		if let Some(node) = batch.get(&index) {
+			return Ok(node.clone());
 		}
+		Ok(self
+			.storage
+			.get(index)?
+			.unwrap_or_else(|| empty_hash.clone()))
+	}
+}
+
+/// Every stored node of a `SparseMerkleTree` together with its empty hashes.
+///
+/// Serializing a snapshot and restoring it with
+/// `SparseMerkleTree::from_snapshot` does not recompute any stored node, only
+/// the empty hashes it checks the snapshot against.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SparseMerkleTreeSnapshot<P: Config> {
+	pub empty_hashes: Vec<Node<P>>,
+	pub nodes: BTreeMap<u64, Node<P>>,
+}
+
+impl<P: Config> CanonicalSerialize for SparseMerkleTreeSnapshot<P> {
+	fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+		self.empty_hashes.serialize(&mut writer)?;
+		self.nodes.serialize(&mut writer)
+	}
+
+	fn serialized_size(&self) -> usize {
+		self.empty_hashes.serialized_size() + self.nodes.serialized_size()
+	}
+}
+
+impl<P: Config> CanonicalDeserialize for SparseMerkleTreeSnapshot<P> {
+	fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+		let empty_hashes = Vec::<Node<P>>::deserialize(&mut reader)?;
+		let nodes = BTreeMap::<u64, Node<P>>::deserialize(&mut reader)?;
+		Ok(Self {
+			empty_hashes,
+			nodes,
+		})
 	}
 }
 
@@ -355,8 +732,8 @@ pub(crate) fn hash_inner_node<P: Config>(
 	parameters: &<P::H as CRH>::Parameters,
 	left: &Node<P>,
 	right: &Node<P>,
-) -> Result<Node<P>, Error> {
-	let bytes = to_bytes![left, right]?;
+) -> Result<Node<P>, MerkleError> {
+	let bytes = with_domain(P::NODE_DOMAIN, to_bytes![left, right]?);
 	let inner = <P::H as CRH>::evaluate(parameters, &bytes)?;
 	Ok(Node::Inner(inner))
 }
@@ -365,24 +742,95 @@ pub(crate) fn hash_inner_node<P: Config>(
 fn hash_leaf<P: Config, L: ToBytes>(
 	parameters: &<P::LeafH as CRH>::Parameters,
 	leaf: &L,
-) -> Result<Node<P>, Error> {
-	let leaf = <P::LeafH as CRH>::evaluate(parameters, &to_bytes![leaf]?)?;
+) -> Result<Node<P>, MerkleError> {
+	let bytes = with_domain(P::LEAF_DOMAIN, to_bytes![leaf]?);
+	let leaf = <P::LeafH as CRH>::evaluate(parameters, &bytes)?;
 	Ok(Node::Leaf(leaf))
 }
 
-fn hash_empty<P: Config>(parameters: &<P::LeafH as CRH>::Parameters) -> Result<Node<P>, Error> {
-	let res = <P::LeafH as CRH>::evaluate(parameters, &vec![
-		0u8;
-		<P::LeafH as CRH>::INPUT_SIZE_BITS / 8
+/// Returns the Node hash of a left and right hash value, hashed as field
+/// elements.
+pub(crate) fn hash_inner_node_fields<P: Config, F: PrimeField>(
+	parameters: &<P::H as CRH>::Parameters,
+	left: &Node<P>,
+	right: &Node<P>,
+) -> Result<Node<P>, MerkleError>
+where
+	P::H: FieldHasher<F>,
+	P::LeafH: FieldHasher<F>,
+{
+	let inputs = with_domain_fields(P::NODE_DOMAIN, vec![
+		node_to_field::<P, F>(left),
+		node_to_field::<P, F>(right),
 	])?;
+	let inner = <P::H as FieldHasher<F>>::hash(parameters, &inputs)?;
+	Ok(Node::Inner(inner))
+}
+
+/// Returns the hash of a leaf given as a field element.
+fn hash_leaf_fields<P: Config, F: PrimeField>(
+	parameters: &<P::LeafH as CRH>::Parameters,
+	leaf: &F,
+) -> Result<Node<P>, MerkleError>
+where
+	P::LeafH: FieldHasher<F>,
+{
+	let inputs = with_domain_fields(P::LEAF_DOMAIN, vec![*leaf])?;
+	let leaf = <P::LeafH as FieldHasher<F>>::hash(parameters, &inputs)?;
+	Ok(Node::Leaf(leaf))
+}
+
+fn node_to_field<P: Config, F: PrimeField>(node: &Node<P>) -> F
+where
+	P::H: FieldHasher<F>,
+	P::LeafH: FieldHasher<F>,
+{
+	match node {
+		Node::Inner(inner) => *inner,
+		Node::Leaf(leaf) => *leaf,
+	}
+}
+
+fn hash_empty<P: Config>(
+	parameters: &<P::LeafH as CRH>::Parameters,
+) -> Result<Node<P>, MerkleError> {
+	let zero_leaf = match P::ZERO_LEAF {
+		Some(zero_leaf) => zero_leaf.to_vec(),
+		None => vec![0u8; <P::LeafH as CRH>::INPUT_SIZE_BITS / 8],
+	};
+	let bytes = with_domain(P::LEAF_DOMAIN, zero_leaf);
+	let res = <P::LeafH as CRH>::evaluate(parameters, &bytes)?;
 
 	Ok(Node::Leaf(res))
 }
 
+/// Returns `bytes` prefixed with the domain tag, if there is one.
+pub(crate) fn with_domain(domain: Option<&[u8]>, bytes: Vec<u8>) -> Vec<u8> {
+	match domain {
+		Some(tag) => tag.iter().copied().chain(bytes).collect(),
+		None => bytes,
+	}
+}
+
+/// Returns `inputs` prefixed with the domain tag read as field elements, if
+/// there is one.
+pub(crate) fn with_domain_fields<F: PrimeField>(
+	domain: Option<&[u8]>,
+	inputs: Vec<F>,
+) -> Result<Vec<F>, MerkleError> {
+	match domain {
+		Some(tag) => Ok(to_field_elements::<F>(tag)?
+			.into_iter()
+			.chain(inputs)
+			.collect()),
+		None => Ok(inputs),
+	}
+}
+
 pub fn gen_empty_hashes<P: Config>(
 	leaf_params: &LeafParameters<P>,
 	inner_params: &InnerParameters<P>,
-) -> Result<Vec<Node<P>>, Error> {
+) -> Result<Vec<Node<P>>, MerkleError> {
 	let mut empty_hashes = Vec::with_capacity(P::HEIGHT as usize);
 
 	let mut empty_hash = hash_empty::<P>(leaf_params)?;
@@ -398,15 +846,19 @@ pub fn gen_empty_hashes<P: Config>(
 
 #[cfg(test)]
 mod test {
-	use super::{gen_empty_hashes, hash_inner_node, hash_leaf, Config, SparseMerkleTree};
+	use super::{
+		gen_empty_hashes, hash_inner_node, hash_leaf, Config, MerkleError, SparseMerkleTree,
+		SparseMerkleTreeSnapshot,
+	};
 	use crate::poseidon::CRH as PoseidonCRH;
 	use ark_bls12_381::Fq;
 	use ark_crypto_primitives::crh::CRH;
-	use ark_ff::{ToBytes, UniformRand};
-	use ark_std::{borrow::Borrow, collections::BTreeMap, rc::Rc, test_rng};
+	use ark_ff::{to_bytes, ToBytes, UniformRand};
+	use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+	use ark_std::{borrow::Borrow, collections::BTreeMap, rc::Rc, test_rng, vec::Vec};
 	use arkworks_utils::{
 		mimc::MiMCParameters,
-		utils::common::{setup_params_x5_3, Curve},
+		utils::common::{setup_params_x5_3, setup_params_x5_5, Curve},
 	};
 
 	type SMTCRH = PoseidonCRH<Fq>;
@@ -448,7 +900,7 @@ mod test {
 		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
 		let smt = create_merkle_tree(inner_params.clone(), leaf_params.clone(), &leaves);
 
-		let root = smt.root();
+		let root = smt.root().unwrap();
 
 		let empty_hashes =
 			gen_empty_hashes::<SMTConfig>(inner_params.borrow(), leaf_params.borrow()).unwrap();
@@ -481,11 +933,38 @@ mod test {
 		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
 		let smt = create_merkle_tree::<_, SMTConfig>(inner_params, leaf_params, &leaves);
 
-		let proof = smt.generate_membership_proof::<{ SMTConfig::HEIGHT as usize }>(0);
+		let proof = smt
+			.generate_membership_proof::<{ SMTConfig::HEIGHT as usize }>(0)
+			.unwrap();
 
-		let res = proof.check_membership(&smt.root(), &leaves[0]).unwrap();
+		let res = proof
+			.check_membership(&smt.root().unwrap(), &leaves[0])
+			.unwrap();
 		assert!(res);
 	}
+
+	#[test]
+	fn should_validate_proof_from_fields() {
+		let rng = &mut test_rng();
+		let params3 = setup_params_x5_3(Curve::Bls381);
+
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = create_merkle_tree::<_, SMTConfig>(inner_params, leaf_params, &leaves);
+		let root = smt.root().unwrap();
+
+		let proof = smt
+			.generate_membership_proof::<{ SMTConfig::HEIGHT as usize }>(1)
+			.unwrap();
+		assert_eq!(proof.root_hash_fields(&leaves[1]).unwrap(), root);
+		assert!(proof.check_membership_fields(&root, &leaves[1]).unwrap());
+		assert!(proof
+			.check_membership_fields(&root, &Fq::rand(rng))
+			.is_err());
+	}
+
 	#[test]
 	fn should_find_the_index_poseidon() {
 		let rng = &mut test_rng();
@@ -499,16 +978,123 @@ mod test {
 		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
 		let smt = create_merkle_tree::<_, SMTConfig>(inner_params, leaf_params, &leaves);
 
-		let proof = smt.generate_membership_proof::<{ MiMCSMTConfig::HEIGHT as usize }>(index);
+		let proof = smt
+			.generate_membership_proof::<{ MiMCSMTConfig::HEIGHT as usize }>(index)
+			.unwrap();
 
 		let res: Fq = proof
-			.get_index(&smt.root(), &leaves[index as usize])
+			.get_index(&smt.root().unwrap(), &leaves[index as usize])
 			.unwrap();
 		let desired_res = Fq::from(index);
 
 		assert_eq!(res, desired_res)
 	}
 
+	#[test]
+	fn should_restore_tree_from_snapshot() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params3 = setup_params_x5_3(curve);
+
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt =
+			create_merkle_tree::<_, SMTConfig>(inner_params.clone(), leaf_params.clone(), &leaves);
+
+		let mut bytes = Vec::new();
+		smt.snapshot().unwrap().serialize(&mut bytes).unwrap();
+		let snapshot = SparseMerkleTreeSnapshot::<SMTConfig>::deserialize(&bytes[..]).unwrap();
+		let restored =
+			SparseMerkleTree::from_snapshot(snapshot, inner_params, leaf_params).unwrap();
+
+		assert_eq!(restored.root().unwrap(), smt.root().unwrap());
+		let proof = restored
+			.generate_membership_proof::<{ SMTConfig::HEIGHT as usize }>(1)
+			.unwrap();
+		assert!(proof
+			.check_membership(&smt.root().unwrap(), &leaves[1])
+			.unwrap());
+	}
+
+	#[test]
+	fn should_reject_snapshot_with_tampered_empty_hashes() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params3 = setup_params_x5_3(curve);
+
+		let inner_params = Rc::new(params3);
+		let leaf_params = inner_params.clone();
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt =
+			create_merkle_tree::<_, SMTConfig>(inner_params.clone(), leaf_params.clone(), &leaves);
+
+		let mut snapshot = smt.snapshot().unwrap();
+		snapshot.empty_hashes[1] = snapshot.empty_hashes[0].clone();
+		assert!(matches!(
+			SparseMerkleTree::from_snapshot(snapshot, inner_params.clone(), leaf_params.clone()),
+			Err(MerkleError::InvalidSnapshot)
+		));
+
+		let mut snapshot = smt.snapshot().unwrap();
+		snapshot.empty_hashes.pop();
+		assert!(matches!(
+			SparseMerkleTree::from_snapshot(snapshot, inner_params, leaf_params),
+			Err(MerkleError::InvalidSnapshot)
+		));
+	}
+
+	/// serialized Bls381 `Fq` element `tag`, filling one Poseidon input
+	const fn domain_tag(tag: u8) -> [u8; 48] {
+		let mut bytes = [0u8; 48];
+		bytes[0] = tag;
+		bytes
+	}
+	const LEAF_TAG: [u8; 48] = domain_tag(1);
+	const NODE_TAG: [u8; 48] = domain_tag(2);
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct DomainConfig;
+	impl Config for DomainConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 3;
+		const LEAF_DOMAIN: Option<&'static [u8]> = Some(&LEAF_TAG);
+		const NODE_DOMAIN: Option<&'static [u8]> = Some(&NODE_TAG);
+	}
+
+	#[test]
+	fn should_separate_leaf_and_node_domains() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params = Rc::new(setup_params_x5_5(curve));
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+
+		let leaf_hash = hash_leaf::<DomainConfig, _>(&params, &leaves[0]).unwrap();
+		let bytes = [&LEAF_TAG[..], &to_bytes![leaves[0]].unwrap()].concat();
+		assert_eq!(
+			leaf_hash.leaf().unwrap(),
+			SMTCRH::evaluate(&params, &bytes).unwrap()
+		);
+
+		let tagged = create_merkle_tree::<_, DomainConfig>(params.clone(), params.clone(), &leaves);
+		let untagged = create_merkle_tree::<_, SMTConfig>(params.clone(), params, &leaves);
+		let tagged_root = tagged.root().unwrap();
+		assert_ne!(
+			tagged_root.clone().inner().unwrap(),
+			untagged.root().unwrap().inner().unwrap()
+		);
+
+		let proof = tagged
+			.generate_membership_proof::<{ DomainConfig::HEIGHT as usize }>(1)
+			.unwrap();
+		assert!(proof.check_membership(&tagged_root, &leaves[1]).unwrap());
+	}
+
 	use crate::mimc::Rounds as MiMCRounds;
 	use ark_ed_on_bn254::Fq as Bn254Fq;
 
@@ -548,7 +1134,7 @@ mod test {
 		let leaves = vec![Bn254Fq::rand(rng), Bn254Fq::rand(rng), Bn254Fq::rand(rng)];
 		let smt = create_merkle_tree(inner_params.clone(), leaf_params.clone(), &leaves);
 
-		let root = smt.root();
+		let root = smt.root().unwrap();
 
 		let empty_hashes =
 			gen_empty_hashes::<MiMCSMTConfig>(inner_params.borrow(), leaf_params.borrow()).unwrap();
@@ -586,9 +1172,13 @@ mod test {
 		let leaves = vec![Bn254Fq::rand(rng), Bn254Fq::rand(rng), Bn254Fq::rand(rng)];
 		let smt = create_merkle_tree::<_, MiMCSMTConfig>(inner_params, leaf_params, &leaves);
 
-		let proof = smt.generate_membership_proof::<{ MiMCSMTConfig::HEIGHT as usize }>(0);
+		let proof = smt
+			.generate_membership_proof::<{ MiMCSMTConfig::HEIGHT as usize }>(0)
+			.unwrap();
 
-		let res = proof.check_membership(&smt.root(), &leaves[0]).unwrap();
+		let res = proof
+			.check_membership(&smt.root().unwrap(), &leaves[0])
+			.unwrap();
 		assert!(res);
 	}
 
@@ -608,13 +1198,30 @@ mod test {
 		let leaves = vec![Bn254Fq::rand(rng), Bn254Fq::rand(rng), Bn254Fq::rand(rng)];
 		let smt = create_merkle_tree::<_, MiMCSMTConfig>(inner_params, leaf_params, &leaves);
 
-		let proof = smt.generate_membership_proof::<{ MiMCSMTConfig::HEIGHT as usize }>(index);
+		let proof = smt
+			.generate_membership_proof::<{ MiMCSMTConfig::HEIGHT as usize }>(index)
+			.unwrap();
 
 		let res: Fq = proof
-			.get_index(&smt.root(), &leaves[index as usize])
+			.get_index(&smt.root().unwrap(), &leaves[index as usize])
 			.unwrap();
 		let desired_res = Fq::from(index);
 
 		assert_eq!(res, desired_res)
 	}
+
+	#[cfg(feature = "r1cs")]
+	#[test]
+	fn should_keep_the_error_kind_in_synthesis_errors() {
+		use super::MerkleError;
+		use ark_relations::r1cs::SynthesisError;
+
+		let missing: SynthesisError = MerkleError::IndexOutOfRange(8).into();
+		assert_eq!(missing, SynthesisError::AssignmentMissing);
+		let invalid: SynthesisError = MerkleError::InvalidRoot.into();
+		assert_eq!(invalid, SynthesisError::Unsatisfiable);
+		let synthesis: SynthesisError =
+			MerkleError::Synthesis(SynthesisError::DivisionByZero).into();
+		assert_eq!(synthesis, SynthesisError::DivisionByZero);
+	}
 }
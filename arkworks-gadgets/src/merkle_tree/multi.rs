@@ -0,0 +1,187 @@
+use super::{
+	convert_index_to_last_level, hash_inner_node, hash_leaf, is_left_child, parent, sibling,
+	storage::MerkleStorage, Config, InnerParameters, LeafParameters, MerkleError, Node,
+	SparseMerkleTree,
+};
+use ark_ff::ToBytes;
+use ark_std::{
+	borrow::Borrow,
+	collections::{BTreeMap, BTreeSet},
+	rc::Rc,
+	vec::Vec,
+};
+
+/// Membership proof for several leaves of one tree.
+///
+/// Only the siblings that cannot be computed from the proven leaves are
+/// stored, so nodes shared between the paths of different leaves appear once.
+/// The auxiliary nodes are ordered level by level from the leaves up, and by
+/// ascending index within a level.
+#[derive(Clone)]
+pub struct MultiPath<P: Config> {
+	/// proven leaf positions, in ascending order
+	pub(crate) indices: Vec<u64>,
+	pub(crate) auxiliary: Vec<Node<P>>,
+	pub(crate) leaf_params: Rc<LeafParameters<P>>,
+	pub(crate) inner_params: Rc<InnerParameters<P>>,
+}
+
+impl<P: Config> MultiPath<P> {
+	/// proven leaf positions, in the order `check_membership` expects the
+	/// leaves
+	pub fn indices(&self) -> &[u64] {
+		&self.indices
+	}
+
+	/// verify the proof, `leaves` being ordered like `indices`
+	pub fn check_membership<L: ToBytes>(
+		&self,
+		root_hash: &Node<P>,
+		leaves: &[L],
+	) -> Result<bool, MerkleError> {
+		let root = self.root_hash(leaves)?;
+		Ok(root_hash == &root)
+	}
+
+	/// Return hash of root computed by the proof
+	pub fn root_hash<L: ToBytes>(&self, leaves: &[L]) -> Result<Node<P>, MerkleError> {
+		if leaves.len() != self.indices.len() {
+			return Err(MerkleError::InvalidPathLength {
+				expected: self.indices.len(),
+				found: leaves.len(),
+			});
+		}
+		if leaves.is_empty() {
+			return Err(MerkleError::EmptyTree);
+		}
+
+		let mut level_nodes = BTreeMap::new();
+		for (index, leaf) in self.indices.iter().zip(leaves) {
+			let leaf_hash = hash_leaf::<P, L>(self.leaf_params.borrow(), leaf)?;
+			level_nodes.insert(convert_index_to_last_level::<P>(*index), leaf_hash);
+		}
+
+		let mut auxiliary = self.auxiliary.iter();
+		for _ in 0..P::HEIGHT {
+			let mut parents = BTreeMap::new();
+			for (&index, node) in &level_nodes {
+				let sibling_index = sibling(index).ok_or(MerkleError::InconsistentPath)?;
+				let sibling_node = match level_nodes.get(&sibling_index) {
+					// the pair was already hashed when visiting the left child
+					Some(_) if !is_left_child(index) => continue,
+					Some(sibling_node) => sibling_node,
+					None => auxiliary.next().ok_or(MerkleError::InconsistentPath)?,
+				};
+
+				let parent_node = if is_left_child(index) {
+					hash_inner_node::<P>(self.inner_params.borrow(), node, sibling_node)?
+				} else {
+					hash_inner_node::<P>(self.inner_params.borrow(), sibling_node, node)?
+				};
+				parents.insert(
+					parent(index).ok_or(MerkleError::InconsistentPath)?,
+					parent_node,
+				);
+			}
+			level_nodes = parents;
+		}
+
+		if auxiliary.next().is_some() {
+			return Err(MerkleError::InconsistentPath);
+		}
+		level_nodes.remove(&0).ok_or(MerkleError::InconsistentPath)
+	}
+}
+
+impl<P: Config, S: MerkleStorage<P>> SparseMerkleTree<P, S> {
+	/// generate one membership proof for all leaves at `indices`
+	pub fn generate_multi_membership_proof(
+		&self,
+		indices: &[u64],
+	) -> Result<MultiPath<P>, MerkleError> {
+		if self.storage.is_empty() {
+			return Err(MerkleError::EmptyTree);
+		}
+
+		let indices: BTreeSet<u64> = indices.iter().copied().collect();
+		let mut level_indices = BTreeSet::new();
+		for &index in &indices {
+			if index >= (1u64 << P::HEIGHT) {
+				return Err(MerkleError::IndexOutOfRange(index));
+			}
+			level_indices.insert(convert_index_to_last_level::<P>(index));
+		}
+
+		let mut auxiliary = Vec::new();
+		for level in 0..P::HEIGHT as usize {
+			let mut parents = BTreeSet::new();
+			for &index in &level_indices {
+				let sibling_index = sibling(index).ok_or(MerkleError::IndexOutOfRange(index))?;
+				if !level_indices.contains(&sibling_index) {
+					let sibling_node = self
+						.storage
+						.get(sibling_index)?
+						.unwrap_or_else(|| self.empty_hashes[level].clone());
+					auxiliary.push(sibling_node);
+				}
+				parents.insert(parent(index).ok_or(MerkleError::IndexOutOfRange(index))?);
+			}
+			level_indices = parents;
+		}
+
+		Ok(MultiPath {
+			indices: indices.into_iter().collect(),
+			auxiliary,
+			inner_params: Rc::clone(&self.inner_params),
+			leaf_params: Rc::clone(&self.leaf_params),
+		})
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use crate::{
+		merkle_tree::{Config, SparseMerkleTree},
+		poseidon::CRH as PoseidonCRH,
+	};
+	use ark_bls12_381::Fq;
+	use ark_ff::UniformRand;
+	use ark_std::{rc::Rc, test_rng};
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	type SMTCRH = PoseidonCRH<Fq>;
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct SMTConfig;
+	impl Config for SMTConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	#[test]
+	fn should_verify_multi_path() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let leaves: Vec<Fq> = (0..6).map(|_| Fq::rand(rng)).collect();
+		let smt = SparseMerkleTree::<SMTConfig>::new_sequential(inner_params, leaf_params, &leaves)
+			.unwrap();
+		let root = smt.root().unwrap();
+
+		let proof = smt.generate_multi_membership_proof(&[5, 0, 1]).unwrap();
+		assert_eq!(proof.indices(), &[0, 1, 5]);
+		// leaf 4, then the siblings of the parents of (0, 1) and of (4, 5)
+		assert_eq!(proof.auxiliary.len(), 3);
+
+		let proven = [leaves[0], leaves[1], leaves[5]];
+		assert!(proof.check_membership(&root, &proven).unwrap());
+
+		let wrong = [leaves[0], leaves[2], leaves[5]];
+		assert!(!proof.check_membership(&root, &wrong).unwrap());
+	}
+}
@@ -0,0 +1,359 @@
+use super::{
+	hash_empty, hash_leaf, with_domain, Config, InnerParameters, LeafParameters, MerkleError, Node,
+};
+use ark_crypto_primitives::CRH;
+use ark_ff::{to_bytes, PrimeField, ToBytes};
+use ark_std::{
+	borrow::Borrow,
+	collections::{BTreeMap, BTreeSet},
+	rc::Rc,
+	vec::Vec,
+};
+use core::convert::TryInto;
+
+/// Number of children of every inner node of a `QuadMerkleTree`
+pub const ARITY: usize = 4;
+
+/// Merkle tree where every inner node hashes four children.
+///
+/// `P::HEIGHT` counts quaternary levels, so the tree holds `4^HEIGHT` leaves
+/// and a Poseidon width-5 hasher absorbs a whole level in one permutation.
+/// Nodes use the same heap layout as `SparseMerkleTree`, with the children of
+/// node `i` at `4i + 1..=4i + 4`.
+pub struct QuadMerkleTree<P: Config> {
+	/// data of the tree
+	tree: BTreeMap<u64, Node<P>>,
+	empty_hashes: Vec<Node<P>>,
+	leaf_params: Rc<LeafParameters<P>>,
+	inner_params: Rc<InnerParameters<P>>,
+}
+
+impl<P: Config> QuadMerkleTree<P> {
+	/// obtain an empty tree
+	pub fn blank(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+	) -> Result<Self, MerkleError> {
+		let empty_hashes = gen_empty_hashes4::<P>(leaf_params.borrow(), inner_params.borrow())?;
+
+		Ok(QuadMerkleTree {
+			tree: BTreeMap::new(),
+			empty_hashes,
+			leaf_params,
+			inner_params,
+		})
+	}
+
+	/// initialize a tree (with optional data)
+	pub fn new<L: Default + ToBytes>(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+		leaves: &BTreeMap<u32, L>,
+	) -> Result<Self, MerkleError> {
+		let mut tree = Self::blank(inner_params, leaf_params)?;
+		tree.insert_batch(leaves)?;
+
+		Ok(tree)
+	}
+
+	pub fn new_sequential<L: Default + ToBytes + Clone>(
+		inner_params: Rc<InnerParameters<P>>,
+		leaf_params: Rc<LeafParameters<P>>,
+		leaves: &[L],
+	) -> Result<Self, MerkleError> {
+		let pairs: BTreeMap<u32, L> = leaves
+			.iter()
+			.enumerate()
+			.map(|(i, l)| (i as u32, l.clone()))
+			.collect();
+
+		Self::new(inner_params, leaf_params, &pairs)
+	}
+
+	pub fn insert_batch<L: Default + ToBytes>(
+		&mut self,
+		leaves: &BTreeMap<u32, L>,
+	) -> Result<(), MerkleError> {
+		let first_leaf = first_leaf_index::<P>();
+
+		let mut level_idxs: BTreeSet<u64> = BTreeSet::new();
+		for (i, leaf) in leaves {
+			if (*i as u64) >= num_leaves::<P>() {
+				return Err(MerkleError::IndexOutOfRange(*i as u64));
+			}
+			let true_index = first_leaf + (*i as u64);
+			let leaf_hash = hash_leaf::<P, _>(self.leaf_params.borrow(), leaf)?;
+			self.tree.insert(true_index, leaf_hash);
+			level_idxs.insert(parent4(true_index).ok_or(MerkleError::IndexOutOfRange(*i as u64))?);
+		}
+
+		for level in 0..P::HEIGHT as usize {
+			let mut new_idxs: BTreeSet<u64> = BTreeSet::new();
+			for i in level_idxs {
+				let children = self.children(i, level);
+				let node = hash_inner_node4::<P>(self.inner_params.borrow(), &children)?;
+				self.tree.insert(i, node);
+
+				if let Some(parent) = parent4(i) {
+					new_idxs.insert(parent);
+				}
+			}
+			level_idxs = new_idxs;
+		}
+
+		Ok(())
+	}
+
+	#[inline]
+	/// obtain the root hash
+	pub fn root(&self) -> Result<Node<P>, MerkleError> {
+		self.tree.get(&0).cloned().ok_or(MerkleError::EmptyTree)
+	}
+
+	/// generate a membership proof (does not check the data point)
+	pub fn generate_membership_proof<const N: usize>(
+		&self,
+		index: u64,
+	) -> Result<Path4<P, N>, MerkleError> {
+		if self.tree.is_empty() {
+			return Err(MerkleError::EmptyTree);
+		}
+		if index >= num_leaves::<P>() {
+			return Err(MerkleError::IndexOutOfRange(index));
+		}
+
+		let mut path = Vec::with_capacity(N);
+		let mut current_node = first_leaf_index::<P>() + index;
+		for level in 0..P::HEIGHT as usize {
+			let parent = parent4(current_node).ok_or(MerkleError::IndexOutOfRange(index))?;
+			path.push(self.children(parent, level));
+			current_node = parent;
+		}
+
+		Ok(Path4 {
+			path: path.try_into().map_err(|v: Vec<[Node<P>; ARITY]>| {
+				MerkleError::InvalidPathLength {
+					expected: N,
+					found: v.len(),
+				}
+			})?,
+			index,
+			inner_params: Rc::clone(&self.inner_params),
+			leaf_params: Rc::clone(&self.leaf_params),
+		})
+	}
+
+	/// children of the node at `index`, which sit on `level` (0 for leaves)
+	fn children(&self, index: u64, level: usize) -> [Node<P>; ARITY] {
+		let empty_hash = &self.empty_hashes[level];
+		let child = |k: u64| {
+			self.tree
+				.get(&(ARITY as u64 * index + k))
+				.cloned()
+				.unwrap_or_else(|| empty_hash.clone())
+		};
+
+		[child(1), child(2), child(3), child(4)]
+	}
+}
+
+/// Membership proof in a `QuadMerkleTree`, holding all four children of every
+/// node on the path from the leaf level up and the leaf index.
+///
+/// Base-4 digit `i` of `index` is the position of the path node among the
+/// children on level `i`.
+#[derive(Clone)]
+pub struct Path4<P: Config, const N: usize> {
+	pub(crate) path: [[Node<P>; ARITY]; N],
+	pub(crate) index: u64,
+	pub(crate) leaf_params: Rc<LeafParameters<P>>,
+	pub(crate) inner_params: Rc<InnerParameters<P>>,
+}
+
+impl<P: Config, const N: usize> Path4<P, N> {
+	/// verify the lookup proof, just checking the membership
+	pub fn check_membership<L: ToBytes>(
+		&self,
+		root_hash: &Node<P>,
+		leaf: &L,
+	) -> Result<bool, MerkleError> {
+		let prev = self.root_hash(leaf)?;
+		Ok(root_hash == &prev)
+	}
+
+	/// Return hash of root computed by the path
+	pub fn root_hash<L: ToBytes>(&self, leaf: &L) -> Result<Node<P>, MerkleError> {
+		if self.path.len() != P::HEIGHT as usize {
+			return Err(MerkleError::InvalidPathLength {
+				expected: P::HEIGHT as usize,
+				found: self.path.len(),
+			});
+		}
+
+		let claimed_leaf_hash = hash_leaf::<P, L>(self.leaf_params.borrow(), leaf)?;
+		if self.path[0][self.position(0)] != claimed_leaf_hash {
+			return Err(MerkleError::LeafNotOnPath);
+		}
+
+		let mut prev = claimed_leaf_hash;
+		// Check levels between leaf level and root.
+		for (level, children) in self.path.iter().enumerate() {
+			// Check that the previous hash is the child selected by the index.
+			if children[self.position(level)] != prev {
+				return Err(MerkleError::InconsistentPath);
+			}
+			prev = hash_inner_node4::<P>(self.inner_params.borrow(), children)?;
+		}
+
+		Ok(prev)
+	}
+
+	/// index of the leaf, as a field element
+	pub fn get_index<F: PrimeField>(&self) -> F {
+		F::from(self.index)
+	}
+
+	/// position of the path node among the children on `level`
+	#[inline]
+	pub(crate) fn position(&self, level: usize) -> usize {
+		((self.index >> (2 * level)) & 3) as usize
+	}
+}
+
+/// Returns the number of leaves of the tree.
+#[inline]
+fn num_leaves<P: Config>() -> u64 {
+	1u64 << (2 * P::HEIGHT as u64)
+}
+
+/// Returns the index of the first leaf, given the tree config.
+#[inline]
+fn first_leaf_index<P: Config>() -> u64 {
+	(num_leaves::<P>() - 1) / 3
+}
+
+/// Returns the index of the parent, given an index.
+#[inline]
+fn parent4(index: u64) -> Option<u64> {
+	if index > 0 {
+		Some((index - 1) / ARITY as u64)
+	} else {
+		None
+	}
+}
+
+/// Returns the Node hash, given the four children.
+pub(crate) fn hash_inner_node4<P: Config>(
+	parameters: &<P::H as CRH>::Parameters,
+	children: &[Node<P>; ARITY],
+) -> Result<Node<P>, MerkleError> {
+	let bytes = with_domain(P::NODE_DOMAIN, to_bytes![
+		children[0],
+		children[1],
+		children[2],
+		children[3]
+	]?);
+	let inner = <P::H as CRH>::evaluate(parameters, &bytes)?;
+	Ok(Node::Inner(inner))
+}
+
+pub fn gen_empty_hashes4<P: Config>(
+	leaf_params: &LeafParameters<P>,
+	inner_params: &InnerParameters<P>,
+) -> Result<Vec<Node<P>>, MerkleError> {
+	let mut empty_hashes = Vec::with_capacity(P::HEIGHT as usize + 1);
+
+	let mut empty_hash = hash_empty::<P>(leaf_params)?;
+	empty_hashes.push(empty_hash.clone());
+
+	for _ in 1..=P::HEIGHT {
+		let children = [
+			empty_hash.clone(),
+			empty_hash.clone(),
+			empty_hash.clone(),
+			empty_hash,
+		];
+		empty_hash = hash_inner_node4::<P>(inner_params, &children)?;
+		empty_hashes.push(empty_hash.clone());
+	}
+
+	Ok(empty_hashes)
+}
+
+#[cfg(test)]
+mod test {
+	use super::QuadMerkleTree;
+	use crate::{
+		merkle_tree::{Config, MerkleError},
+		poseidon::CRH as PoseidonCRH,
+	};
+	use ark_bls12_381::Fq;
+	use ark_crypto_primitives::CRH;
+	use ark_ff::{to_bytes, UniformRand};
+	use ark_std::{rc::Rc, test_rng};
+	use arkworks_utils::utils::common::{setup_params_x5_5, Curve};
+
+	type SMTCRH = PoseidonCRH<Fq>;
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct QuadConfig;
+	impl Config for QuadConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 2;
+	}
+
+	#[test]
+	fn should_match_reference_root() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params = Rc::new(setup_params_x5_5(curve));
+
+		let leaves: Vec<Fq> = (0..6).map(|_| Fq::rand(rng)).collect();
+		let tree =
+			QuadMerkleTree::<QuadConfig>::new_sequential(params.clone(), params.clone(), &leaves)
+				.unwrap();
+
+		// hash the 16 leaves in groups of four, then the 4 resulting nodes
+		let empty_leaf = SMTCRH::evaluate(&params, &[]).unwrap();
+		let mut level: Vec<Fq> = (0..16)
+			.map(|i| match leaves.get(i) {
+				Some(leaf) => SMTCRH::evaluate(&params, &to_bytes![leaf].unwrap()).unwrap(),
+				None => empty_leaf,
+			})
+			.collect();
+		while level.len() > 1 {
+			level = level
+				.chunks(4)
+				.map(|c| SMTCRH::evaluate(&params, &to_bytes![c[0], c[1], c[2], c[3]].unwrap()))
+				.collect::<Result<_, _>>()
+				.unwrap();
+		}
+
+		assert_eq!(tree.root().unwrap().inner().unwrap(), level[0]);
+	}
+
+	#[test]
+	fn should_generate_and_validate_proof() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let params = Rc::new(setup_params_x5_5(curve));
+
+		let leaves: Vec<Fq> = (0..6).map(|_| Fq::rand(rng)).collect();
+		let tree =
+			QuadMerkleTree::<QuadConfig>::new_sequential(params.clone(), params, &leaves).unwrap();
+		let root = tree.root().unwrap();
+
+		let proof = tree
+			.generate_membership_proof::<{ QuadConfig::HEIGHT as usize }>(5)
+			.unwrap();
+		assert!(proof.check_membership(&root, &leaves[5]).unwrap());
+		assert!(matches!(
+			proof.check_membership(&root, &leaves[0]),
+			Err(MerkleError::LeafNotOnPath)
+		));
+	}
+}
@@ -0,0 +1,344 @@
+use super::{Config, MerkleError, Node};
+use ark_std::collections::BTreeMap;
+
+/// Backend holding the nodes of a `SparseMerkleTree`, keyed by their index.
+pub trait MerkleStorage<P: Config> {
+	/// fetch the node stored at `index`, if any
+	fn get(&self, index: u64) -> Result<Option<Node<P>>, MerkleError>;
+
+	/// store a single node at `index`
+	fn put(&mut self, index: u64, node: Node<P>) -> Result<(), MerkleError>;
+
+	/// drop the node stored at `index`, if any
+	fn remove(&mut self, index: u64) -> Result<(), MerkleError>;
+
+	/// store a batch of nodes in one go
+	fn commit(&mut self, nodes: BTreeMap<u64, Node<P>>) -> Result<(), MerkleError> {
+		for (index, node) in nodes {
+			self.put(index, node)?;
+		}
+		Ok(())
+	}
+
+	/// all stored nodes, ordered by index
+	fn nodes(&self) -> Result<BTreeMap<u64, Node<P>>, MerkleError>;
+
+	/// true iff no node was stored yet
+	fn is_empty(&self) -> bool;
+}
+
+/// Storage keeping every node in memory
+#[derive(Clone)]
+pub struct InMemoryStorage<P: Config> {
+	nodes: BTreeMap<u64, Node<P>>,
+}
+
+impl<P: Config> Default for InMemoryStorage<P> {
+	fn default() -> Self {
+		Self {
+			nodes: BTreeMap::new(),
+		}
+	}
+}
+
+impl<P: Config> InMemoryStorage<P> {
+	pub fn new(nodes: BTreeMap<u64, Node<P>>) -> Self {
+		Self { nodes }
+	}
+}
+
+impl<P: Config> MerkleStorage<P> for InMemoryStorage<P> {
+	fn get(&self, index: u64) -> Result<Option<Node<P>>, MerkleError> {
+		Ok(self.nodes.get(&index).cloned())
+	}
+
+	fn put(&mut self, index: u64, node: Node<P>) -> Result<(), MerkleError> {
+		self.nodes.insert(index, node);
+		Ok(())
+	}
+
+	fn remove(&mut self, index: u64) -> Result<(), MerkleError> {
+		self.nodes.remove(&index);
+		Ok(())
+	}
+
+	fn commit(&mut self, nodes: BTreeMap<u64, Node<P>>) -> Result<(), MerkleError> {
+		self.nodes.extend(nodes);
+		Ok(())
+	}
+
+	fn nodes(&self) -> Result<BTreeMap<u64, Node<P>>, MerkleError> {
+		Ok(self.nodes.clone())
+	}
+
+	fn is_empty(&self) -> bool {
+		self.nodes.is_empty()
+	}
+}
+
+#[cfg(feature = "std")]
+pub use file::AppendLogStorage;
+
+#[cfg(feature = "std")]
+mod file {
+	use super::{Config, MerkleError, MerkleStorage, Node};
+	use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+	use ark_std::{collections::BTreeMap, vec::Vec};
+	use std::{
+		fs::{File, OpenOptions},
+		io::{ErrorKind, Read, Write},
+		path::Path,
+	};
+
+	const STORED: u8 = 0;
+	const REMOVED: u8 = 1;
+
+	/// Storage that appends every committed node to a log file.
+	///
+	/// Each record is the little-endian node index, a tag byte (0 for a stored
+	/// node, 1 for a removed one) and, for stored nodes, the canonically
+	/// serialized node. Replaying the log on `open` restores the
+	/// latest version of every node, so a tree can be reloaded without
+	/// rehashing its leaves. A last record torn by a crash is truncated away.
+	pub struct AppendLogStorage<P: Config> {
+		file: File,
+		nodes: BTreeMap<u64, Node<P>>,
+	}
+
+	impl<P: Config> AppendLogStorage<P> {
+		/// open the log at `path`, creating it if it does not exist
+		pub fn open<Q: AsRef<Path>>(path: Q) -> Result<Self, MerkleError> {
+			let mut file = OpenOptions::new()
+				.create(true)
+				.read(true)
+				.append(true)
+				.open(path)
+				.map_err(|e| MerkleError::Storage(e.into()))?;
+
+			let mut bytes = Vec::new();
+			file.read_to_end(&mut bytes)
+				.map_err(|e| MerkleError::Storage(e.into()))?;
+
+			let mut reader = &bytes[..];
+			let mut nodes = BTreeMap::new();
+			while !reader.is_empty() {
+				let record_start = bytes.len() - reader.len();
+				match Self::replay(&mut reader, &mut nodes) {
+					Ok(()) => {}
+					// the last record was cut short by a crash during `append`,
+					// drop it so that later records start at a record boundary
+					Err(SerializationError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => {
+						file.set_len(record_start as u64)
+							.map_err(|e| MerkleError::Storage(e.into()))?;
+						break;
+					}
+					Err(e) => return Err(e.into()),
+				}
+			}
+
+			Ok(Self { file, nodes })
+		}
+
+		fn replay(
+			reader: &mut &[u8],
+			nodes: &mut BTreeMap<u64, Node<P>>,
+		) -> Result<(), SerializationError> {
+			let index = u64::deserialize(&mut *reader)?;
+			match u8::deserialize(&mut *reader)? {
+				STORED => {
+					nodes.insert(index, Node::<P>::deserialize(&mut *reader)?);
+				}
+				REMOVED => {
+					nodes.remove(&index);
+				}
+				_ => return Err(SerializationError::InvalidData),
+			}
+			Ok(())
+		}
+
+		fn append(&mut self, record: &[u8]) -> Result<(), MerkleError> {
+			self.file
+				.write_all(record)
+				.and_then(|_| self.file.flush())
+				.map_err(|e| MerkleError::Storage(e.into()))
+		}
+	}
+
+	impl<P: Config> MerkleStorage<P> for AppendLogStorage<P> {
+		fn get(&self, index: u64) -> Result<Option<Node<P>>, MerkleError> {
+			Ok(self.nodes.get(&index).cloned())
+		}
+
+		fn put(&mut self, index: u64, node: Node<P>) -> Result<(), MerkleError> {
+			let mut batch = BTreeMap::new();
+			batch.insert(index, node);
+			self.commit(batch)
+		}
+
+		fn remove(&mut self, index: u64) -> Result<(), MerkleError> {
+			let mut record = Vec::new();
+			index.serialize(&mut record)?;
+			REMOVED.serialize(&mut record)?;
+			self.append(&record)?;
+			self.nodes.remove(&index);
+			Ok(())
+		}
+
+		fn commit(&mut self, nodes: BTreeMap<u64, Node<P>>) -> Result<(), MerkleError> {
+			let mut record = Vec::new();
+			for (index, node) in &nodes {
+				index.serialize(&mut record)?;
+				STORED.serialize(&mut record)?;
+				node.serialize(&mut record)?;
+			}
+			self.append(&record)?;
+			self.nodes.extend(nodes);
+			Ok(())
+		}
+
+		fn nodes(&self) -> Result<BTreeMap<u64, Node<P>>, MerkleError> {
+			Ok(self.nodes.clone())
+		}
+
+		fn is_empty(&self) -> bool {
+			self.nodes.is_empty()
+		}
+	}
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+	use super::{AppendLogStorage, InMemoryStorage};
+	use crate::{
+		merkle_tree::{Config, SparseMerkleTree},
+		poseidon::CRH as PoseidonCRH,
+	};
+	use ark_bls12_381::Fq;
+	use ark_ff::UniformRand;
+	use ark_std::{collections::BTreeMap, rc::Rc, test_rng};
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	type SMTCRH = PoseidonCRH<Fq>;
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct SMTConfig;
+	impl Config for SMTConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	#[test]
+	fn should_reload_tree_from_append_log() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let path = std::env::temp_dir().join(format!(
+			"arkworks-gadgets-merkle-log-{}",
+			std::process::id()
+		));
+		let _ = std::fs::remove_file(&path);
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let pairs: BTreeMap<u32, Fq> = leaves
+			.iter()
+			.enumerate()
+			.map(|(i, l)| (i as u32, *l))
+			.collect();
+
+		let storage = AppendLogStorage::<SMTConfig>::open(&path).unwrap();
+		let mut smt = SparseMerkleTree::<SMTConfig, _>::from_storage(
+			storage,
+			inner_params.clone(),
+			leaf_params.clone(),
+		)
+		.unwrap();
+		smt.insert_batch(&pairs).unwrap();
+		let root = smt.root().unwrap();
+		drop(smt);
+
+		let storage = AppendLogStorage::<SMTConfig>::open(&path).unwrap();
+		let reloaded = SparseMerkleTree::<SMTConfig, _>::from_storage(
+			storage,
+			inner_params.clone(),
+			leaf_params.clone(),
+		)
+		.unwrap();
+		assert_eq!(reloaded.root().unwrap(), root);
+
+		let in_memory = SparseMerkleTree::<SMTConfig, InMemoryStorage<SMTConfig>>::new_sequential(
+			inner_params,
+			leaf_params,
+			&leaves,
+		)
+		.unwrap();
+		assert_eq!(in_memory.root().unwrap(), root);
+
+		std::fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn should_drop_torn_last_record_on_open() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let path = std::env::temp_dir().join(format!(
+			"arkworks-gadgets-merkle-torn-log-{}",
+			std::process::id()
+		));
+		let _ = std::fs::remove_file(&path);
+
+		let storage = AppendLogStorage::<SMTConfig>::open(&path).unwrap();
+		let mut smt = SparseMerkleTree::<SMTConfig, _>::from_storage(
+			storage,
+			inner_params.clone(),
+			leaf_params.clone(),
+		)
+		.unwrap();
+		let mut pairs = BTreeMap::new();
+		pairs.insert(0u32, Fq::rand(rng));
+		smt.insert_batch(&pairs).unwrap();
+		let root = smt.root().unwrap();
+		let complete_len = std::fs::metadata(&path).unwrap().len();
+
+		let mut pairs = BTreeMap::new();
+		pairs.insert(1u32, Fq::rand(rng));
+		smt.insert_batch(&pairs).unwrap();
+		drop(smt);
+
+		// cut the log in the middle of the first record written by the second batch
+		let file = std::fs::OpenOptions::new().write(true).open(&path).unwrap();
+		file.set_len(complete_len + 5).unwrap();
+		drop(file);
+
+		let storage = AppendLogStorage::<SMTConfig>::open(&path).unwrap();
+		assert_eq!(std::fs::metadata(&path).unwrap().len(), complete_len);
+		let mut reloaded = SparseMerkleTree::<SMTConfig, _>::from_storage(
+			storage,
+			inner_params.clone(),
+			leaf_params.clone(),
+		)
+		.unwrap();
+		assert_eq!(reloaded.root().unwrap(), root);
+
+		// the log keeps working after the torn record was dropped
+		reloaded.insert_batch(&pairs).unwrap();
+		let new_root = reloaded.root().unwrap();
+		drop(reloaded);
+
+		let storage = AppendLogStorage::<SMTConfig>::open(&path).unwrap();
+		let reloaded =
+			SparseMerkleTree::<SMTConfig, _>::from_storage(storage, inner_params, leaf_params)
+				.unwrap();
+		assert_eq!(reloaded.root().unwrap(), new_root);
+
+		std::fs::remove_file(&path).unwrap();
+	}
+}
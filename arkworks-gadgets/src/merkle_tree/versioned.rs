@@ -0,0 +1,237 @@
+use super::{
+	storage::{InMemoryStorage, MerkleStorage},
+	Config, MerkleError, Node, Path, SparseMerkleTree,
+};
+use ark_ff::ToBytes;
+use ark_std::{collections::BTreeMap, vec::Vec};
+
+/// `SparseMerkleTree` that remembers every inserted batch, so it can be
+/// rolled back when the chain it follows reorgs.
+///
+/// Version 0 is the tree the wrapper was created with, and every call to
+/// `insert_batch` creates the next version. For every version the tree keeps
+/// the previous value of each node the batch overwrote, which is enough to
+/// answer root and path queries against any earlier version.
+pub struct VersionedMerkleTree<P: Config, S: MerkleStorage<P> = InMemoryStorage<P>> {
+	tree: SparseMerkleTree<P, S>,
+	/// nodes overwritten by the batch creating version `i + 1`, with the value
+	/// they had before it
+	journal: Vec<BTreeMap<u64, Option<Node<P>>>>,
+	/// root of every version
+	roots: Vec<Option<Node<P>>>,
+}
+
+impl<P: Config, S: MerkleStorage<P>> VersionedMerkleTree<P, S> {
+	/// start versioning `tree`, its current state becomes version 0
+	pub fn new(tree: SparseMerkleTree<P, S>) -> Result<Self, MerkleError> {
+		let root = tree.storage.get(0)?;
+
+		Ok(VersionedMerkleTree {
+			tree,
+			journal: Vec::new(),
+			roots: vec![root],
+		})
+	}
+
+	/// insert a batch of leaves and return the version it created
+	pub fn insert_batch<L: Default + ToBytes>(
+		&mut self,
+		leaves: &BTreeMap<u32, L>,
+	) -> Result<u64, MerkleError> {
+		let batch = self.tree.compute_batch(leaves)?;
+
+		let mut previous = BTreeMap::new();
+		for index in batch.keys() {
+			previous.insert(*index, self.tree.storage.get(*index)?);
+		}
+		let root = batch.get(&0).cloned();
+
+		self.tree.storage.commit(batch)?;
+		self.journal.push(previous);
+		self.roots.push(root);
+
+		Ok(self.version())
+	}
+
+	#[inline]
+	/// latest version of the tree
+	pub fn version(&self) -> u64 {
+		self.journal.len() as u64
+	}
+
+	#[inline]
+	/// obtain the tree at its latest version
+	pub fn tree(&self) -> &SparseMerkleTree<P, S> {
+		&self.tree
+	}
+
+	/// obtain the root hash the tree had at `version`
+	pub fn root_at(&self, version: u64) -> Result<Node<P>, MerkleError> {
+		self.check_version(version)?;
+		self.roots[version as usize]
+			.clone()
+			.ok_or(MerkleError::EmptyTree)
+	}
+
+	/// undo every batch inserted after `version`
+	pub fn rollback_to(&mut self, version: u64) -> Result<(), MerkleError> {
+		self.check_version(version)?;
+
+		while self.version() > version {
+			let previous = match self.journal.pop() {
+				Some(previous) => previous,
+				None => break,
+			};
+			let mut restored = BTreeMap::new();
+			for (index, node) in previous {
+				match node {
+					Some(node) => {
+						restored.insert(index, node);
+					}
+					None => self.tree.storage.remove(index)?,
+				}
+			}
+			self.tree.storage.commit(restored)?;
+			self.roots.pop();
+		}
+
+		Ok(())
+	}
+
+	/// generate a membership proof against the root of `version`
+	pub fn generate_membership_proof_at<const N: usize>(
+		&self,
+		version: u64,
+		index: u64,
+	) -> Result<Path<P, N>, MerkleError> {
+		self.root_at(version)?;
+
+		self.tree.build_path(index, |i| self.node_at(version, i))
+	}
+
+	/// value of the node at `index` in `version`: the value it had before the
+	/// first later batch overwrote it, or the current one if none did
+	fn node_at(&self, version: u64, index: u64) -> Result<Option<Node<P>>, MerkleError> {
+		for previous in &self.journal[version as usize..] {
+			if let Some(node) = previous.get(&index) {
+				return Ok(node.clone());
+			}
+		}
+
+		self.tree.storage.get(index)
+	}
+
+	fn check_version(&self, version: u64) -> Result<(), MerkleError> {
+		if version > self.version() {
+			return Err(MerkleError::UnknownVersion(version));
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::VersionedMerkleTree;
+	use crate::{
+		merkle_tree::{Config, MerkleError, SparseMerkleTree},
+		poseidon::CRH as PoseidonCRH,
+	};
+	use ark_bls12_381::Fq;
+	use ark_ff::UniformRand;
+	use ark_std::{collections::BTreeMap, rc::Rc, test_rng};
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	type SMTCRH = PoseidonCRH<Fq>;
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct SMTConfig;
+	impl Config for SMTConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	fn batch(start: u32, leaves: &[Fq]) -> BTreeMap<u32, Fq> {
+		leaves
+			.iter()
+			.enumerate()
+			.map(|(i, l)| (start + i as u32, *l))
+			.collect()
+	}
+
+	#[test]
+	fn should_prove_against_historical_root() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = SparseMerkleTree::<SMTConfig>::blank(inner_params.clone(), leaf_params.clone())
+			.unwrap();
+		let mut vmt = VersionedMerkleTree::new(smt).unwrap();
+
+		let v1 = vmt.insert_batch(&batch(0, &leaves[..2])).unwrap();
+		let v2 = vmt.insert_batch(&batch(2, &leaves[2..])).unwrap();
+		assert_eq!((v1, v2), (1, 2));
+
+		let expected =
+			SparseMerkleTree::<SMTConfig>::new_sequential(inner_params, leaf_params, &leaves[..2])
+				.unwrap();
+		let root = vmt.root_at(v1).unwrap();
+		assert_eq!(root, expected.root().unwrap());
+
+		// index 1 has a sibling path that changed in version 2
+		let path = vmt
+			.generate_membership_proof_at::<{ SMTConfig::HEIGHT as usize }>(v1, 1)
+			.unwrap();
+		let expected_path = expected
+			.generate_membership_proof::<{ SMTConfig::HEIGHT as usize }>(1)
+			.unwrap();
+		assert!(path.path == expected_path.path);
+		assert!(path.check_membership(&root, &leaves[1]).unwrap());
+
+		assert!(matches!(
+			vmt.root_at(3),
+			Err(MerkleError::UnknownVersion(3))
+		));
+		assert!(matches!(vmt.root_at(0), Err(MerkleError::EmptyTree)));
+	}
+
+	#[test]
+	fn should_rollback_to_earlier_version() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bls381;
+
+		let inner_params = Rc::new(setup_params_x5_3(curve));
+		let leaf_params = inner_params.clone();
+
+		let leaves = vec![Fq::rand(rng), Fq::rand(rng), Fq::rand(rng)];
+		let smt = SparseMerkleTree::<SMTConfig>::new_sequential(
+			inner_params.clone(),
+			leaf_params.clone(),
+			&leaves[..1],
+		)
+		.unwrap();
+		let mut vmt = VersionedMerkleTree::new(smt).unwrap();
+		let root0 = vmt.root_at(0).unwrap();
+
+		vmt.insert_batch(&batch(1, &leaves[1..2])).unwrap();
+		let root1 = vmt.root_at(1).unwrap();
+		vmt.insert_batch(&batch(1, &leaves[2..])).unwrap();
+
+		vmt.rollback_to(1).unwrap();
+		assert_eq!(vmt.version(), 1);
+		assert_eq!(vmt.tree().root().unwrap(), root1);
+
+		vmt.rollback_to(0).unwrap();
+		assert_eq!(vmt.tree().root().unwrap(), root0);
+		let path = vmt
+			.tree()
+			.generate_membership_proof::<{ SMTConfig::HEIGHT as usize }>(0)
+			.unwrap();
+		assert!(path.check_membership(&root0, &leaves[0]).unwrap());
+	}
+}
@@ -0,0 +1,131 @@
+use super::{
+	circom::field_to_hex, gen_empty_hashes, Config, InnerParameters, LeafParameters, MerkleError,
+};
+use ark_ff::{to_bytes, BigInteger, PrimeField};
+use ark_std::{string::String, vec::Vec};
+
+/// Hash of an empty subtree on every level of a tree, from the empty leaf up
+/// to the root of the empty tree.
+///
+/// The table is derived from the same `gen_empty_hashes` the prover uses, so
+/// contracts and pallets that hard-code one of its exports stay in sync with
+/// the config it was generated for.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZeroHashes<F: PrimeField> {
+	hashes: Vec<F>,
+}
+
+impl<F: PrimeField> ZeroHashes<F> {
+	/// compute the table of the tree described by `P`
+	pub fn new<P: Config>(
+		leaf_params: &LeafParameters<P>,
+		inner_params: &InnerParameters<P>,
+	) -> Result<Self, MerkleError> {
+		let hashes = gen_empty_hashes::<P>(leaf_params, inner_params)?
+			.iter()
+			.map(|node| Ok(F::from_le_bytes_mod_order(&to_bytes![node]?)))
+			.collect::<Result<Vec<F>, MerkleError>>()?;
+
+		Ok(Self { hashes })
+	}
+
+	/// zero hashes as field elements, the empty leaf first
+	pub fn as_field_elements(&self) -> &[F] {
+		&self.hashes
+	}
+
+	/// zero hashes as `0x`-prefixed big-endian hex strings, the way EVM
+	/// contracts store them
+	pub fn to_hex(&self) -> Vec<String> {
+		self.hashes.iter().map(field_to_hex).collect()
+	}
+
+	/// Rust source declaring the zero hashes as a `const` array named `name`,
+	/// every entry being the little-endian serialization of one hash
+	pub fn to_rust_const(&self, name: &str) -> String {
+		let size = F::BigInt::NUM_LIMBS * 8;
+		let mut out = format!(
+			"pub const {}: [[u8; {}]; {}] = [\n",
+			name,
+			size,
+			self.hashes.len()
+		);
+		for hash in &self.hashes {
+			let bytes: Vec<String> = hash
+				.into_repr()
+				.to_bytes_le()
+				.iter()
+				.map(|b| format!("0x{:02x}", b))
+				.collect();
+			out.push_str(&format!("\t[{}],\n", bytes.join(", ")));
+		}
+		out.push_str("];\n");
+
+		out
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::ZeroHashes;
+	use crate::{merkle_tree::Config, poseidon::CRH as PoseidonCRH};
+	use ark_bls12_381::Fq;
+	use ark_crypto_primitives::CRH;
+	use ark_ff::{to_bytes, PrimeField};
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	type SMTCRH = PoseidonCRH<Fq>;
+
+	/// serialized `Fq` element 42
+	const fn zero_leaf() -> [u8; 48] {
+		let mut bytes = [0u8; 48];
+		bytes[0] = 42;
+		bytes
+	}
+	const ZERO_LEAF: [u8; 48] = zero_leaf();
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct ZeroConfig;
+	impl Config for ZeroConfig {
+		type H = SMTCRH;
+		type LeafH = SMTCRH;
+
+		const HEIGHT: u8 = 3;
+		const ZERO_LEAF: Option<&'static [u8]> = Some(&ZERO_LEAF);
+	}
+
+	#[test]
+	fn should_export_zero_hashes_of_custom_zero_leaf() {
+		let params = setup_params_x5_3(Curve::Bls381);
+
+		let zeros = ZeroHashes::<Fq>::new::<ZeroConfig>(&params, &params).unwrap();
+		let hashes = zeros.as_field_elements();
+		assert_eq!(hashes.len(), ZeroConfig::HEIGHT as usize + 1);
+
+		let mut expected = SMTCRH::evaluate(&params, &to_bytes![Fq::from(42u64)].unwrap()).unwrap();
+		assert_eq!(hashes[0], expected);
+		for hash in &hashes[1..] {
+			expected = SMTCRH::evaluate(&params, &to_bytes![expected, expected].unwrap()).unwrap();
+			assert_eq!(*hash, expected);
+		}
+
+		let hex = zeros.to_hex();
+		assert_eq!(hex.len(), hashes.len());
+		assert_eq!(
+			Fq::from_be_bytes_mod_order(&hex_to_bytes(&hex[2])),
+			hashes[2]
+		);
+
+		let source = zeros.to_rust_const("ZERO_HASHES");
+		assert!(source.starts_with("pub const ZERO_HASHES: [[u8; 48]; 4] = [\n"));
+		assert_eq!(source.lines().count(), hashes.len() + 2);
+	}
+
+	fn hex_to_bytes(hex: &str) -> Vec<u8> {
+		let hex = hex.trim_start_matches("0x");
+		(0..hex.len())
+			.step_by(2)
+			.map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+			.collect()
+	}
+}
@@ -1,4 +1,5 @@
 use super::{MiMCParameters, Rounds, CRH};
+use crate::field_hasher::constraints::FieldHasherGadget;
 use ark_crypto_primitives::crh::constraints::{CRHGadget as CRHGadgetTrait, TwoToOneCRHGadget};
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
@@ -146,6 +147,25 @@ impl<F: PrimeField, P: Rounds> CRHGadgetTrait<CRH<F, P>, F> for CRHGadget<F, P>
 	}
 }
 
+impl<F: PrimeField, P: Rounds> FieldHasherGadget<CRH<F, P>, F> for CRHGadget<F, P> {
+	fn hash(
+		parameters: &MiMCParametersVar<F>,
+		inputs: &[FpVar<F>],
+	) -> Result<FpVar<F>, SynthesisError> {
+		if inputs.len() > P::WIDTH {
+			return Err(SynthesisError::Unsatisfiable);
+		}
+
+		let mut buffer = vec![FpVar::zero(); P::WIDTH];
+		buffer[..inputs.len()].clone_from_slice(inputs);
+		let result = Self::mimc(parameters, buffer)?;
+		result
+			.get(0)
+			.cloned()
+			.ok_or(SynthesisError::AssignmentMissing)
+	}
+}
+
 impl<F: PrimeField, P: Rounds> TwoToOneCRHGadget<CRH<F, P>, F> for CRHGadget<F, P> {
 	type OutputVar = FpVar<F>;
 	type ParametersVar = MiMCParametersVar<F>;
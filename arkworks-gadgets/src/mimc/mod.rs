@@ -1,4 +1,4 @@
-use crate::ark_std::string::ToString;
+use crate::{ark_std::string::ToString, field_hasher::FieldHasher};
 use ark_crypto_primitives::{crh::TwoToOneCRH, Error, CRH as CRHTrait};
 use ark_ff::{fields::PrimeField, BigInteger};
 use ark_std::{error::Error as ArkError, marker::PhantomData, rand::Rng, vec::Vec};
@@ -144,6 +144,19 @@ impl<F: PrimeField, P: Rounds> CRHTrait for CRH<F, P> {
 	}
 }
 
+impl<F: PrimeField, P: Rounds> FieldHasher<F> for CRH<F, P> {
+	fn hash(parameters: &MiMCParameters<F>, inputs: &[F]) -> Result<F, Error> {
+		if inputs.len() > P::WIDTH {
+			return Err(MiMCError::InvalidInputs.into());
+		}
+
+		let mut buffer = vec![F::zero(); P::WIDTH];
+		buffer[..inputs.len()].copy_from_slice(inputs);
+		let result = Self::mimc(parameters, buffer)?;
+		Ok(result.get(0).cloned().ok_or(MiMCError::InvalidInputs)?)
+	}
+}
+
 impl<F: PrimeField, P: Rounds> TwoToOneCRH for CRH<F, P> {
 	type Output = F;
 	type Parameters = MiMCParameters<F>;
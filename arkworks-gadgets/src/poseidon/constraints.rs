@@ -1,4 +1,4 @@
-use crate::poseidon::CRH;
+use crate::{field_hasher::constraints::FieldHasherGadget, poseidon::CRH};
 use ark_crypto_primitives::crh::constraints::{CRHGadget as CRHGadgetTrait, TwoToOneCRHGadget};
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
@@ -10,6 +10,7 @@ use ark_r1cs_std::{
 use ark_relations::r1cs::{Namespace, SynthesisError};
 use ark_std::{marker::PhantomData, vec::Vec};
 use arkworks_utils::poseidon::{
+	optimized::OptimizedPoseidonParameters,
 	sbox::{constraints::SboxConstraints, PoseidonSbox},
 	PoseidonParameters,
 };
@@ -32,13 +33,18 @@ pub struct PoseidonParametersVar<F: PrimeField> {
 	pub width: u8,
 	/// The S-box to apply in the sub words layer.
 	pub sbox: PoseidonSbox,
+	/// Constants of the optimized permutation, kept native as they are only
+	/// ever multiplied with or added to variables. Only set for parameters
+	/// allocated as constants, so they always match `round_keys` and
+	/// `mds_matrix`.
+	pub optimized: Option<OptimizedPoseidonParameters<F>>,
 }
 
 impl<F: PrimeField> AllocVar<PoseidonParameters<F>, F> for PoseidonParametersVar<F> {
 	fn new_variable<T: Borrow<PoseidonParameters<F>>>(
 		_cs: impl Into<Namespace<F>>,
 		f: impl FnOnce() -> Result<T, SynthesisError>,
-		_mode: AllocationMode,
+		mode: AllocationMode,
 	) -> Result<Self, SynthesisError> {
 		let params = f()?.borrow().clone();
 		let mut round_keys_var = Vec::new();
@@ -57,6 +63,12 @@ impl<F: PrimeField> AllocVar<PoseidonParameters<F>, F> for PoseidonParametersVar
 		let partial_rounds = params.partial_rounds;
 		let width = params.width;
 		let sbox = params.sbox;
+		// The optimized constants are derived natively from the round keys and
+		// the MDS matrix, so other modes keep the dense permutation
+		let optimized = match mode {
+			AllocationMode::Constant => params.optimized,
+			_ => None,
+		};
 
 		Ok(Self {
 			round_keys: round_keys_var,
@@ -65,6 +77,7 @@ impl<F: PrimeField> AllocVar<PoseidonParameters<F>, F> for PoseidonParametersVar
 			partial_rounds,
 			width,
 			sbox,
+			optimized,
 		})
 	}
 }
@@ -72,10 +85,19 @@ impl<F: PrimeField> AllocVar<PoseidonParameters<F>, F> for PoseidonParametersVar
 pub struct CRHGadget<F: PrimeField>(PhantomData<F>);
 
 impl<F: PrimeField> CRHGadget<F> {
-	fn permute(
+	pub(crate) fn permute(
 		params: &PoseidonParametersVar<F>,
 		mut state: Vec<FpVar<F>>,
 	) -> Result<Vec<FpVar<F>>, SynthesisError> {
+		// The sparse matrices mix a full state only, shorter ones keep the
+		// dense rounds
+		match &params.optimized {
+			Some(optimized) if state.len() == params.width as usize => {
+				return Self::permute_optimized(params, optimized, state);
+			}
+			_ => {}
+		}
+
 		let nr = (params.full_rounds + params.partial_rounds) as usize;
 		for r in 0..nr {
 			state.iter_mut().enumerate().for_each(|(i, a)| {
@@ -108,6 +130,76 @@ impl<F: PrimeField> CRHGadget<F> {
 		}
 		Ok(state)
 	}
+
+	/// Same as `permute`, with the sparse matrices and the moved constants of
+	/// `optimized` in the partial rounds. Every partial round costs
+	/// `3 * width - 2` multiplications by constants instead of `width^2`.
+	fn permute_optimized(
+		params: &PoseidonParametersVar<F>,
+		optimized: &OptimizedPoseidonParameters<F>,
+		mut state: Vec<FpVar<F>>,
+	) -> Result<Vec<FpVar<F>>, SynthesisError> {
+		let half_rounds = (params.full_rounds as usize) / 2;
+		let pre_sparse_matrix: Vec<Vec<FpVar<F>>> = optimized
+			.pre_sparse_matrix
+			.iter()
+			.map(|row| row.iter().map(|m| FpVar::Constant(*m)).collect())
+			.collect();
+		let full_round = |state: &mut Vec<FpVar<F>>,
+		                  keys: &[F],
+		                  matrix: &[Vec<FpVar<F>>]|
+		 -> Result<(), SynthesisError> {
+			state
+				.iter_mut()
+				.zip(keys.iter())
+				.for_each(|(a, c)| *a += *c);
+			state
+				.iter_mut()
+				.try_for_each(|a| params.sbox.synthesize_sbox(a).map(|f| *a = f))?;
+			*state = matrix
+				.iter()
+				.map(|row| {
+					row.iter()
+						.zip(state.iter())
+						.fold(FpVar::<F>::zero(), |acc, (m, a)| acc + a * m)
+				})
+				.collect();
+			Ok(())
+		};
+
+		for (r, keys) in optimized.full_round_keys[..half_rounds].iter().enumerate() {
+			let matrix = if r + 1 == half_rounds {
+				&pre_sparse_matrix
+			} else {
+				&params.mds_matrix
+			};
+			full_round(&mut state, keys, matrix)?;
+		}
+
+		for (c, sparse) in optimized
+			.partial_round_keys
+			.iter()
+			.zip(optimized.sparse_matrices.iter())
+		{
+			state[0] = params.sbox.synthesize_sbox(&(&state[0] + *c))?;
+
+			let first = sparse
+				.row
+				.iter()
+				.zip(state.iter())
+				.fold(FpVar::<F>::zero(), |acc, (m, a)| acc + a * *m);
+			let (sboxed, rest) = state.split_at_mut(1);
+			for (m, a) in sparse.column.iter().zip(rest.iter_mut()) {
+				*a += &sboxed[0] * *m;
+			}
+			state[0] = first;
+		}
+
+		for keys in &optimized.full_round_keys[half_rounds..] {
+			full_round(&mut state, keys, &params.mds_matrix)?;
+		}
+		Ok(state)
+	}
 }
 
 impl<F: PrimeField> CRHGadgetTrait<CRH<F>, F> for CRHGadget<F> {
@@ -138,6 +230,26 @@ impl<F: PrimeField> CRHGadgetTrait<CRH<F>, F> for CRHGadget<F> {
 	}
 }
 
+impl<F: PrimeField> FieldHasherGadget<CRH<F>, F> for CRHGadget<F> {
+	fn hash(
+		parameters: &PoseidonParametersVar<F>,
+		inputs: &[FpVar<F>],
+	) -> Result<FpVar<F>, SynthesisError> {
+		if inputs.len() >= parameters.width.into() {
+			return Err(SynthesisError::Unsatisfiable);
+		}
+
+		let mut buffer = vec![FpVar::zero()];
+		buffer.extend_from_slice(inputs);
+		let result = Self::permute(parameters, buffer)?;
+
+		result
+			.get(0)
+			.cloned()
+			.ok_or(SynthesisError::AssignmentMissing)
+	}
+}
+
 impl<F: PrimeField> TwoToOneCRHGadget<CRH<F>, F> for CRHGadget<F> {
 	type OutputVar = FpVar<F>;
 	type ParametersVar = PoseidonParametersVar<F>;
@@ -161,6 +273,7 @@ impl<F: PrimeField> TwoToOneCRHGadget<CRH<F>, F> for CRHGadget<F> {
 #[cfg(test)]
 mod test {
 	use super::*;
+	use crate::field_hasher::FieldHasher;
 	use ark_crypto_primitives::crh::CRH as CRHTrait;
 	use ark_ed_on_bn254::Fq;
 	use ark_ff::to_bytes;
@@ -169,7 +282,7 @@ mod test {
 		R1CSVar,
 	};
 	use ark_relations::r1cs::ConstraintSystem;
-	use arkworks_utils::utils::common::setup_params_x5_3;
+	use arkworks_utils::utils::common::{setup_params_x5_3, setup_params_x5_5};
 
 	type PoseidonCRH3 = CRH<Fq>;
 	type PoseidonCRH3Gadget = CRHGadget<Fq>;
@@ -211,4 +324,83 @@ mod test {
 				.unwrap();
 		assert_eq!(res, res_var.value().unwrap());
 	}
+
+	#[test]
+	fn optimized_permutation_native_equality() {
+		let curve = arkworks_utils::utils::common::Curve::Bn254;
+		let params = setup_params_x5_3::<Fq>(curve);
+		let optimized = params.clone().optimized().unwrap();
+		let inp = to_bytes![Fq::from(1u128), Fq::from(2u128)].unwrap();
+
+		let synthesize = |params: &PoseidonParameters<Fq>| {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let params_var = PoseidonParametersVar::new_constant(cs.clone(), params).unwrap();
+			let inp_var = Vec::<UInt8<Fq>>::new_witness(cs.clone(), || Ok(inp.clone())).unwrap();
+			let res_var =
+				<PoseidonCRH3Gadget as CRHGadgetTrait<_, _>>::evaluate(&params_var, &inp_var)
+					.unwrap();
+			assert!(cs.is_satisfied().unwrap());
+			let num_lcs = cs.borrow().unwrap().num_linear_combinations;
+			(res_var.value().unwrap(), cs.num_constraints(), num_lcs)
+		};
+
+		let res = PoseidonCRH3::evaluate(&params, &inp).unwrap();
+		let (res_var, num_constraints, num_lcs) = synthesize(&params);
+		let (optimized_res_var, optimized_num_constraints, optimized_num_lcs) =
+			synthesize(&optimized);
+		assert_eq!(res, PoseidonCRH3::evaluate(&optimized, &inp).unwrap());
+		assert_eq!(res, res_var);
+		assert_eq!(res, optimized_res_var);
+
+		// The linear layers are free in R1CS, the S-boxes cost the same
+		assert_eq!(optimized_num_constraints, num_constraints);
+		assert!(optimized_num_lcs < num_lcs);
+	}
+
+	#[test]
+	fn optimized_permutation_input_params() {
+		let curve = arkworks_utils::utils::common::Curve::Bn254;
+		let params = setup_params_x5_3::<Fq>(curve);
+		let optimized = params.clone().optimized().unwrap();
+		let inputs = [Fq::from(1u64), Fq::from(2u64)];
+		let res = <PoseidonCRH3 as FieldHasher<_>>::hash(&params, &inputs).unwrap();
+
+		for params in [params, optimized].iter() {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let params_var = PoseidonParametersVar::new_input(cs.clone(), || Ok(params)).unwrap();
+			assert!(params_var.optimized.is_none());
+			let inputs_var = inputs
+				.iter()
+				.map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)).unwrap())
+				.collect::<Vec<_>>();
+			let res_var =
+				<PoseidonCRH3Gadget as FieldHasherGadget<_, _>>::hash(&params_var, &inputs_var)
+					.unwrap();
+			assert!(cs.is_satisfied().unwrap());
+			assert_eq!(res, res_var.value().unwrap());
+		}
+	}
+
+	#[test]
+	fn optimized_permutation_short_input() {
+		let curve = arkworks_utils::utils::common::Curve::Bn254;
+		let params = setup_params_x5_5::<Fq>(curve);
+		let optimized = params.clone().optimized().unwrap();
+		let inputs = [Fq::from(1u64), Fq::from(2u64)];
+		let res = <PoseidonCRH3 as FieldHasher<_>>::hash(&params, &inputs).unwrap();
+
+		for params in [params, optimized].iter() {
+			let cs = ConstraintSystem::<Fq>::new_ref();
+			let params_var = PoseidonParametersVar::new_constant(cs.clone(), params).unwrap();
+			let inputs_var = inputs
+				.iter()
+				.map(|x| FpVar::new_witness(cs.clone(), || Ok(*x)).unwrap())
+				.collect::<Vec<_>>();
+			let res_var =
+				<PoseidonCRH3Gadget as FieldHasherGadget<_, _>>::hash(&params_var, &inputs_var)
+					.unwrap();
+			assert!(cs.is_satisfied().unwrap());
+			assert_eq!(res, res_var.value().unwrap());
+		}
+	}
 }
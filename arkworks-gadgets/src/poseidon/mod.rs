@@ -1,15 +1,28 @@
+use crate::field_hasher::FieldHasher;
 use ark_crypto_primitives::{crh::TwoToOneCRH, Error, CRH as CRHTrait};
 use ark_ff::fields::PrimeField;
 use ark_std::{marker::PhantomData, rand::Rng, vec::Vec};
-use arkworks_utils::poseidon::{PoseidonError, PoseidonParameters};
+use arkworks_utils::poseidon::{
+	optimized::OptimizedPoseidonParameters, PoseidonError, PoseidonParameters,
+};
 
 #[cfg(feature = "r1cs")]
 pub mod constraints;
+pub mod sponge;
 
 pub struct CRH<F: PrimeField>(PhantomData<F>);
 
 impl<F: PrimeField> CRH<F> {
 	fn permute(params: &PoseidonParameters<F>, mut state: Vec<F>) -> Result<Vec<F>, PoseidonError> {
+		// The sparse matrices mix a full state only, shorter ones keep the
+		// dense rounds
+		match &params.optimized {
+			Some(optimized) if state.len() == params.width as usize => {
+				return Self::permute_optimized(params, optimized, state);
+			}
+			_ => {}
+		}
+
 		let nr = (params.full_rounds + params.partial_rounds) as usize;
 		for r in 0..nr {
 			state.iter_mut().enumerate().for_each(|(i, a)| {
@@ -39,6 +52,55 @@ impl<F: PrimeField> CRH<F> {
 		}
 		Ok(state)
 	}
+
+	/// Same as `permute`, with the sparse matrices and the moved constants of
+	/// `optimized` in the partial rounds.
+	fn permute_optimized(
+		params: &PoseidonParameters<F>,
+		optimized: &OptimizedPoseidonParameters<F>,
+		mut state: Vec<F>,
+	) -> Result<Vec<F>, PoseidonError> {
+		let half_rounds = (params.full_rounds as usize) / 2;
+		let full_round =
+			|state: &mut Vec<F>, keys: &[F], matrix: &[Vec<F>]| -> Result<(), PoseidonError> {
+				state.iter_mut().zip(keys.iter()).for_each(|(a, c)| *a += c);
+				state
+					.iter_mut()
+					.try_for_each(|a| params.sbox.apply_sbox(*a).map(|f| *a = f))?;
+				*state = matrix
+					.iter()
+					.map(|row| {
+						row.iter()
+							.zip(state.iter())
+							.fold(F::zero(), |acc, (m, a)| acc + *m * a)
+					})
+					.collect();
+				Ok(())
+			};
+
+		for (r, keys) in optimized.full_round_keys[..half_rounds].iter().enumerate() {
+			let matrix = if r + 1 == half_rounds {
+				&optimized.pre_sparse_matrix
+			} else {
+				&params.mds_matrix
+			};
+			full_round(&mut state, keys, matrix)?;
+		}
+
+		for (c, sparse) in optimized
+			.partial_round_keys
+			.iter()
+			.zip(optimized.sparse_matrices.iter())
+		{
+			state[0] = params.sbox.apply_sbox(state[0] + c)?;
+			state = sparse.apply(&state);
+		}
+
+		for keys in &optimized.full_round_keys[half_rounds..] {
+			full_round(&mut state, keys, &params.mds_matrix)?;
+		}
+		Ok(state)
+	}
 }
 
 impl<F: PrimeField> CRHTrait for CRH<F> {
@@ -77,6 +139,20 @@ impl<F: PrimeField> CRHTrait for CRH<F> {
 	}
 }
 
+impl<F: PrimeField> FieldHasher<F> for CRH<F> {
+	fn hash(parameters: &PoseidonParameters<F>, inputs: &[F]) -> Result<F, Error> {
+		if inputs.len() >= parameters.width.into() {
+			return Err(PoseidonError::InvalidInputs.into());
+		}
+
+		let mut buffer = vec![F::zero()];
+		buffer.extend_from_slice(inputs);
+		let result = Self::permute(parameters, buffer)?;
+
+		Ok(result.get(0).cloned().ok_or(PoseidonError::InvalidInputs)?)
+	}
+}
+
 impl<F: PrimeField> TwoToOneCRH for CRH<F> {
 	type Output = F;
 	type Parameters = PoseidonParameters<F>;
@@ -113,12 +189,13 @@ mod test {
 	// use ark_bn254::Fq as Bn254Fq;
 	use ark_ed_on_bn254::Fq;
 
-	use ark_ff::{BigInteger, Field};
-	use ark_std::One;
+	use ark_ff::{BigInteger, Field, UniformRand};
+	use ark_std::{test_rng, One};
 
 	use arkworks_utils::utils::{
 		common::{
-			setup_params_x5_2, setup_params_x5_3, setup_params_x5_4, setup_params_x5_5, Curve,
+			setup_params_x17_3, setup_params_x3_3, setup_params_x5_2, setup_params_x5_3,
+			setup_params_x5_4, setup_params_x5_5, Curve,
 		},
 		parse_vec,
 	};
@@ -270,4 +347,39 @@ mod test {
 			expected_nullifier[0], computed_nullifier
 		);
 	}
+
+	#[test]
+	fn test_optimized_permutation_is_identical() {
+		let rng = &mut test_rng();
+		let curve = Curve::Bn254;
+
+		for params in vec![
+			setup_params_x5_2::<Fq>(curve),
+			setup_params_x5_3(curve),
+			setup_params_x5_4(curve),
+			setup_params_x5_5(curve),
+			setup_params_x3_3(curve),
+			setup_params_x17_3(curve),
+		] {
+			let optimized = params.clone().optimized().unwrap();
+			let state: Vec<Fq> = (0..params.width).map(|_| Fq::rand(rng)).collect();
+			assert_eq!(
+				CRH::permute(&params, state.clone()).unwrap(),
+				CRH::permute(&optimized, state).unwrap()
+			);
+		}
+	}
+
+	#[test]
+	fn test_optimized_permutation_short_input() {
+		let params = setup_params_x5_5::<Fq>(Curve::Bn254);
+		let optimized = params.clone().optimized().unwrap();
+		let inputs = [Fq::from(1u64), Fq::from(2u64)];
+
+		// A state of 3 elements for a width of 5
+		assert_eq!(
+			<CRH<Fq> as FieldHasher<_>>::hash(&params, &inputs).unwrap(),
+			<CRH<Fq> as FieldHasher<_>>::hash(&optimized, &inputs).unwrap()
+		);
+	}
 }
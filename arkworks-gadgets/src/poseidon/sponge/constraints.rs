@@ -0,0 +1,237 @@
+use super::{PoseidonSponge, SPONGE_DOMAIN};
+use crate::poseidon::constraints::{CRHGadget, PoseidonParametersVar};
+use ark_ff::{FpParameters, PrimeField};
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_sponge::{
+	constraints::{AbsorbGadget, CryptographicSpongeVar},
+	DuplexSpongeMode,
+};
+use ark_std::vec::Vec;
+use arkworks_utils::poseidon::PoseidonParameters;
+
+/// Gadget for `PoseidonSponge`, squeezing the same elements for the same
+/// absorbed elements.
+#[derive(Clone)]
+pub struct PoseidonSpongeVar<F: PrimeField> {
+	/// Constraint system the sponge allocates in
+	pub cs: ConstraintSystemRef<F>,
+	/// Parameters of the permutation
+	pub parameters: PoseidonParametersVar<F>,
+	/// Current state of the permutation
+	pub state: Vec<FpVar<F>>,
+	/// Whether the sponge is absorbing or squeezing, with the next position
+	/// of the rate to use
+	pub mode: DuplexSpongeMode,
+}
+
+impl<F: PrimeField> PoseidonSpongeVar<F> {
+	/// create a sponge whose capacity element is `domain`
+	pub fn with_domain(
+		cs: ConstraintSystemRef<F>,
+		parameters: &PoseidonParameters<F>,
+		domain: F,
+	) -> Result<Self, SynthesisError> {
+		let parameters = PoseidonParametersVar::new_constant(cs.clone(), parameters)?;
+		let mut state = vec![FpVar::<F>::zero(); parameters.width as usize];
+		state[0] = FpVar::Constant(domain);
+
+		Ok(Self {
+			cs,
+			parameters,
+			state,
+			mode: DuplexSpongeMode::Absorbing {
+				next_absorb_index: 0,
+			},
+		})
+	}
+
+	fn rate(&self) -> usize {
+		self.parameters.width as usize - 1
+	}
+
+	fn permute(&mut self) -> Result<(), SynthesisError> {
+		let state = self.state.clone();
+		self.state = CRHGadget::<F>::permute(&self.parameters, state)?;
+		Ok(())
+	}
+
+	fn absorb_internal(
+		&mut self,
+		mut rate_start: usize,
+		elements: &[FpVar<F>],
+	) -> Result<(), SynthesisError> {
+		let rate = self.rate();
+		let mut remaining = elements;
+		loop {
+			if rate_start + remaining.len() <= rate {
+				for (i, element) in remaining.iter().enumerate() {
+					self.state[1 + rate_start + i] += element;
+				}
+				self.mode = DuplexSpongeMode::Absorbing {
+					next_absorb_index: rate_start + remaining.len(),
+				};
+				return Ok(());
+			}
+
+			let num_absorbed = rate - rate_start;
+			for (i, element) in remaining[..num_absorbed].iter().enumerate() {
+				self.state[1 + rate_start + i] += element;
+			}
+			self.permute()?;
+			remaining = &remaining[num_absorbed..];
+			rate_start = 0;
+		}
+	}
+
+	fn squeeze_internal(
+		&mut self,
+		mut rate_start: usize,
+		num_elements: usize,
+	) -> Result<Vec<FpVar<F>>, SynthesisError> {
+		let rate = self.rate();
+		let mut output = Vec::with_capacity(num_elements);
+		loop {
+			let remaining = num_elements - output.len();
+			if rate_start + remaining <= rate {
+				output.extend_from_slice(&self.state[1 + rate_start..1 + rate_start + remaining]);
+				self.mode = DuplexSpongeMode::Squeezing {
+					next_squeeze_index: rate_start + remaining,
+				};
+				return Ok(output);
+			}
+
+			output.extend_from_slice(&self.state[1 + rate_start..1 + rate]);
+			self.permute()?;
+			rate_start = 0;
+		}
+	}
+
+	fn pad(&mut self, next_absorb_index: usize) -> Result<(), SynthesisError> {
+		let mut pad_index = next_absorb_index;
+		if pad_index == self.rate() {
+			self.permute()?;
+			pad_index = 0;
+		}
+		self.state[1 + pad_index] += F::one();
+		self.permute()
+	}
+}
+
+impl<F: PrimeField> CryptographicSpongeVar<F, PoseidonSponge<F>> for PoseidonSpongeVar<F> {
+	type Parameters = PoseidonParameters<F>;
+
+	fn new(cs: ConstraintSystemRef<F>, parameters: &Self::Parameters) -> Self {
+		Self::with_domain(cs, parameters, F::from(SPONGE_DOMAIN))
+			.expect("parameters are allocated as constants")
+	}
+
+	fn cs(&self) -> ConstraintSystemRef<F> {
+		self.cs.clone()
+	}
+
+	fn absorb(&mut self, input: &impl AbsorbGadget<F>) -> Result<(), SynthesisError> {
+		let elements = input.to_sponge_field_elements()?;
+		if elements.is_empty() {
+			return Ok(());
+		}
+
+		match self.mode {
+			DuplexSpongeMode::Absorbing { next_absorb_index } => {
+				let mut absorb_index = next_absorb_index;
+				if absorb_index == self.rate() {
+					self.permute()?;
+					absorb_index = 0;
+				}
+				self.absorb_internal(absorb_index, &elements)
+			}
+			DuplexSpongeMode::Squeezing { .. } => {
+				self.permute()?;
+				self.absorb_internal(0, &elements)
+			}
+		}
+	}
+
+	fn squeeze_bytes(&mut self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+		let usable_bytes = (F::Params::CAPACITY / 8) as usize;
+		let num_elements = (num_bytes + usable_bytes - 1) / usable_bytes;
+
+		let mut bytes = Vec::with_capacity(usable_bytes * num_elements);
+		for element in self.squeeze_field_elements(num_elements)? {
+			bytes.extend_from_slice(&element.to_bytes()?[..usable_bytes]);
+		}
+		bytes.truncate(num_bytes);
+		Ok(bytes)
+	}
+
+	fn squeeze_bits(&mut self, num_bits: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+		let usable_bits = F::Params::CAPACITY as usize;
+		let num_elements = (num_bits + usable_bits - 1) / usable_bits;
+
+		let mut bits = Vec::with_capacity(usable_bits * num_elements);
+		for element in self.squeeze_field_elements(num_elements)? {
+			bits.extend_from_slice(&element.to_bits_le()?[..usable_bits]);
+		}
+		bits.truncate(num_bits);
+		Ok(bits)
+	}
+
+	fn squeeze_field_elements(
+		&mut self,
+		num_elements: usize,
+	) -> Result<Vec<FpVar<F>>, SynthesisError> {
+		match self.mode {
+			DuplexSpongeMode::Absorbing { next_absorb_index } => {
+				self.pad(next_absorb_index)?;
+				self.squeeze_internal(0, num_elements)
+			}
+			DuplexSpongeMode::Squeezing { next_squeeze_index } => {
+				let mut squeeze_index = next_squeeze_index;
+				if squeeze_index == self.rate() {
+					self.permute()?;
+					squeeze_index = 0;
+				}
+				self.squeeze_internal(squeeze_index, num_elements)
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_ed_on_bn254::Fq;
+	use ark_ff::UniformRand;
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_sponge::{CryptographicSponge, FieldBasedCryptographicSponge};
+	use ark_std::test_rng;
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	#[test]
+	fn should_match_native_sponge() {
+		let rng = &mut test_rng();
+		let cs = ConstraintSystem::<Fq>::new_ref();
+		let params = setup_params_x5_3::<Fq>(Curve::Bn254);
+
+		let first: Vec<Fq> = (0..5).map(|_| Fq::rand(rng)).collect();
+		let second: Vec<Fq> = (0..2).map(|_| Fq::rand(rng)).collect();
+		let first_var = Vec::<FpVar<Fq>>::new_witness(cs.clone(), || Ok(first.clone())).unwrap();
+		let second_var = Vec::<FpVar<Fq>>::new_witness(cs.clone(), || Ok(second.clone())).unwrap();
+
+		let mut sponge = PoseidonSponge::new(&params);
+		sponge.absorb(&first);
+		let output = sponge.squeeze_native_field_elements(3);
+		sponge.absorb(&second);
+		let bytes = sponge.squeeze_bytes(40);
+
+		let mut sponge_var = PoseidonSpongeVar::new(cs.clone(), &params);
+		sponge_var.absorb(&first_var).unwrap();
+		let output_var = sponge_var.squeeze_field_elements(3).unwrap();
+		sponge_var.absorb(&second_var).unwrap();
+		let bytes_var = sponge_var.squeeze_bytes(40).unwrap();
+
+		assert_eq!(output_var.value().unwrap(), output);
+		assert_eq!(bytes_var.value().unwrap(), bytes);
+		assert!(cs.is_satisfied().unwrap());
+	}
+}
@@ -0,0 +1,249 @@
+use super::CRH;
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use ark_sponge::{Absorb, CryptographicSponge, DuplexSpongeMode, FieldBasedCryptographicSponge};
+use ark_std::vec::Vec;
+use arkworks_utils::poseidon::PoseidonParameters;
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// Capacity element of a sponge created with `PoseidonSponge::new`, following
+/// the variable-length domain of the Poseidon paper. It keeps sponge outputs
+/// apart from `CRH` outputs, whose capacity element is 0.
+pub const SPONGE_DOMAIN: u128 = 1 << 64;
+
+/// Poseidon duplex sponge, absorbing and squeezing any number of field
+/// elements.
+///
+/// The first element of the state is the capacity and the others the rate.
+/// Switching from absorbing to squeezing pads the absorbed elements with a
+/// single 1, so inputs that only differ by trailing zeros never collide.
+#[derive(Clone)]
+pub struct PoseidonSponge<F: PrimeField> {
+	/// Parameters of the permutation
+	pub parameters: PoseidonParameters<F>,
+	/// Current state of the permutation
+	pub state: Vec<F>,
+	/// Whether the sponge is absorbing or squeezing, with the next position
+	/// of the rate to use
+	pub mode: DuplexSpongeMode,
+}
+
+impl<F: PrimeField> PoseidonSponge<F> {
+	/// create a sponge whose capacity element is `domain`
+	pub fn with_domain(parameters: &PoseidonParameters<F>, domain: F) -> Self {
+		let mut state = vec![F::zero(); parameters.width as usize];
+		state[0] = domain;
+
+		Self {
+			parameters: parameters.clone(),
+			state,
+			mode: DuplexSpongeMode::Absorbing {
+				next_absorb_index: 0,
+			},
+		}
+	}
+
+	fn rate(&self) -> usize {
+		self.parameters.width as usize - 1
+	}
+
+	fn permute(&mut self) {
+		let state = self.state.clone();
+		self.state = CRH::<F>::permute(&self.parameters, state)
+			.expect("the sbox of the parameters is supported");
+	}
+
+	/// Absorbs everything in `elements`, starting at `rate_start`.
+	fn absorb_internal(&mut self, mut rate_start: usize, elements: &[F]) {
+		let rate = self.rate();
+		let mut remaining = elements;
+		loop {
+			if rate_start + remaining.len() <= rate {
+				for (i, element) in remaining.iter().enumerate() {
+					self.state[1 + rate_start + i] += element;
+				}
+				self.mode = DuplexSpongeMode::Absorbing {
+					next_absorb_index: rate_start + remaining.len(),
+				};
+				return;
+			}
+
+			let num_absorbed = rate - rate_start;
+			for (i, element) in remaining[..num_absorbed].iter().enumerate() {
+				self.state[1 + rate_start + i] += element;
+			}
+			self.permute();
+			remaining = &remaining[num_absorbed..];
+			rate_start = 0;
+		}
+	}
+
+	/// Squeezes enough to fill `output`, starting at `rate_start`.
+	fn squeeze_internal(&mut self, mut rate_start: usize, output: &mut [F]) {
+		let rate = self.rate();
+		let mut remaining = output;
+		loop {
+			if rate_start + remaining.len() <= rate {
+				let len = remaining.len();
+				remaining.clone_from_slice(&self.state[1 + rate_start..1 + rate_start + len]);
+				self.mode = DuplexSpongeMode::Squeezing {
+					next_squeeze_index: rate_start + len,
+				};
+				return;
+			}
+
+			let num_squeezed = rate - rate_start;
+			remaining[..num_squeezed]
+				.clone_from_slice(&self.state[1 + rate_start..1 + rate_start + num_squeezed]);
+			self.permute();
+			remaining = &mut remaining[num_squeezed..];
+			rate_start = 0;
+		}
+	}
+
+	/// Pads the absorbed elements, which end before `next_absorb_index`, and
+	/// permutes the state so it is ready to be squeezed.
+	fn pad(&mut self, next_absorb_index: usize) {
+		let mut pad_index = next_absorb_index;
+		if pad_index == self.rate() {
+			self.permute();
+			pad_index = 0;
+		}
+		self.state[1 + pad_index] += F::one();
+		self.permute();
+	}
+}
+
+impl<F: PrimeField> CryptographicSponge for PoseidonSponge<F> {
+	type Parameters = PoseidonParameters<F>;
+
+	fn new(parameters: &Self::Parameters) -> Self {
+		Self::with_domain(parameters, F::from(SPONGE_DOMAIN))
+	}
+
+	fn absorb(&mut self, input: &impl Absorb) {
+		let elements = input.to_sponge_field_elements_as_vec::<F>();
+		if elements.is_empty() {
+			return;
+		}
+
+		match self.mode {
+			DuplexSpongeMode::Absorbing { next_absorb_index } => {
+				let mut absorb_index = next_absorb_index;
+				if absorb_index == self.rate() {
+					self.permute();
+					absorb_index = 0;
+				}
+				self.absorb_internal(absorb_index, &elements);
+			}
+			DuplexSpongeMode::Squeezing { .. } => {
+				self.permute();
+				self.absorb_internal(0, &elements);
+			}
+		}
+	}
+
+	fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
+		let usable_bytes = (F::Params::CAPACITY / 8) as usize;
+		let num_elements = (num_bytes + usable_bytes - 1) / usable_bytes;
+
+		let mut bytes = Vec::with_capacity(usable_bytes * num_elements);
+		for element in self.squeeze_native_field_elements(num_elements) {
+			bytes.extend_from_slice(&element.into_repr().to_bytes_le()[..usable_bytes]);
+		}
+		bytes.truncate(num_bytes);
+		bytes
+	}
+
+	fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool> {
+		let usable_bits = F::Params::CAPACITY as usize;
+		let num_elements = (num_bits + usable_bits - 1) / usable_bits;
+
+		let mut bits = Vec::with_capacity(usable_bits * num_elements);
+		for element in self.squeeze_native_field_elements(num_elements) {
+			bits.extend_from_slice(&element.into_repr().to_bits_le()[..usable_bits]);
+		}
+		bits.truncate(num_bits);
+		bits
+	}
+}
+
+impl<F: PrimeField> FieldBasedCryptographicSponge<F> for PoseidonSponge<F> {
+	fn squeeze_native_field_elements(&mut self, num_elements: usize) -> Vec<F> {
+		let mut output = vec![F::zero(); num_elements];
+		match self.mode {
+			DuplexSpongeMode::Absorbing { next_absorb_index } => {
+				self.pad(next_absorb_index);
+				self.squeeze_internal(0, &mut output);
+			}
+			DuplexSpongeMode::Squeezing { next_squeeze_index } => {
+				let mut squeeze_index = next_squeeze_index;
+				if squeeze_index == self.rate() {
+					self.permute();
+					squeeze_index = 0;
+				}
+				self.squeeze_internal(squeeze_index, &mut output);
+			}
+		}
+		output
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_ed_on_bn254::Fq;
+	use ark_ff::UniformRand;
+	use ark_std::test_rng;
+	use arkworks_utils::utils::common::{setup_params_x5_3, Curve};
+
+	#[test]
+	fn should_absorb_and_squeeze_any_length() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_3::<Fq>(Curve::Bn254);
+
+		// more elements than the width of the parameters
+		let input: Vec<Fq> = (0..7).map(|_| Fq::rand(rng)).collect();
+		let mut sponge = PoseidonSponge::new(&params);
+		sponge.absorb(&input);
+		let output = sponge.squeeze_native_field_elements(5);
+		assert_eq!(output.len(), 5);
+
+		// absorbing in several calls gives the same state
+		let mut split = PoseidonSponge::new(&params);
+		split.absorb(&input[..3].to_vec());
+		split.absorb(&input[3..].to_vec());
+		assert_eq!(split.squeeze_native_field_elements(2), output[..2].to_vec());
+		assert_eq!(split.squeeze_native_field_elements(3), output[2..].to_vec());
+
+		assert_eq!(sponge.squeeze_bytes(40).len(), 40);
+		assert_eq!(sponge.squeeze_bits(300).len(), 300);
+	}
+
+	#[test]
+	fn should_separate_padding_and_domains() {
+		let rng = &mut test_rng();
+		let params = setup_params_x5_3::<Fq>(Curve::Bn254);
+		let x = Fq::rand(rng);
+
+		let squeeze = |mut sponge: PoseidonSponge<Fq>, input: Vec<Fq>| {
+			sponge.absorb(&input);
+			sponge.squeeze_native_field_elements(1)[0]
+		};
+		let output = squeeze(PoseidonSponge::new(&params), vec![x]);
+
+		// a trailing zero is not swallowed by the padding
+		assert_ne!(
+			output,
+			squeeze(PoseidonSponge::new(&params), vec![x, Fq::from(0u64)])
+		);
+		assert_ne!(output, squeeze(PoseidonSponge::new(&params), vec![]));
+		assert_ne!(
+			output,
+			squeeze(PoseidonSponge::with_domain(&params, Fq::from(1u64)), vec![
+				x
+			])
+		);
+	}
+}
@@ -0,0 +1,297 @@
+use crate::{field_hasher::constraints::FieldHasherGadget, poseidon2::CRH};
+use ark_crypto_primitives::crh::constraints::{CRHGadget as CRHGadgetTrait, TwoToOneCRHGadget};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+	alloc::AllocVar,
+	fields::{fp::FpVar, FieldVar},
+	prelude::*,
+	uint8::UInt8,
+};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_std::{marker::PhantomData, vec::Vec};
+use arkworks_utils::{
+	poseidon::sbox::{constraints::SboxConstraints, PoseidonSbox},
+	poseidon2::Poseidon2Parameters,
+};
+use core::borrow::Borrow;
+
+#[derive(Default, Clone)]
+pub struct Poseidon2ParametersVar<F: PrimeField> {
+	/// The round key constants, `width` per full round and one per partial
+	/// round
+	pub round_keys: Vec<FpVar<F>>,
+	/// The diagonal of the internal matrix, minus one
+	pub mat_internal_diag_m_1: Vec<FpVar<F>>,
+	/// Number of full SBox rounds
+	pub full_rounds: u8,
+	/// Number of partial rounds
+	pub partial_rounds: u8,
+	/// The size of the permutation, in field elements.
+	pub width: u8,
+	/// The S-box to apply in the sub words layer.
+	pub sbox: PoseidonSbox,
+}
+
+impl<F: PrimeField> AllocVar<Poseidon2Parameters<F>, F> for Poseidon2ParametersVar<F> {
+	fn new_variable<T: Borrow<Poseidon2Parameters<F>>>(
+		_cs: impl Into<Namespace<F>>,
+		f: impl FnOnce() -> Result<T, SynthesisError>,
+		_mode: AllocationMode,
+	) -> Result<Self, SynthesisError> {
+		let params = f()?.borrow().clone();
+		params.check().map_err(|_| SynthesisError::Unsatisfiable)?;
+		let round_keys = params.round_keys.into_iter().map(FpVar::Constant).collect();
+		let mat_internal_diag_m_1 = params
+			.mat_internal_diag_m_1
+			.into_iter()
+			.map(FpVar::Constant)
+			.collect();
+
+		Ok(Self {
+			round_keys,
+			mat_internal_diag_m_1,
+			full_rounds: params.full_rounds,
+			partial_rounds: params.partial_rounds,
+			width: params.width,
+			sbox: params.sbox,
+		})
+	}
+}
+
+pub struct CRHGadget<F: PrimeField>(PhantomData<F>);
+
+impl<F: PrimeField> CRHGadget<F> {
+	/// Same as the native permutation. Only the S-boxes cost constraints, the
+	/// matrices having small or constant entries.
+	fn permute(
+		params: &Poseidon2ParametersVar<F>,
+		mut state: Vec<FpVar<F>>,
+	) -> Result<Vec<FpVar<F>>, SynthesisError> {
+		let width = params.width as usize;
+		if state.len() != width {
+			return Err(SynthesisError::Unsatisfiable);
+		}
+
+		let half_rounds = (params.full_rounds as usize) / 2;
+		let (first_keys, keys) = params.round_keys.split_at(half_rounds * width);
+		let (partial_keys, last_keys) = keys.split_at(params.partial_rounds as usize);
+		let full_round =
+			|state: &mut Vec<FpVar<F>>, keys: &[FpVar<F>]| -> Result<(), SynthesisError> {
+				state.iter_mut().zip(keys.iter()).for_each(|(a, c)| *a += c);
+				state
+					.iter_mut()
+					.try_for_each(|a| params.sbox.synthesize_sbox(a).map(|f| *a = f))?;
+				matmul_external(state)
+			};
+
+		matmul_external(&mut state)?;
+		for keys in first_keys.chunks(width) {
+			full_round(&mut state, keys)?;
+		}
+		for c in partial_keys {
+			state[0] = params.sbox.synthesize_sbox(&(&state[0] + c))?;
+			let sum = state.iter().fold(FpVar::<F>::zero(), |acc, a| acc + a);
+			for (a, d) in state.iter_mut().zip(params.mat_internal_diag_m_1.iter()) {
+				*a = &*a * d + &sum;
+			}
+		}
+		for keys in last_keys.chunks(width) {
+			full_round(&mut state, keys)?;
+		}
+		Ok(state)
+	}
+}
+
+/// Same as `Poseidon2Parameters::matmul_external`.
+fn matmul_external<F: PrimeField>(state: &mut [FpVar<F>]) -> Result<(), SynthesisError> {
+	match state.len() {
+		2 | 3 => {
+			let sum = state.iter().fold(FpVar::<F>::zero(), |acc, a| acc + a);
+			state.iter_mut().for_each(|a| *a += &sum);
+		}
+		_ => {
+			for chunk in state.chunks_exact_mut(4) {
+				matmul_m4(chunk)?;
+			}
+			let mut sums = vec![FpVar::<F>::zero(); 4];
+			for chunk in state.chunks_exact(4) {
+				for (sum, a) in sums.iter_mut().zip(chunk.iter()) {
+					*sum += a;
+				}
+			}
+			for chunk in state.chunks_exact_mut(4) {
+				for (a, sum) in chunk.iter_mut().zip(sums.iter()) {
+					*a += sum;
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+fn matmul_m4<F: PrimeField>(x: &mut [FpVar<F>]) -> Result<(), SynthesisError> {
+	let t0 = &x[0] + &x[1];
+	let t1 = &x[2] + &x[3];
+	let t2 = x[1].double()? + &t1;
+	let t3 = x[3].double()? + &t0;
+	let t4 = t1.double()?.double()? + &t3;
+	let t5 = t0.double()?.double()? + &t2;
+	x[0] = &t3 + &t5;
+	x[1] = t5;
+	x[2] = &t2 + &t4;
+	x[3] = t4;
+	Ok(())
+}
+
+impl<F: PrimeField> CRHGadgetTrait<CRH<F>, F> for CRHGadget<F> {
+	type OutputVar = FpVar<F>;
+	type ParametersVar = Poseidon2ParametersVar<F>;
+
+	fn evaluate(
+		parameters: &Self::ParametersVar,
+		input: &[UInt8<F>],
+	) -> Result<Self::OutputVar, SynthesisError> {
+		let f_var_inputs = arkworks_utils::utils::to_field_var_elements(input)?;
+		<Self as FieldHasherGadget<_, _>>::hash(parameters, &f_var_inputs)
+	}
+}
+
+impl<F: PrimeField> FieldHasherGadget<CRH<F>, F> for CRHGadget<F> {
+	fn hash(
+		parameters: &Poseidon2ParametersVar<F>,
+		inputs: &[FpVar<F>],
+	) -> Result<FpVar<F>, SynthesisError> {
+		if inputs.len() >= parameters.width.into() {
+			return Err(SynthesisError::Unsatisfiable);
+		}
+
+		let mut buffer = vec![FpVar::zero(); parameters.width as usize];
+		buffer[1..=inputs.len()].clone_from_slice(inputs);
+		let result = Self::permute(parameters, buffer)?;
+
+		Ok(result[0].clone())
+	}
+}
+
+impl<F: PrimeField> TwoToOneCRHGadget<CRH<F>, F> for CRHGadget<F> {
+	type OutputVar = FpVar<F>;
+	type ParametersVar = Poseidon2ParametersVar<F>;
+
+	fn evaluate(
+		parameters: &Self::ParametersVar,
+		left_input: &[UInt8<F>],
+		right_input: &[UInt8<F>],
+	) -> Result<Self::OutputVar, SynthesisError> {
+		// assume equality of left and right length
+		assert_eq!(left_input.len(), right_input.len());
+		let chained_input: Vec<_> = left_input
+			.iter()
+			.chain(right_input.iter())
+			.cloned()
+			.collect();
+		<Self as CRHGadgetTrait<_, _>>::evaluate(parameters, &chained_input)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::merkle_tree::{
+		constraints::{NodeVar, PathVar},
+		Config, SparseMerkleTree,
+	};
+	use ark_bn254::Fr;
+	use ark_crypto_primitives::crh::CRH as CRHTrait;
+	use ark_ff::{to_bytes, UniformRand};
+	use ark_r1cs_std::R1CSVar;
+	use ark_relations::r1cs::ConstraintSystem;
+	use ark_std::{rc::Rc, test_rng};
+	use arkworks_utils::utils::common::{
+		setup_poseidon2_params_x5_2, setup_poseidon2_params_x5_3, setup_poseidon2_params_x5_4,
+		Curve,
+	};
+
+	#[test]
+	fn poseidon2_native_equality() {
+		let rng = &mut test_rng();
+		for params in [
+			setup_poseidon2_params_x5_2::<Fr>(Curve::Bn254),
+			setup_poseidon2_params_x5_3::<Fr>(Curve::Bn254),
+			setup_poseidon2_params_x5_4::<Fr>(Curve::Bn254),
+		] {
+			let cs = ConstraintSystem::<Fr>::new_ref();
+			let params_var = Poseidon2ParametersVar::new_constant(cs.clone(), &params).unwrap();
+
+			let inputs: Vec<Fr> = (1..params.width).map(|_| Fr::rand(rng)).collect();
+			let bytes = to_bytes![inputs].unwrap();
+			let bytes_var =
+				Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(bytes.clone())).unwrap();
+
+			let res = CRH::<Fr>::evaluate(&params, &bytes).unwrap();
+			let res_var =
+				<CRHGadget<Fr> as CRHGadgetTrait<_, _>>::evaluate(&params_var, &bytes_var).unwrap();
+			assert_eq!(res, res_var.value().unwrap());
+			assert!(cs.is_satisfied().unwrap());
+		}
+	}
+
+	#[test]
+	fn should_cost_only_the_sboxes() {
+		let rng = &mut test_rng();
+		let params = setup_poseidon2_params_x5_4::<Fr>(Curve::Bn254);
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let params_var = Poseidon2ParametersVar::new_constant(cs.clone(), &params).unwrap();
+		let inputs: Vec<FpVar<Fr>> = (1..params.width)
+			.map(|_| FpVar::new_witness(cs.clone(), || Ok(Fr::rand(rng))).unwrap())
+			.collect();
+
+		<CRHGadget<Fr> as FieldHasherGadget<_, _>>::hash(&params_var, &inputs).unwrap();
+
+		// x^5 costs 3 constraints
+		let num_sboxes =
+			params.full_rounds as usize * params.width as usize + params.partial_rounds as usize;
+		assert_eq!(cs.num_constraints(), 3 * num_sboxes);
+	}
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct Poseidon2TreeConfig;
+	impl Config for Poseidon2TreeConfig {
+		type H = CRH<Fr>;
+		type LeafH = CRH<Fr>;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	#[test]
+	fn should_verify_merkle_path() {
+		let rng = &mut test_rng();
+		let cs = ConstraintSystem::<Fr>::new_ref();
+		let params = Rc::new(setup_poseidon2_params_x5_3::<Fr>(Curve::Bn254));
+		let leaves = vec![Fr::rand(rng), Fr::rand(rng), Fr::rand(rng)];
+		let smt = SparseMerkleTree::<Poseidon2TreeConfig>::new_sequential(
+			params.clone(),
+			params,
+			&leaves,
+		)
+		.unwrap();
+		let root = smt.root().unwrap();
+		let path = smt.generate_membership_proof(2).unwrap();
+
+		let path_var = PathVar::<
+			_,
+			_,
+			CRHGadget<Fr>,
+			CRHGadget<Fr>,
+			{ Poseidon2TreeConfig::HEIGHT as usize },
+		>::new_witness(cs.clone(), || Ok(path))
+		.unwrap();
+		let root_var =
+			NodeVar::<_, _, CRHGadget<Fr>, CRHGadget<Fr>>::new_witness(cs.clone(), || Ok(root))
+				.unwrap();
+		let leaf_var = FpVar::new_witness(cs.clone(), || Ok(leaves[2])).unwrap();
+
+		let res = path_var.check_membership(&root_var, &leaf_var).unwrap();
+		assert!(res.value().unwrap());
+		assert!(cs.is_satisfied().unwrap());
+	}
+}
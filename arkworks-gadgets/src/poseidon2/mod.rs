@@ -0,0 +1,265 @@
+use crate::field_hasher::FieldHasher;
+use ark_crypto_primitives::{crh::TwoToOneCRH, Error, CRH as CRHTrait};
+use ark_ff::fields::PrimeField;
+use ark_std::{marker::PhantomData, rand::Rng, vec::Vec};
+use arkworks_utils::{poseidon::PoseidonError, poseidon2::Poseidon2Parameters};
+
+#[cfg(feature = "r1cs")]
+pub mod constraints;
+
+/// Poseidon2 hash, laid out like the Poseidon `CRH`: the inputs follow a zero
+/// capacity element in the state and the first element of the permuted state
+/// is the output. Inputs shorter than `width - 1` are padded with zeros, so
+/// every call site should hash inputs of a fixed length.
+pub struct CRH<F: PrimeField>(PhantomData<F>);
+
+impl<F: PrimeField> CRH<F> {
+	fn permute(
+		params: &Poseidon2Parameters<F>,
+		mut state: Vec<F>,
+	) -> Result<Vec<F>, PoseidonError> {
+		params.check()?;
+		let width = params.width as usize;
+		if state.len() != width {
+			return Err(PoseidonError::InvalidInputs);
+		}
+
+		let half_rounds = (params.full_rounds as usize) / 2;
+		let (first_keys, keys) = params.round_keys.split_at(half_rounds * width);
+		let (partial_keys, last_keys) = keys.split_at(params.partial_rounds as usize);
+		let full_round = |state: &mut Vec<F>, keys: &[F]| -> Result<(), PoseidonError> {
+			state.iter_mut().zip(keys.iter()).for_each(|(a, c)| *a += c);
+			state
+				.iter_mut()
+				.try_for_each(|a| params.sbox.apply_sbox(*a).map(|f| *a = f))?;
+			Poseidon2Parameters::matmul_external(state);
+			Ok(())
+		};
+
+		Poseidon2Parameters::matmul_external(&mut state);
+		for keys in first_keys.chunks(width) {
+			full_round(&mut state, keys)?;
+		}
+		for c in partial_keys {
+			state[0] = params.sbox.apply_sbox(state[0] + c)?;
+			params.matmul_internal(&mut state);
+		}
+		for keys in last_keys.chunks(width) {
+			full_round(&mut state, keys)?;
+		}
+		Ok(state)
+	}
+}
+
+impl<F: PrimeField> CRHTrait for CRH<F> {
+	type Output = F;
+	type Parameters = Poseidon2Parameters<F>;
+
+	const INPUT_SIZE_BITS: usize = 0;
+
+	fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+		unreachable!("Poseidon2Parameters are already precomputed.");
+	}
+
+	fn evaluate(parameters: &Self::Parameters, input: &[u8]) -> Result<Self::Output, Error> {
+		let eval_time = start_timer!(|| "Poseidon2CRH::Eval");
+		let f_inputs = arkworks_utils::utils::to_field_elements(input)?;
+		let result = <Self as FieldHasher<F>>::hash(parameters, &f_inputs)?;
+		end_timer!(eval_time);
+
+		Ok(result)
+	}
+}
+
+impl<F: PrimeField> FieldHasher<F> for CRH<F> {
+	fn hash(parameters: &Poseidon2Parameters<F>, inputs: &[F]) -> Result<F, Error> {
+		if inputs.len() >= parameters.width.into() {
+			return Err(PoseidonError::InvalidInputs.into());
+		}
+
+		let mut buffer = vec![F::zero(); parameters.width as usize];
+		buffer[1..=inputs.len()].copy_from_slice(inputs);
+		let result = Self::permute(parameters, buffer)?;
+
+		Ok(result[0])
+	}
+}
+
+impl<F: PrimeField> TwoToOneCRH for CRH<F> {
+	type Output = F;
+	type Parameters = Poseidon2Parameters<F>;
+
+	const LEFT_INPUT_SIZE_BITS: usize = Self::INPUT_SIZE_BITS / 2;
+	const RIGHT_INPUT_SIZE_BITS: usize = Self::INPUT_SIZE_BITS / 2;
+
+	fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
+		<Self as CRHTrait>::setup(rng)
+	}
+
+	/// Hash the left and right inputs chained together, which must have the
+	/// same length.
+	fn evaluate(
+		parameters: &Self::Parameters,
+		left_input: &[u8],
+		right_input: &[u8],
+	) -> Result<Self::Output, Error> {
+		assert_eq!(left_input.len(), right_input.len());
+		let chained: Vec<_> = left_input
+			.iter()
+			.chain(right_input.iter())
+			.copied()
+			.collect();
+
+		<Self as CRHTrait>::evaluate(parameters, &chained)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::merkle_tree::{Config, SparseMerkleTree};
+	use ark_ff::to_bytes;
+	use ark_std::{rc::Rc, test_rng, UniformRand};
+	use arkworks_utils::utils::{
+		common::{
+			setup_poseidon2_params_x5_2, setup_poseidon2_params_x5_3, setup_poseidon2_params_x5_4,
+			Curve,
+		},
+		parse_vec,
+	};
+
+	type BnFr = ark_bn254::Fr;
+	type BlsFr = ark_bls12_381::Fr;
+
+	fn permute_range<F: PrimeField>(params: &Poseidon2Parameters<F>) -> Vec<F> {
+		let state = (0..params.width as u64).map(F::from).collect();
+		CRH::permute(params, state).unwrap()
+	}
+
+	#[test]
+	fn should_match_reference_permutations() {
+		// Permutations of `[0, 1, .., width - 1]` from the reference
+		// implementation (x5_3) and from barretenberg (x5_4)
+		let res = permute_range(&setup_poseidon2_params_x5_3::<BnFr>(Curve::Bn254));
+		assert_eq!(
+			res,
+			parse_vec(vec![
+				"0x0bb61d24daca55eebcb1929a82650f328134334da98ea4f847f760054f4a3033",
+				"0x303b6f7c86d043bfcbcc80214f26a30277a15d3f74ca654992defe7ff8d03570",
+				"0x1ed25194542b12eef8617361c3ba7c52e660b145994427cc86296242cf766ec8",
+			])
+		);
+
+		let res = permute_range(&setup_poseidon2_params_x5_4::<BnFr>(Curve::Bn254));
+		assert_eq!(
+			res,
+			parse_vec(vec![
+				"0x01bd538c2ee014ed5141b29e9ae240bf8db3fe5b9a38629a9647cf8d76c01737",
+				"0x239b62e7db98aa3a2a8f6a0d2fa1709e7a35959aa6c7034814d9daa90cbac662",
+				"0x04cbb44c61d928ed06808456bf758cbf0c18d1e15a7b6dbc8245fa7515d5e3cb",
+				"0x2e11c5cff2a22c64d01304b778d78f6998eff1ab73163a35603f54794c30847a",
+			])
+		);
+	}
+
+	#[test]
+	fn should_match_permutation_vectors() {
+		// Permutations of `[0, 1, .., width - 1]` with the same constants,
+		// self-generated with a model of the reference implementation, as no
+		// published vectors exist for these sets
+		let res = permute_range(&setup_poseidon2_params_x5_2::<BnFr>(Curve::Bn254));
+		assert_eq!(
+			res,
+			parse_vec(vec![
+				"0x1d01e56f49579cec72319e145f06f6177f6c5253206e78c2689781452a31878b",
+				"0x0d189ec589c41b8cffa88cfc523618a055abe8192c70f75aa72fc514560f6c61",
+			])
+		);
+
+		let res = permute_range(&setup_poseidon2_params_x5_2::<BlsFr>(Curve::Bls381));
+		assert_eq!(
+			res,
+			parse_vec(vec![
+				"0x73c46dd530e248a87b61d19e67fa1b4ed30fc3d09f16531fe189fb945a15ce4e",
+				"0x1f0e305ee21c9366d5793b80251405032a3fee32b9dd0b5f4578262891b043b4",
+			])
+		);
+
+		let res = permute_range(&setup_poseidon2_params_x5_3::<BlsFr>(Curve::Bls381));
+		assert_eq!(
+			res,
+			parse_vec(vec![
+				"0x1b152349b1950b6a8ca75ee4407b6e26ca5cca5650534e56ef3fd45761fbf5f0",
+				"0x4c5793c87d51bdc2c08a32108437dc0000bd0275868f09ebc5f36919af5b3891",
+				"0x1fc8ed171e67902ca49863159fe5ba6325318843d13976143b8125f08b50dc6b",
+			])
+		);
+
+		let res = permute_range(&setup_poseidon2_params_x5_4::<BlsFr>(Curve::Bls381));
+		assert_eq!(
+			res,
+			parse_vec(vec![
+				"0x28ff6c4edf9768c08ae26290487e93449cc8bc155fc2fad92a344adceb3ada6d",
+				"0x0e56f2b6fad25075aa93560185b70e2b180ed7e269159c507c288b6747a0db2d",
+				"0x6d8196f28da6006bb89b3df94600acdc03d0ba7c2b0f3f4409a54c1db6bf30d0",
+				"0x07cfb49540ee456cce38b8a7d1a930a57ffc6660737f6589ef184c5e15334e36",
+			])
+		);
+	}
+
+	#[test]
+	fn should_hash_bytes_and_fields_alike() {
+		let rng = &mut test_rng();
+		let params = setup_poseidon2_params_x5_3::<BnFr>(Curve::Bn254);
+		let left = BnFr::rand(rng);
+		let right = BnFr::rand(rng);
+
+		let res = <CRH<BnFr> as FieldHasher<_>>::hash(&params, &[left, right]).unwrap();
+		let bytes = to_bytes![left, right].unwrap();
+		assert_eq!(
+			res,
+			<CRH<BnFr> as CRHTrait>::evaluate(&params, &bytes).unwrap()
+		);
+		assert_eq!(
+			res,
+			<CRH<BnFr> as TwoToOneCRH>::evaluate(
+				&params,
+				&to_bytes![left].unwrap(),
+				&to_bytes![right].unwrap()
+			)
+			.unwrap()
+		);
+
+		// The capacity element leaves room for width - 1 inputs only
+		assert!(<CRH<BnFr> as FieldHasher<_>>::hash(&params, &[left, right, left]).is_err());
+	}
+
+	#[derive(Clone, Debug, Eq, PartialEq)]
+	struct Poseidon2TreeConfig;
+	impl Config for Poseidon2TreeConfig {
+		type H = CRH<BnFr>;
+		type LeafH = CRH<BnFr>;
+
+		const HEIGHT: u8 = 3;
+	}
+
+	#[test]
+	fn should_build_merkle_tree() {
+		let rng = &mut test_rng();
+		let params = Rc::new(setup_poseidon2_params_x5_3::<BnFr>(Curve::Bn254));
+		let leaves = vec![BnFr::rand(rng), BnFr::rand(rng), BnFr::rand(rng)];
+		let smt = SparseMerkleTree::<Poseidon2TreeConfig>::new_sequential(
+			params.clone(),
+			params,
+			&leaves,
+		)
+		.unwrap();
+		let root = smt.root().unwrap();
+
+		let path = smt
+			.generate_membership_proof::<{ Poseidon2TreeConfig::HEIGHT as usize }>(1)
+			.unwrap();
+		assert!(path.check_membership(&root, &leaves[1]).unwrap());
+		assert!(path.check_membership_fields(&root, &leaves[1]).unwrap());
+	}
+}
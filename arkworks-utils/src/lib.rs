@@ -8,6 +8,7 @@ pub(crate) use ark_std::vec::Vec;
 
 pub mod mimc;
 pub mod poseidon;
+pub mod poseidon2;
 pub mod utils;
 
 pub use mimc::*;
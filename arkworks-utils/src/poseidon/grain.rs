@@ -0,0 +1,120 @@
+use ark_ff::{fields::PrimeField, BigInteger};
+use ark_std::vec::Vec;
+
+/// The Grain LFSR of the Poseidon reference script
+/// (`generate_parameters_grain.sage`), used to derive round constants and MDS
+/// matrices from the parameters of the permutation alone.
+#[derive(Clone, Debug)]
+pub struct PoseidonGrainLFSR {
+	state: [bool; 80],
+	head: usize,
+}
+
+impl PoseidonGrainLFSR {
+	/// Seed the LFSR with the parameters of the permutation, `field_size`
+	/// being the bit size of the prime as passed to the script.
+	pub fn new(
+		is_sbox_an_inverse: bool,
+		field_size: u16,
+		width: u8,
+		full_rounds: u8,
+		partial_rounds: u8,
+	) -> Self {
+		let mut state = [false; 80];
+
+		// b0, b1 describe the field: 1 for a prime field
+		set_bits(&mut state[0..2], 1);
+		// b2..=b5 describe the s-box: 0 for x^alpha, 1 for x^-1
+		set_bits(&mut state[2..6], is_sbox_an_inverse as u64);
+		set_bits(&mut state[6..18], field_size as u64);
+		set_bits(&mut state[18..30], width as u64);
+		set_bits(&mut state[30..40], full_rounds as u64);
+		set_bits(&mut state[40..50], partial_rounds as u64);
+		// b50..=b79 are set to 1
+		for bit in state[50..80].iter_mut() {
+			*bit = true;
+		}
+
+		let mut lfsr = PoseidonGrainLFSR { state, head: 0 };
+		// The first 160 bits are discarded
+		for _ in 0..160 {
+			lfsr.update();
+		}
+		lfsr
+	}
+
+	/// Return the next `num_bits` bits of the self-shrinking output, the most
+	/// significant first.
+	pub fn get_bits(&mut self, num_bits: usize) -> Vec<bool> {
+		let mut bits = Vec::with_capacity(num_bits);
+		for _ in 0..num_bits {
+			// A pair of bits is kept only when its first bit is set
+			let mut first = self.update();
+			while !first {
+				self.update();
+				first = self.update();
+			}
+			bits.push(self.update());
+		}
+		bits
+	}
+
+	/// Sample `num_elems` field elements of `field_size` bits each, drawing
+	/// again whenever the sample is not smaller than the modulus.
+	pub fn get_field_elements_rejection_sampling<F: PrimeField>(
+		&mut self,
+		field_size: u16,
+		num_elems: usize,
+	) -> Vec<F> {
+		let mut elems = Vec::with_capacity(num_elems);
+		while elems.len() < num_elems {
+			let bits = self.get_bits(field_size as usize);
+			if let Some(elem) = F::from_repr(F::BigInt::from_bits_be(&bits)) {
+				elems.push(elem);
+			}
+		}
+		elems
+	}
+
+	/// Sample `num_elems` field elements of `field_size` bits each, reducing
+	/// the samples modulo the prime.
+	pub fn get_field_elements_mod_p<F: PrimeField>(
+		&mut self,
+		field_size: u16,
+		num_elems: usize,
+	) -> Vec<F> {
+		// Reduced bit by bit, `from_be_bytes_mod_order` expecting the modulus
+		// to reach the last limb of its representation
+		(0..num_elems)
+			.map(|_| {
+				self.get_bits(field_size as usize)
+					.iter()
+					.fold(F::zero(), |acc, bit| {
+						let acc = acc.double();
+						if *bit {
+							acc + F::one()
+						} else {
+							acc
+						}
+					})
+			})
+			.collect()
+	}
+
+	#[inline]
+	fn update(&mut self) -> bool {
+		let bit = |offset: usize| self.state[(self.head + offset) % 80];
+		let new_bit = bit(62) ^ bit(51) ^ bit(38) ^ bit(23) ^ bit(13) ^ bit(0);
+		self.state[self.head] = new_bit;
+		self.head = (self.head + 1) % 80;
+		new_bit
+	}
+}
+
+/// Write `value` into `bits`, the most significant bit first.
+fn set_bits(bits: &mut [bool], value: u64) {
+	let len = bits.len();
+	for (i, bit) in bits.iter_mut().enumerate() {
+		*bit = (value >> (len - 1 - i)) & 1 == 1;
+	}
+}
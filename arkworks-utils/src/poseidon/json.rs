@@ -0,0 +1,294 @@
+use super::{sbox::PoseidonSbox, PoseidonError, PoseidonParameters};
+use ark_ff::{fields::PrimeField, BigInteger};
+use ark_std::{
+	string::{String, ToString},
+	vec::Vec,
+};
+use serde::{de::IgnoredAny, Deserialize};
+
+/// Full rounds of every circomlib parameter set.
+pub const CIRCOM_FULL_ROUNDS: u8 = 8;
+
+/// Partial rounds of the circomlib parameter sets, `N_ROUNDS_P` of
+/// circomlib, for the widths 2 to 17.
+pub const CIRCOM_PARTIAL_ROUNDS: [u8; 16] = [
+	56, 57, 56, 60, 60, 63, 64, 63, 60, 66, 60, 65, 70, 60, 64, 68,
+];
+
+/// The unoptimized `poseidon_constants.json` of circomlib(js): the round
+/// constants `C` and the MDS matrices `M` of every width, starting at
+/// width 2. The optimized layout, which also holds the sparse matrices `S`
+/// and the pre-sparse matrices `P` and compresses `C`, is rejected.
+#[derive(Deserialize)]
+struct CircomConstants {
+	#[serde(rename = "C")]
+	round_constants: Vec<Vec<String>>,
+	#[serde(rename = "M")]
+	mds_matrices: Vec<Vec<Vec<String>>>,
+	#[serde(rename = "S")]
+	sparse_matrices: Option<IgnoredAny>,
+	#[serde(rename = "P")]
+	pre_sparse_matrices: Option<IgnoredAny>,
+}
+
+/// Parameters as output by the reference script
+/// (`generate_parameters_grain.sage`), with the round constants flattened in
+/// round order.
+#[derive(Deserialize)]
+struct ReferenceParameters {
+	alpha: i8,
+	t: u8,
+	#[serde(rename = "R_F")]
+	full_rounds: u8,
+	#[serde(rename = "R_P")]
+	partial_rounds: u8,
+	round_constants: Vec<String>,
+	mds_matrix: Vec<Vec<String>>,
+}
+
+impl<F: PrimeField> PoseidonParameters<F> {
+	/// Load the parameters of `width` from circomlib's unoptimized
+	/// `poseidon_constants.json`. Circomlib always uses `x^5`, 8 full rounds
+	/// and the partial rounds of `CIRCOM_PARTIAL_ROUNDS`.
+	pub fn from_circom_json(json: &str, width: u8) -> Result<Self, PoseidonError> {
+		let constants: CircomConstants =
+			serde_json::from_str(json).map_err(|_| PoseidonError::InvalidEncoding)?;
+		if constants.sparse_matrices.is_some() || constants.pre_sparse_matrices.is_some() {
+			return Err(PoseidonError::InvalidEncoding);
+		}
+		let index = (width as usize)
+			.checked_sub(2)
+			.ok_or(PoseidonError::InvalidInputs)?;
+		let partial_rounds = *CIRCOM_PARTIAL_ROUNDS
+			.get(index)
+			.ok_or(PoseidonError::InvalidInputs)?;
+		let round_constants = constants
+			.round_constants
+			.get(index)
+			.ok_or(PoseidonError::InvalidInputs)?;
+		let mds_matrix = constants
+			.mds_matrices
+			.get(index)
+			.ok_or(PoseidonError::InvalidInputs)?;
+
+		Self::from_strings(
+			round_constants,
+			mds_matrix,
+			CIRCOM_FULL_ROUNDS,
+			partial_rounds,
+			width,
+			PoseidonSbox(5),
+		)
+	}
+
+	/// Load parameters from a JSON object of the reference script's outputs:
+	///
+	/// ```json
+	/// {
+	///   "alpha": 5, "t": 3, "R_F": 8, "R_P": 57,
+	///   "round_constants": ["0x..", ..],
+	///   "mds_matrix": [["0x..", ..], ..]
+	/// }
+	/// ```
+	pub fn from_reference_json(json: &str) -> Result<Self, PoseidonError> {
+		let params: ReferenceParameters =
+			serde_json::from_str(json).map_err(|_| PoseidonError::InvalidEncoding)?;
+		PoseidonSbox(params.alpha).check()?;
+
+		Self::from_strings(
+			&params.round_constants,
+			&params.mds_matrix,
+			params.full_rounds,
+			params.partial_rounds,
+			params.t,
+			PoseidonSbox(params.alpha),
+		)
+	}
+
+	fn from_strings(
+		round_keys: &[String],
+		mds_matrix: &[Vec<String>],
+		full_rounds: u8,
+		partial_rounds: u8,
+		width: u8,
+		sbox: PoseidonSbox,
+	) -> Result<Self, PoseidonError> {
+		let round_keys = round_keys
+			.iter()
+			.map(|key| parse_field(key))
+			.collect::<Result<Vec<F>, PoseidonError>>()?;
+		let mds_matrix = mds_matrix
+			.iter()
+			.map(|row| row.iter().map(|m| parse_field(m)).collect())
+			.collect::<Result<Vec<Vec<F>>, PoseidonError>>()?;
+
+		let params = Self::new(
+			round_keys,
+			mds_matrix,
+			full_rounds,
+			partial_rounds,
+			width,
+			sbox,
+		);
+		if !params.has_valid_dimensions() {
+			return Err(PoseidonError::InvalidEncoding);
+		}
+		Ok(params)
+	}
+}
+
+/// Parses a decimal or `0x`-prefixed hex string into a field element,
+/// rejecting values that are not reduced.
+fn parse_field<F: PrimeField>(element: &str) -> Result<F, PoseidonError> {
+	let hex = match element.strip_prefix("0x") {
+		Some(hex) => hex,
+		None => return F::from_str(element).map_err(|_| PoseidonError::InvalidEncoding),
+	};
+	if hex.is_empty() || hex.len() > F::BigInt::NUM_LIMBS * 16 {
+		return Err(PoseidonError::InvalidEncoding);
+	}
+
+	let padded = if hex.len() % 2 == 1 {
+		format!("0{}", hex)
+	} else {
+		hex.to_string()
+	};
+	let mut bytes = (0..padded.len())
+		.step_by(2)
+		.map(|i| u8::from_str_radix(&padded[i..i + 2], 16))
+		.collect::<Result<Vec<u8>, _>>()
+		.map_err(|_| PoseidonError::InvalidEncoding)?;
+	bytes.reverse();
+	bytes.resize(F::BigInt::NUM_LIMBS * 8, 0);
+
+	let mut repr = F::BigInt::default();
+	for (limb, chunk) in repr.as_mut().iter_mut().zip(bytes.chunks(8)) {
+		let mut limb_bytes = [0u8; 8];
+		limb_bytes.copy_from_slice(chunk);
+		*limb = u64::from_le_bytes(limb_bytes);
+	}
+	F::from_repr(repr).ok_or(PoseidonError::InvalidEncoding)
+}
+
+#[cfg(all(test, feature = "poseidon_bn254_x5_3"))]
+mod test {
+	use super::*;
+	use crate::utils::bn254_x5_3::get_poseidon_bn254_x5_3;
+	use ark_ed_on_bn254::Fq;
+
+	fn to_hex(element: &Fq) -> String {
+		let hex: String = element
+			.into_repr()
+			.to_bytes_be()
+			.iter()
+			.map(|b| format!("{:02x}", b))
+			.collect();
+		format!("\"0x{}\"", hex)
+	}
+
+	fn to_json_list(elements: &[Fq]) -> String {
+		let items: Vec<String> = elements.iter().map(to_hex).collect();
+		format!("[{}]", items.join(","))
+	}
+
+	fn to_json_matrix(matrix: &[Vec<Fq>]) -> String {
+		let rows: Vec<String> = matrix.iter().map(|row| to_json_list(row)).collect();
+		format!("[{}]", rows.join(","))
+	}
+
+	fn assert_same(params: &PoseidonParameters<Fq>, loaded: &PoseidonParameters<Fq>) {
+		assert_eq!(loaded.round_keys, params.round_keys);
+		assert_eq!(loaded.mds_matrix, params.mds_matrix);
+		assert_eq!(loaded.full_rounds, params.full_rounds);
+		assert_eq!(loaded.partial_rounds, params.partial_rounds);
+		assert_eq!(loaded.width, params.width);
+		assert_eq!(loaded.sbox, params.sbox);
+	}
+
+	#[test]
+	fn should_load_circom_json() {
+		let params = get_poseidon_bn254_x5_3::<Fq>();
+		// circomlib starts at width 2, whose constants are left empty here
+		let json = format!(
+			"{{\"C\": [[], {}], \"M\": [[], {}]}}",
+			to_json_list(&params.round_keys),
+			to_json_matrix(&params.mds_matrix)
+		);
+
+		assert_same(
+			&params,
+			&PoseidonParameters::from_circom_json(&json, 3).unwrap(),
+		);
+		assert!(matches!(
+			PoseidonParameters::<Fq>::from_circom_json(&json, 4),
+			Err(PoseidonError::InvalidInputs)
+		));
+		assert!(matches!(
+			PoseidonParameters::<Fq>::from_circom_json(&json, 2),
+			Err(PoseidonError::InvalidEncoding)
+		));
+	}
+
+	#[test]
+	fn should_reject_optimized_circom_json() {
+		let params = get_poseidon_bn254_x5_3::<Fq>();
+		let optimized = params.clone().optimized().unwrap();
+		let optimized_json = format!(
+			"{{\"C\": [[], {}], \"M\": [[], {}], \"S\": [[], []], \"P\": [[], {}]}}",
+			to_json_list(&params.round_keys),
+			to_json_matrix(&params.mds_matrix),
+			to_json_matrix(&optimized.optimized.unwrap().pre_sparse_matrix)
+		);
+		assert!(matches!(
+			PoseidonParameters::<Fq>::from_circom_json(&optimized_json, 3),
+			Err(PoseidonError::InvalidEncoding)
+		));
+
+		// Compressed constants, fewer than `(8 + 57) * 3`
+		let compressed = &params.round_keys[..params.round_keys.len() - 1];
+		let json = format!(
+			"{{\"C\": [[], {}], \"M\": [[], {}]}}",
+			to_json_list(compressed),
+			to_json_matrix(&params.mds_matrix)
+		);
+		assert!(matches!(
+			PoseidonParameters::<Fq>::from_circom_json(&json, 3),
+			Err(PoseidonError::InvalidEncoding)
+		));
+	}
+
+	#[test]
+	fn should_load_reference_json() {
+		let params = get_poseidon_bn254_x5_3::<Fq>();
+		let json = format!(
+			"{{\"alpha\": 5, \"t\": 3, \"R_F\": 8, \"R_P\": 57, \"round_constants\": {}, \
+			 \"mds_matrix\": {}}}",
+			to_json_list(&params.round_keys),
+			to_json_matrix(&params.mds_matrix)
+		);
+		assert_same(
+			&params,
+			&PoseidonParameters::from_reference_json(&json).unwrap(),
+		);
+
+		// One round short of constants
+		let json = json.replace("\"R_P\": 57", "\"R_P\": 58");
+		assert!(matches!(
+			PoseidonParameters::<Fq>::from_reference_json(&json),
+			Err(PoseidonError::InvalidEncoding)
+		));
+		assert!(matches!(
+			PoseidonParameters::<Fq>::from_reference_json("{}"),
+			Err(PoseidonError::InvalidEncoding)
+		));
+	}
+
+	#[test]
+	fn should_parse_decimal_and_hex() {
+		assert_eq!(parse_field::<Fq>("255").unwrap(), Fq::from(255u64));
+		assert_eq!(parse_field::<Fq>("0xff").unwrap(), Fq::from(255u64));
+		assert_eq!(parse_field::<Fq>("0x0ff").unwrap(), Fq::from(255u64));
+		assert!(parse_field::<Fq>("0x").is_err());
+		assert!(parse_field::<Fq>("0xzz").is_err());
+	}
+}
@@ -1,20 +1,33 @@
 use crate::{
 	ark_std::string::ToString,
-	poseidon::sbox::PoseidonSbox,
+	poseidon::{
+		grain::PoseidonGrainLFSR, optimized::OptimizedPoseidonParameters, sbox::PoseidonSbox,
+	},
 	utils::{from_field_elements, to_field_elements},
 };
 use ark_crypto_primitives::Error;
 use ark_ff::{fields::PrimeField, BigInteger};
 use ark_serialize::Read;
-use ark_std::{error::Error as ArkError, rand::Rng, vec::Vec};
+use ark_std::{error::Error as ArkError, vec::Vec};
 
+pub mod grain;
+#[cfg(feature = "std")]
+pub mod json;
+pub mod optimized;
 pub mod sbox;
+#[cfg(feature = "std")]
+pub mod security;
+pub mod serialization;
 
 #[derive(Debug)]
 pub enum PoseidonError {
 	InvalidSboxSize(i8),
 	ApplySboxFailed,
 	InvalidInputs,
+	InvalidFieldSize(u16),
+	UnreachableSecurityLevel(u32),
+	InvalidEncoding,
+	InvalidWidth(u8),
 }
 
 impl core::fmt::Display for PoseidonError {
@@ -24,6 +37,10 @@ impl core::fmt::Display for PoseidonError {
 			InvalidSboxSize(s) => format!("sbox is not supported: {}", s),
 			ApplySboxFailed => "failed to apply sbox".to_string(),
 			InvalidInputs => "invalid inputs".to_string(),
+			InvalidFieldSize(n) => format!("field size is not supported: {}", n),
+			UnreachableSecurityLevel(n) => format!("no round numbers reach {} bits of security", n),
+			InvalidEncoding => "invalid encoding of the parameters".to_string(),
+			InvalidWidth(n) => format!("width is not supported: {}", n),
 		};
 		write!(f, "{}", msg)
 	}
@@ -46,6 +63,9 @@ pub struct PoseidonParameters<F: PrimeField> {
 	pub width: u8,
 	/// The S-box to apply in the sub words layer.
 	pub sbox: PoseidonSbox,
+	/// Constants of the optimized permutation, used instead of the ones above
+	/// when set and the state holds `width` elements
+	pub optimized: Option<OptimizedPoseidonParameters<F>>,
 }
 
 impl<F: PrimeField> PoseidonParameters<F> {
@@ -64,21 +84,113 @@ impl<F: PrimeField> PoseidonParameters<F> {
 			full_rounds,
 			partial_rounds,
 			sbox,
+			optimized: None,
 		}
 	}
 
-	pub fn generate<R: Rng>(_rng: &mut R) -> Self {
-		unimplemented!();
+	/// Generate the parameters of the reference script for the field, with
+	/// the round constants and the MDS matrix drawn from the Grain LFSR.
+	pub fn generate(
+		sbox: PoseidonSbox,
+		width: u8,
+		full_rounds: u8,
+		partial_rounds: u8,
+	) -> Result<Self, PoseidonError> {
+		Self::generate_with_field_size(
+			F::size_in_bits() as u16,
+			sbox,
+			width,
+			full_rounds,
+			partial_rounds,
+		)
+	}
+
+	/// Same as `generate`, but seeds the LFSR with `field_size` instead of the
+	/// bit size of the modulus. Some tables were generated that way, e.g. the
+	/// ed_on_bn254 ones with a field size of 254.
+	pub fn generate_with_field_size(
+		field_size: u16,
+		sbox: PoseidonSbox,
+		width: u8,
+		full_rounds: u8,
+		partial_rounds: u8,
+	) -> Result<Self, PoseidonError> {
+		sbox.check()?;
+		let is_sbox_an_inverse = sbox.0 == -1;
+		let max_field_size = F::BigInt::NUM_LIMBS * 64;
+		if (field_size as usize) < F::size_in_bits() || (field_size as usize) > max_field_size {
+			return Err(PoseidonError::InvalidFieldSize(field_size));
+		}
+		if width == 0 {
+			return Err(PoseidonError::InvalidInputs);
+		}
+
+		let mut lfsr = PoseidonGrainLFSR::new(
+			is_sbox_an_inverse,
+			field_size,
+			width,
+			full_rounds,
+			partial_rounds,
+		);
+		let round_keys =
+			Self::create_round_keys(&mut lfsr, field_size, width, full_rounds, partial_rounds);
+		let mds_matrix = Self::create_mds(&mut lfsr, field_size, width);
+
+		Ok(Self::new(
+			round_keys,
+			mds_matrix,
+			full_rounds,
+			partial_rounds,
+			width,
+			sbox,
+		))
 	}
 
-	pub fn create_mds<R: Rng>(_rng: &mut R) -> Vec<Vec<F>> {
-		unimplemented!();
+	/// Draw the Cauchy matrix `1 / (x_i + y_j)` from the LFSR, as the script
+	/// does once the round constants are drawn.
+	///
+	/// Candidates whose `x_i, y_j` are not distinct or whose sums vanish are
+	/// drawn again, and so are the ones failing the secure MDS checks of the
+	/// script, see `is_secure_mds`.
+	pub fn create_mds(lfsr: &mut PoseidonGrainLFSR, field_size: u16, width: u8) -> Vec<Vec<F>> {
+		let width = width as usize;
+		loop {
+			let mut elems: Vec<F> = lfsr.get_field_elements_mod_p(field_size, 2 * width);
+			while has_duplicates(&elems) {
+				elems = lfsr.get_field_elements_mod_p(field_size, 2 * width);
+			}
+			let (xs, ys) = elems.split_at(width);
+
+			let mds: Option<Vec<Vec<F>>> = xs
+				.iter()
+				.map(|x| {
+					ys.iter()
+						.map(|y| (*x + y).inverse())
+						.collect::<Option<Vec<F>>>()
+				})
+				.collect();
+			match mds {
+				Some(mds) if is_secure_mds(&mds) => return mds,
+				_ => continue,
+			}
+		}
 	}
 
-	pub fn create_round_keys<R: Rng>(_rng: &mut R) -> Vec<F> {
-		unimplemented!();
+	/// Draw the `(full_rounds + partial_rounds) * width` round constants from
+	/// the LFSR.
+	pub fn create_round_keys(
+		lfsr: &mut PoseidonGrainLFSR,
+		field_size: u16,
+		width: u8,
+		full_rounds: u8,
+		partial_rounds: u8,
+	) -> Vec<F> {
+		let num_keys = (full_rounds as usize + partial_rounds as usize) * width as usize;
+		lfsr.get_field_elements_rejection_sampling(field_size, num_keys)
 	}
 
+	/// Legacy unversioned encoding, kept for the parameters already stored
+	/// with it. New encodings should use `CanonicalSerialize`.
 	pub fn to_bytes(&self) -> Vec<u8> {
 		let max_elt_size = F::BigInt::NUM_LIMBS * 8;
 		let mut buf: Vec<u8> = vec![];
@@ -125,6 +237,7 @@ impl<F: PrimeField> PoseidonParameters<F> {
 		let mut exponentiation_u8 = [0u8; 1];
 		bytes.read_exact(&mut exponentiation_u8)?;
 		let exp = i8::from_be_bytes(exponentiation_u8);
+		PoseidonSbox(exp).check()?;
 
 		let mut round_key_len = [0u8; 4];
 		bytes.read_exact(&mut round_key_len)?;
@@ -154,14 +267,166 @@ impl<F: PrimeField> PoseidonParameters<F> {
 			full_rounds,
 			partial_rounds,
 			sbox: PoseidonSbox(exp),
+			optimized: None,
+		})
+	}
+}
+
+fn has_duplicates<F: PrimeField>(elems: &[F]) -> bool {
+	elems
+		.iter()
+		.enumerate()
+		.any(|(i, elem)| elems[i + 1..].contains(elem))
+}
+
+/// The script's checks against infinitely long subspace trails, for a single
+/// S-box in the partial rounds, `S` being the subspace of the states whose
+/// first element is zero:
+/// 1. for `i` in `1..width`, `M^i` is not a multiple of the identity, has no
+///    eigenvector in `S` whose eigenvalue is in the field, and does not map
+///    `S` onto itself;
+/// 2. and 3. for `r` in `1..=4 * width`, `e_0` spans the whole space under
+///    `M^r`.
+fn is_secure_mds<F: PrimeField>(mds: &[Vec<F>]) -> bool {
+	let width = mds.len();
+	// The eigenvectors of `A = M^i` with an eigenvalue in the field span the
+	// kernel of `A^p - A`
+	let frobenius = pow(mds, F::characteristic());
+	let mut frobenius_power = frobenius.clone();
+	let mut power = mds.to_vec();
+	for i in 1..=4 * width {
+		if !spans_whole_space(&power) {
+			return false;
+		}
+		if i < width {
+			let is_scalar = power.iter().enumerate().all(|(j, row)| {
+				row.iter()
+					.enumerate()
+					.all(|(k, elem)| *elem == if j == k { power[0][0] } else { F::zero() })
+			});
+			let keeps_subspace = power[0][1..].iter().all(|elem| elem.is_zero());
+			if is_scalar || keeps_subspace {
+				return false;
+			}
+
+			// An eigenvector in `S` lies in the kernel of `A^p - A` and of
+			// every `e_0^T A^k`
+			let mut rows: Vec<Vec<F>> = frobenius_power
+				.iter()
+				.zip(power.iter())
+				.map(|(f_row, row)| f_row.iter().zip(row.iter()).map(|(f, a)| *f - a).collect())
+				.collect();
+			rows.extend(krylov(&transpose(&power)));
+			if rank(rows) < width {
+				return false;
+			}
+			frobenius_power = mul(&frobenius_power, &frobenius);
+		}
+		power = mul(&power, mds);
+	}
+	true
+}
+
+/// Returns `e_0, M e_0, .., M^(width - 1) e_0`.
+fn krylov<F: PrimeField>(matrix: &[Vec<F>]) -> Vec<Vec<F>> {
+	let width = matrix.len();
+	let mut vectors = Vec::with_capacity(width);
+	let mut vector: Vec<F> = (0..width)
+		.map(|i| if i == 0 { F::one() } else { F::zero() })
+		.collect();
+	for _ in 0..width {
+		let next = matrix
+			.iter()
+			.map(|row| {
+				row.iter()
+					.zip(vector.iter())
+					.fold(F::zero(), |acc, (m, v)| acc + *m * v)
+			})
+			.collect();
+		vectors.push(vector);
+		vector = next;
+	}
+	vectors
+}
+
+/// Whether `e_0, M e_0, M^2 e_0, ...` span the whole space.
+fn spans_whole_space<F: PrimeField>(matrix: &[Vec<F>]) -> bool {
+	rank(krylov(matrix)) == matrix.len()
+}
+
+fn transpose<F: PrimeField>(matrix: &[Vec<F>]) -> Vec<Vec<F>> {
+	(0..matrix.len())
+		.map(|j| matrix.iter().map(|row| row[j]).collect())
+		.collect()
+}
+
+fn mul<F: PrimeField>(a: &[Vec<F>], b: &[Vec<F>]) -> Vec<Vec<F>> {
+	a.iter()
+		.map(|row| {
+			(0..b.len())
+				.map(|j| {
+					row.iter()
+						.zip(b.iter())
+						.fold(F::zero(), |acc, (r, b_row)| acc + *r * b_row[j])
+				})
+				.collect()
+		})
+		.collect()
+}
+
+/// Raises `matrix` to the power of the little-endian limbs `exp`.
+fn pow<F: PrimeField>(matrix: &[Vec<F>], exp: &[u64]) -> Vec<Vec<F>> {
+	let width = matrix.len();
+	let mut res: Vec<Vec<F>> = (0..width)
+		.map(|i| {
+			(0..width)
+				.map(|j| if i == j { F::one() } else { F::zero() })
+				.collect()
 		})
+		.collect();
+	for limb in exp.iter().rev() {
+		for bit in (0..64).rev() {
+			res = mul(&res, &res);
+			if (*limb >> bit) & 1 == 1 {
+				res = mul(&res, matrix);
+			}
+		}
 	}
+	res
+}
+
+/// Returns the rank of `matrix`, by Gaussian elimination.
+pub(crate) fn rank<F: PrimeField>(mut matrix: Vec<Vec<F>>) -> usize {
+	let num_cols = matrix.first().map_or(0, |row| row.len());
+	let mut rank = 0;
+	for col in 0..num_cols {
+		let pivot = match (rank..matrix.len()).find(|i| !matrix[*i][col].is_zero()) {
+			Some(pivot) => pivot,
+			None => continue,
+		};
+		matrix.swap(rank, pivot);
+
+		let inv = matrix[rank][col].inverse().unwrap();
+		let pivot_row = matrix[rank].clone();
+		for (i, row) in matrix.iter_mut().enumerate() {
+			if i == rank || row[col].is_zero() {
+				continue;
+			}
+			let factor = row[col] * inv;
+			for (elem, pivot_elem) in row.iter_mut().zip(pivot_row.iter()) {
+				*elem -= factor * pivot_elem;
+			}
+		}
+		rank += 1;
+	}
+	rank
 }
 
 #[cfg(all(test, feature = "poseidon_bn254_x5_5", feature = "poseidon_bn254_x5_3",))]
 mod test {
 	use super::*;
 	use ark_ed_on_bn254::Fq;
+	use ark_ff::Zero;
 
 	use crate::utils::bn254_x5_3::get_poseidon_bn254_x5_3;
 
@@ -173,4 +438,221 @@ mod test {
 		let new_params: PoseidonParameters<Fq> = PoseidonParameters::from_bytes(&bytes).unwrap();
 		assert_eq!(bytes, new_params.to_bytes());
 	}
+
+	fn assert_generated<F: PrimeField>(params: PoseidonParameters<F>) {
+		let generated = PoseidonParameters::<F>::generate(
+			params.sbox,
+			params.width,
+			params.full_rounds,
+			params.partial_rounds,
+		)
+		.unwrap();
+		assert_eq!(generated.round_keys, params.round_keys);
+		assert_eq!(generated.mds_matrix, params.mds_matrix);
+	}
+
+	/// The field of 257 elements, small enough for the script to draw MDS
+	/// matrices failing its checks
+	mod f257 {
+		use ark_ff::{
+			biginteger::BigInteger256 as BigInteger,
+			fields::{FftParameters, Fp256, Fp256Parameters, FpParameters},
+		};
+
+		pub type F257 = Fp256<F257Parameters>;
+
+		pub struct F257Parameters;
+
+		impl Fp256Parameters for F257Parameters {}
+		impl FftParameters for F257Parameters {
+			type BigInt = BigInteger;
+
+			const TWO_ADICITY: u32 = 8;
+			// R = 2^256 = 1 mod 257, so the Montgomery form is the integer itself
+			const TWO_ADIC_ROOT_OF_UNITY: BigInteger = BigInteger([3, 0, 0, 0]);
+		}
+		impl FpParameters for F257Parameters {
+			const CAPACITY: u32 = Self::MODULUS_BITS - 1;
+			const GENERATOR: BigInteger = BigInteger([3, 0, 0, 0]);
+			const INV: u64 = 0x00ff_00ff_00ff_00ff;
+			const MODULUS: BigInteger = BigInteger([257, 0, 0, 0]);
+			const MODULUS_BITS: u32 = 9;
+			const MODULUS_MINUS_ONE_DIV_TWO: BigInteger = BigInteger([128, 0, 0, 0]);
+			const R: BigInteger = BigInteger([1, 0, 0, 0]);
+			const R2: BigInteger = BigInteger([1, 0, 0, 0]);
+			const REPR_SHAVE_BITS: u32 = 247;
+			const T: BigInteger = BigInteger([1, 0, 0, 0]);
+			const T_MINUS_ONE_DIV_TWO: BigInteger = BigInteger([0, 0, 0, 0]);
+		}
+	}
+
+	#[test]
+	fn test_secure_mds_checks() {
+		let params = get_poseidon_bn254_x5_3::<Fq>();
+		assert!(is_secure_mds(&params.mds_matrix));
+
+		// a diagonal matrix keeps `e_0` in its own line
+		let diagonal = vec![
+			vec![Fq::from(2u64), Fq::zero(), Fq::zero()],
+			vec![Fq::zero(), Fq::from(3u64), Fq::zero()],
+			vec![Fq::zero(), Fq::zero(), Fq::from(5u64)],
+		];
+		assert!(!spans_whole_space(&diagonal));
+		assert!(!is_secure_mds(&diagonal));
+	}
+
+	#[test]
+	fn test_generate_redraws_insecure_mds() {
+		use f257::F257;
+		let to_matrix = |rows: [[u64; 3]; 3]| -> Vec<Vec<F257>> {
+			rows.iter()
+				.map(|row| row.iter().map(|elem| F257::from(*elem)).collect())
+				.collect()
+		};
+
+		// The first candidate and the matrix the script keeps, over the field
+		// of 257 elements with `x^5`, a width of 3 and 8 full rounds, computed
+		// with a port of the script's checks. With 42 partial rounds, `e_0`
+		// does not span the space under `M^4`. With 193, `(0, 169, 1)` is an
+		// eigenvector of `M` for the eigenvalue 110.
+		for (partial_rounds, first, expected) in [
+			(42, [[85, 192, 94], [46, 203, 88], [199, 66, 15]], [
+				[55, 238, 9],
+				[181, 218, 101],
+				[202, 243, 62],
+			]),
+			(193, [[218, 159, 114], [165, 65, 152], [123, 213, 93]], [
+				[141, 240, 210],
+				[169, 87, 187],
+				[139, 20, 50],
+			]),
+		] {
+			let mut lfsr = PoseidonGrainLFSR::new(false, 9, 3, 8, partial_rounds);
+			PoseidonParameters::<F257>::create_round_keys(&mut lfsr, 9, 3, 8, partial_rounds);
+			let elems: Vec<F257> = lfsr.get_field_elements_mod_p(9, 6);
+			let candidate: Vec<Vec<F257>> = elems[..3]
+				.iter()
+				.map(|x| {
+					elems[3..]
+						.iter()
+						.map(|y| (*x + y).inverse().unwrap())
+						.collect()
+				})
+				.collect();
+			assert_eq!(candidate, to_matrix(first));
+			assert!(!is_secure_mds(&candidate));
+
+			let generated =
+				PoseidonParameters::<F257>::generate(PoseidonSbox(5), 3, 8, partial_rounds)
+					.unwrap();
+			assert_eq!(generated.mds_matrix, to_matrix(expected));
+		}
+	}
+
+	#[test]
+	fn test_generate_bn254_x5() {
+		use crate::utils::bn254_x5_5::get_poseidon_bn254_x5_5;
+
+		assert_generated(get_poseidon_bn254_x5_3::<Fq>());
+		assert_generated(get_poseidon_bn254_x5_5::<Fq>());
+	}
+
+	#[cfg(all(feature = "poseidon_bn254_x5_2", feature = "poseidon_bn254_x5_4"))]
+	#[test]
+	fn test_generate_bn254_x5_2_and_x5_4() {
+		use crate::utils::{
+			bn254_x5_2::get_poseidon_bn254_x5_2, bn254_x5_4::get_poseidon_bn254_x5_4,
+		};
+
+		assert_generated(get_poseidon_bn254_x5_2::<Fq>());
+		assert_generated(get_poseidon_bn254_x5_4::<Fq>());
+	}
+
+	#[cfg(all(feature = "poseidon_bn254_x17_3", feature = "poseidon_bn254_x3_3"))]
+	#[test]
+	fn test_generate_bn254_x3_and_x17() {
+		use crate::utils::{
+			bn254_x17_3::get_poseidon_bn254_x17_3, bn254_x3_3::get_poseidon_bn254_x3_3,
+		};
+
+		assert_generated(get_poseidon_bn254_x3_3::<Fq>());
+		assert_generated(get_poseidon_bn254_x17_3::<Fq>());
+	}
+
+	#[cfg(all(feature = "poseidon_bls381_x5_3", feature = "poseidon_bls381_x17_5"))]
+	#[test]
+	fn test_generate_bls381() {
+		use crate::utils::{
+			bls381_x17_5::get_poseidon_bls381_x17_5, bls381_x5_3::get_poseidon_bls381_x5_3,
+		};
+		use ark_bls12_381::Fr;
+
+		assert_generated(get_poseidon_bls381_x5_3::<Fr>());
+		assert_generated(get_poseidon_bls381_x17_5::<Fr>());
+	}
+
+	#[cfg(all(feature = "poseidon_bls381_x5_2", feature = "poseidon_bls381_x5_4"))]
+	#[test]
+	fn test_generate_bls381_x5_2_and_x5_4() {
+		use crate::{
+			poseidon::security::{required_rounds, DEFAULT_SECURITY_LEVEL},
+			utils::{bls381_x5_2::get_poseidon_bls381_x5_2, bls381_x5_4::get_poseidon_bls381_x5_4},
+		};
+		use ark_bls12_381::Fr;
+
+		// Generated with the rounds the reference script picks for the field
+		for params in [
+			get_poseidon_bls381_x5_2::<Fr>(),
+			get_poseidon_bls381_x5_4::<Fr>(),
+		] {
+			assert_eq!(
+				required_rounds::<Fr>(params.sbox, params.width, DEFAULT_SECURITY_LEVEL).unwrap(),
+				(params.full_rounds, params.partial_rounds)
+			);
+			assert_generated(params);
+		}
+	}
+
+	#[cfg(all(feature = "poseidon_bls381_x17_2", feature = "poseidon_bls381_x17_4"))]
+	#[test]
+	fn test_generate_bls381_x17_widths_2_and_4() {
+		use crate::{
+			poseidon::security::{required_rounds, DEFAULT_SECURITY_LEVEL},
+			utils::{
+				bls381_x17_2::get_poseidon_bls381_x17_2, bls381_x17_4::get_poseidon_bls381_x17_4,
+			},
+		};
+		use ark_bls12_381::Fr;
+
+		for params in [
+			get_poseidon_bls381_x17_2::<Fr>(),
+			get_poseidon_bls381_x17_4::<Fr>(),
+		] {
+			assert_eq!(
+				required_rounds::<Fr>(params.sbox, params.width, DEFAULT_SECURITY_LEVEL).unwrap(),
+				(params.full_rounds, params.partial_rounds)
+			);
+			assert_generated(params);
+		}
+	}
+
+	#[test]
+	fn test_generate_with_field_size() {
+		// The reference script is seeded with the size of the field, which some
+		// tables set above the bit size of the modulus
+		let generated =
+			PoseidonParameters::<Fq>::generate_with_field_size(255, PoseidonSbox(5), 3, 8, 57)
+				.unwrap();
+		let params = get_poseidon_bn254_x5_3::<Fq>();
+		assert_ne!(generated.round_keys, params.round_keys);
+
+		assert!(matches!(
+			PoseidonParameters::<Fq>::generate_with_field_size(253, PoseidonSbox(5), 3, 8, 57),
+			Err(PoseidonError::InvalidFieldSize(253))
+		));
+		assert!(matches!(
+			PoseidonParameters::<Fq>::generate(PoseidonSbox(7), 3, 8, 57),
+			Err(PoseidonError::InvalidSboxSize(7))
+		));
+	}
 }
@@ -0,0 +1,260 @@
+use super::{PoseidonError, PoseidonParameters};
+use ark_ff::fields::PrimeField;
+use ark_std::vec::Vec;
+
+/// A matrix whose rows other than the first are those of the identity matrix,
+/// except for their first element.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SparseMatrix<F: PrimeField> {
+	/// The first row, `width` elements
+	pub row: Vec<F>,
+	/// The first column without its first element, `width - 1` elements
+	pub column: Vec<F>,
+}
+
+impl<F: PrimeField> SparseMatrix<F> {
+	/// Multiply `state` by the matrix, with `3 * width - 2` multiplications
+	/// instead of `width^2`.
+	pub fn apply(&self, state: &[F]) -> Vec<F> {
+		let first = self
+			.row
+			.iter()
+			.zip(state.iter())
+			.fold(F::zero(), |acc, (m, a)| acc + *m * a);
+
+		let mut new_state = Vec::with_capacity(state.len());
+		new_state.push(first);
+		for (m, a) in self.column.iter().zip(state[1..].iter()) {
+			new_state.push(*m * state[0] + a);
+		}
+		new_state
+	}
+}
+
+/// Constants of the optimized permutation of the Poseidon paper (appendix B),
+/// equivalent to those of the `PoseidonParameters` they were derived from.
+///
+/// The round constants of a partial round only reach its S-box through their
+/// first element, so the others are moved forward to the next round and only
+/// the first is kept. The MDS matrix of every partial round is then factored
+/// into a sparse matrix, applied in that round, and a matrix leaving the first
+/// element alone, moved back to the previous round. The last matrix moved back
+/// ends up in the last full round before the partial rounds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OptimizedPoseidonParameters<F: PrimeField> {
+	/// The round constants of the full rounds, `width` per round
+	pub full_round_keys: Vec<Vec<F>>,
+	/// The round constant of every partial round, added to the first element
+	pub partial_round_keys: Vec<F>,
+	/// The matrix applied in the last full round before the partial rounds
+	pub pre_sparse_matrix: Vec<Vec<F>>,
+	/// The matrix applied in every partial round
+	pub sparse_matrices: Vec<SparseMatrix<F>>,
+}
+
+impl<F: PrimeField> OptimizedPoseidonParameters<F> {
+	/// Derive the constants of the optimized permutation from `params`.
+	///
+	/// Fails if there are no full rounds to hand the moved constants and
+	/// matrices to, or if the MDS matrix without its first row and column is
+	/// not invertible, which cannot happen for Cauchy matrices.
+	pub fn new(params: &PoseidonParameters<F>) -> Result<Self, PoseidonError> {
+		let width = params.width as usize;
+		let full_rounds = params.full_rounds as usize;
+		let partial_rounds = params.partial_rounds as usize;
+		let half_rounds = full_rounds / 2;
+		if width == 0
+			|| half_rounds == 0
+			|| params.mds_matrix.len() != width
+			|| params.round_keys.len() < (full_rounds + partial_rounds) * width
+		{
+			return Err(PoseidonError::InvalidInputs);
+		}
+
+		let mds = &params.mds_matrix;
+		let round_keys: Vec<&[F]> = params.round_keys.chunks(width).collect();
+
+		// Move the constants of every partial round but the first element
+		// through the MDS matrix, into the constants of the next round.
+		let mut partial_round_keys = Vec::with_capacity(partial_rounds);
+		let mut carry = vec![F::zero(); width];
+		for keys in &round_keys[half_rounds..half_rounds + partial_rounds] {
+			let mut keys: Vec<F> = keys.iter().zip(carry.iter()).map(|(k, c)| *k + c).collect();
+			partial_round_keys.push(keys[0]);
+			keys[0] = F::zero();
+			carry = mul_vector(mds, &keys);
+		}
+
+		let mut full_round_keys: Vec<Vec<F>> = round_keys[..half_rounds]
+			.iter()
+			.chain(round_keys[half_rounds + partial_rounds..full_rounds + partial_rounds].iter())
+			.map(|keys| keys.to_vec())
+			.collect();
+		for (key, c) in full_round_keys[half_rounds].iter_mut().zip(carry.iter()) {
+			*key += c;
+		}
+
+		// Factor the matrix of every partial round, from the last one, into a
+		// sparse matrix and a matrix moved back to the previous round.
+		let mut matrix = mds.clone();
+		let mut sparse_matrices = Vec::with_capacity(partial_rounds);
+		for _ in 0..partial_rounds {
+			let (sparse, moved) = factor(&matrix)?;
+			sparse_matrices.push(sparse);
+			matrix = mul_block(&moved, mds);
+		}
+		sparse_matrices.reverse();
+
+		Ok(Self {
+			full_round_keys,
+			partial_round_keys,
+			pre_sparse_matrix: matrix,
+			sparse_matrices,
+		})
+	}
+}
+
+impl<F: PrimeField> PoseidonParameters<F> {
+	/// Switch the permutation of the parameters to the optimized one, which
+	/// gives the same outputs with fewer operations.
+	///
+	/// The constants are derived once, so they must be derived again if the
+	/// round keys or the MDS matrix change.
+	pub fn optimized(mut self) -> Result<Self, PoseidonError> {
+		self.optimized = Some(OptimizedPoseidonParameters::new(&self)?);
+		Ok(self)
+	}
+}
+
+/// Returns `matrix * vector`.
+fn mul_vector<F: PrimeField>(matrix: &[Vec<F>], vector: &[F]) -> Vec<F> {
+	matrix
+		.iter()
+		.map(|row| {
+			row.iter()
+				.zip(vector.iter())
+				.fold(F::zero(), |acc, (m, a)| acc + *m * a)
+		})
+		.collect()
+}
+
+/// Returns `diag(1, block) * matrix`.
+fn mul_block<F: PrimeField>(block: &[Vec<F>], matrix: &[Vec<F>]) -> Vec<Vec<F>> {
+	let mut product = vec![matrix[0].clone()];
+	for block_row in block {
+		let row = (0..matrix.len())
+			.map(|j| {
+				block_row
+					.iter()
+					.zip(matrix[1..].iter())
+					.fold(F::zero(), |acc, (b, m_row)| acc + *b * m_row[j])
+			})
+			.collect();
+		product.push(row);
+	}
+	product
+}
+
+/// Factor `matrix` into `sparse * diag(1, block)`, returning the sparse
+/// matrix and `block`, the lower right block of `matrix`.
+fn factor<F: PrimeField>(
+	matrix: &[Vec<F>],
+) -> Result<(SparseMatrix<F>, Vec<Vec<F>>), PoseidonError> {
+	let block: Vec<Vec<F>> = matrix[1..].iter().map(|row| row[1..].to_vec()).collect();
+	let block_inv = invert(block.clone()).ok_or(PoseidonError::InvalidInputs)?;
+
+	let mut row = vec![matrix[0][0]];
+	for j in 0..block.len() {
+		row.push(
+			matrix[0][1..]
+				.iter()
+				.zip(block_inv.iter())
+				.fold(F::zero(), |acc, (m, inv_row)| acc + *m * inv_row[j]),
+		);
+	}
+	let column = matrix[1..].iter().map(|row| row[0]).collect();
+
+	Ok((SparseMatrix { row, column }, block))
+}
+
+/// Invert a square matrix with Gauss-Jordan elimination.
+fn invert<F: PrimeField>(mut matrix: Vec<Vec<F>>) -> Option<Vec<Vec<F>>> {
+	let n = matrix.len();
+	let mut inverse: Vec<Vec<F>> = (0..n)
+		.map(|i| {
+			(0..n)
+				.map(|j| if i == j { F::one() } else { F::zero() })
+				.collect()
+		})
+		.collect();
+
+	for col in 0..n {
+		let pivot = (col..n).find(|i| !matrix[*i][col].is_zero())?;
+		matrix.swap(col, pivot);
+		inverse.swap(col, pivot);
+
+		let inv = matrix[col][col].inverse()?;
+		matrix[col].iter_mut().for_each(|elem| *elem *= inv);
+		inverse[col].iter_mut().for_each(|elem| *elem *= inv);
+
+		let (pivot_row, pivot_inverse_row) = (matrix[col].clone(), inverse[col].clone());
+		for i in (0..n).filter(|i| *i != col) {
+			let factor = matrix[i][col];
+			if factor.is_zero() {
+				continue;
+			}
+			for (elem, pivot_elem) in matrix[i].iter_mut().zip(pivot_row.iter()) {
+				*elem -= factor * pivot_elem;
+			}
+			for (elem, pivot_elem) in inverse[i].iter_mut().zip(pivot_inverse_row.iter()) {
+				*elem -= factor * pivot_elem;
+			}
+		}
+	}
+	Some(inverse)
+}
+
+#[cfg(all(test, feature = "poseidon_bn254_x5_3"))]
+mod test {
+	use super::*;
+	use crate::utils::bn254_x5_3::get_poseidon_bn254_x5_3;
+	use ark_ed_on_bn254::Fq;
+	use ark_ff::{One, UniformRand, Zero};
+	use ark_std::test_rng;
+
+	#[test]
+	fn should_factor_into_sparse_matrices() {
+		let rng = &mut test_rng();
+		let params = get_poseidon_bn254_x5_3::<Fq>();
+		let optimized = OptimizedPoseidonParameters::new(&params).unwrap();
+		assert_eq!(optimized.full_round_keys.len(), params.full_rounds as usize);
+		assert_eq!(
+			optimized.partial_round_keys.len(),
+			params.partial_rounds as usize
+		);
+
+		// The last sparse matrix times the block moved back is the MDS matrix
+		let (sparse, block) = factor(&params.mds_matrix).unwrap();
+		assert_eq!(&sparse, optimized.sparse_matrices.last().unwrap());
+		let state: Vec<Fq> = (0..params.width).map(|_| Fq::rand(rng)).collect();
+		let identity: Vec<Vec<Fq>> = (0..params.width)
+			.map(|i| {
+				(0..params.width)
+					.map(|j| if i == j { Fq::one() } else { Fq::zero() })
+					.collect()
+			})
+			.collect();
+		let moved = mul_vector(&mul_block(&block, &identity), &state);
+		assert_eq!(sparse.apply(&moved), mul_vector(&params.mds_matrix, &state));
+	}
+
+	#[test]
+	fn should_reject_parameters_without_full_rounds() {
+		let mut params = get_poseidon_bn254_x5_3::<Fq>();
+		params.full_rounds = 1;
+		assert!(matches!(
+			OptimizedPoseidonParameters::new(&params),
+			Err(PoseidonError::InvalidInputs)
+		));
+	}
+}
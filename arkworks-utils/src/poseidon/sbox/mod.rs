@@ -15,6 +15,15 @@ impl Default for PoseidonSbox {
 }
 
 impl PoseidonSbox {
+	/// Fails with `PoseidonError::InvalidSboxSize` unless the exponent is 3,
+	/// 5, 17 or -1.
+	pub fn check(&self) -> Result<(), PoseidonError> {
+		match self.0 {
+			3 | 5 | 17 | -1 => Ok(()),
+			n => Err(PoseidonError::InvalidSboxSize(n)),
+		}
+	}
+
 	pub fn apply_sbox<F: PrimeField>(&self, elem: F) -> Result<F, PoseidonError> {
 		let res = match self.0 {
 			3 => elem * elem * elem,
@@ -0,0 +1,438 @@
+use super::{rank, sbox::PoseidonSbox, PoseidonError, PoseidonParameters};
+use ark_ff::{fields::PrimeField, FpParameters};
+use ark_std::{fmt, vec::Vec};
+
+/// Security level of the bundled parameter sets, in bits.
+pub const DEFAULT_SECURITY_LEVEL: u32 = 128;
+
+/// Outcome of `PoseidonParameters::validate`, listing every check so a caller
+/// can tell why a parameter set is rejected.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SecurityReport {
+	/// targeted security level, in bits
+	pub security_level: u32,
+	/// rounds picked by the reference script for the field, sbox and width,
+	/// with its security margin, or `None` when the level is unreachable
+	pub required_rounds: Option<(u8, u8)>,
+	pub full_rounds: u8,
+	pub partial_rounds: u8,
+	/// whether the sbox is a permutation of the field
+	pub sbox_is_permutation: bool,
+	/// whether there are `(full_rounds + partial_rounds) * width` round keys
+	/// and a `width x width` MDS matrix
+	pub dimensions_ok: bool,
+	/// rows and columns of every singular square submatrix of the MDS matrix
+	pub singular_submatrices: Vec<(Vec<usize>, Vec<usize>)>,
+	/// dimension of the largest subspace that keeps the partial-round sbox
+	/// inactive forever, giving an infinitely long subspace trail
+	pub invariant_subspace_dim: usize,
+}
+
+impl SecurityReport {
+	pub fn rounds_ok(&self) -> bool {
+		match self.required_rounds {
+			Some((full_rounds, partial_rounds)) => {
+				self.full_rounds >= full_rounds && self.partial_rounds >= partial_rounds
+			}
+			None => false,
+		}
+	}
+
+	pub fn is_secure(&self) -> bool {
+		self.rounds_ok()
+			&& self.sbox_is_permutation
+			&& self.dimensions_ok
+			&& self.singular_submatrices.is_empty()
+			&& self.invariant_subspace_dim == 0
+	}
+}
+
+impl fmt::Display for SecurityReport {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "security level: {} bits", self.security_level)?;
+		match self.required_rounds {
+			Some((full_rounds, partial_rounds)) => {
+				writeln!(
+					f,
+					"full rounds: {} (required {})",
+					self.full_rounds, full_rounds
+				)?;
+				writeln!(
+					f,
+					"partial rounds: {} (required {})",
+					self.partial_rounds, partial_rounds
+				)?;
+			}
+			None => writeln!(f, "rounds: security level is not reachable")?,
+		}
+		writeln!(f, "sbox is a permutation: {}", self.sbox_is_permutation)?;
+		writeln!(f, "dimensions ok: {}", self.dimensions_ok)?;
+		writeln!(
+			f,
+			"singular mds submatrices: {}",
+			self.singular_submatrices.len()
+		)?;
+		write!(
+			f,
+			"invariant subspace dimension: {}",
+			self.invariant_subspace_dim
+		)
+	}
+}
+
+impl<F: PrimeField> PoseidonParameters<F> {
+	/// check the parameters against the attacks of the Poseidon paper for a
+	/// security level of `security_level` bits
+	///
+	/// The MDS matrix is checked on every square submatrix, which takes time
+	/// exponential in the width.
+	pub fn validate(&self, security_level: u32) -> SecurityReport {
+		let width = self.width as usize;
+		let num_rounds = self.full_rounds as usize + self.partial_rounds as usize;
+		let dimensions_ok = self.round_keys.len() == num_rounds * width
+			&& self.mds_matrix.len() == width
+			&& self.mds_matrix.iter().all(|row| row.len() == width);
+
+		let (singular_submatrices, invariant_subspace_dim) = if dimensions_ok {
+			(
+				singular_submatrices(&self.mds_matrix),
+				invariant_subspace_dim(&self.mds_matrix),
+			)
+		} else {
+			(Vec::new(), 0)
+		};
+
+		SecurityReport {
+			security_level,
+			required_rounds: required_rounds::<F>(self.sbox, self.width, security_level).ok(),
+			full_rounds: self.full_rounds,
+			partial_rounds: self.partial_rounds,
+			sbox_is_permutation: is_permutation::<F>(self.sbox),
+			dimensions_ok,
+			singular_submatrices,
+			invariant_subspace_dim,
+		}
+	}
+}
+
+/// Returns the full and partial rounds the reference script
+/// (`calc_round_numbers.py`) picks for the field, sbox and width: the
+/// cheapest numbers meeting the statistical, interpolation and Gröbner basis
+/// bounds, plus 2 full rounds and 7.5% partial rounds of margin.
+pub fn required_rounds<F: PrimeField>(
+	sbox: PoseidonSbox,
+	width: u8,
+	security_level: u32,
+) -> Result<(u8, u8), PoseidonError> {
+	match sbox.0 {
+		3 | 5 | 17 | -1 => {}
+		n => return Err(PoseidonError::InvalidSboxSize(n)),
+	}
+	if width < 2 {
+		return Err(PoseidonError::InvalidInputs);
+	}
+
+	let log2_p = log2_modulus::<F>();
+	let t = width as f64;
+	let m = security_level as f64;
+	let mut best: Option<(u64, u64, u64)> = None;
+	for partial_rounds in 1..500u64 {
+		for full_rounds in (4..100u64).step_by(2) {
+			if !satisfies_bounds(
+				log2_p,
+				t,
+				full_rounds as f64,
+				partial_rounds as f64,
+				sbox.0,
+				m,
+			) {
+				continue;
+			}
+
+			let full_rounds = full_rounds + 2;
+			let partial_rounds = (partial_rounds as f64 * 1.075).ceil() as u64;
+			let cost = full_rounds * width as u64 + partial_rounds;
+			let is_better = match best {
+				Some((best_cost, best_full_rounds, _)) => {
+					cost < best_cost || (cost == best_cost && full_rounds < best_full_rounds)
+				}
+				None => true,
+			};
+			if is_better {
+				best = Some((cost, full_rounds, partial_rounds));
+			}
+		}
+	}
+
+	match best {
+		Some((_, full_rounds, partial_rounds)) if full_rounds <= 255 && partial_rounds <= 255 => {
+			Ok((full_rounds as u8, partial_rounds as u8))
+		}
+		_ => Err(PoseidonError::UnreachableSecurityLevel(security_level)),
+	}
+}
+
+/// Whether `full_rounds` and `partial_rounds` resist the attacks of the
+/// Poseidon paper, without any security margin.
+fn satisfies_bounds(
+	log2_p: f64,
+	t: f64,
+	full_rounds: f64,
+	partial_rounds: f64,
+	alpha: i8,
+	m: f64,
+) -> bool {
+	let n = log2_p.ceil();
+	if alpha > 0 {
+		let alpha = alpha as f64;
+		let log_alpha = |x: f64| x.ln() / alpha.ln();
+
+		// Statistical attacks
+		let rf_1 = if m <= (log2_p - (alpha - 1.0) / 2.0).floor() * (t + 1.0) {
+			6.0
+		} else {
+			10.0
+		};
+		// Interpolation attack
+		let rf_2 = 1.0 + (log_alpha(2.0) * m.min(n)).ceil() + log_alpha(t).ceil() - partial_rounds;
+		// Gröbner basis attacks
+		let rf_3 = log_alpha(2.0) * m.min(log2_p) - partial_rounds;
+		let rf_4 = t - 1.0 + log_alpha(2.0) * (m / (t + 1.0)).min(log2_p / 2.0) - partial_rounds;
+		let rf_5 = (t - 2.0 + m / (2.0 * alpha.log2()) - partial_rounds) / (t - 1.0);
+
+		let rf_max = [rf_1, rf_2, rf_3, rf_4, rf_5]
+			.iter()
+			.fold(f64::MIN, |max, rf| max.max(rf.ceil()));
+		full_rounds >= rf_max
+	} else {
+		let log2_t = t.log2();
+
+		// Statistical attacks
+		let rf_1 = if m <= (log2_p - 2.0).floor() * (t + 1.0) {
+			6.0
+		} else {
+			10.0
+		};
+		// Interpolation attack
+		let rp_1 = 1.0 + (0.5 * m.min(n)).ceil() + log2_t.ceil() - (full_rounds * log2_t).floor();
+		// Gröbner basis attack
+		let rp_2 = t - 1.0 + log2_t.ceil() + (m / (t + 1.0)).ceil().min((0.5 * log2_p).ceil())
+			- (full_rounds * log2_t).floor();
+
+		full_rounds >= rf_1 && partial_rounds >= rp_1.max(rp_2)
+	}
+}
+
+/// Whether `x -> x^alpha` is a permutation of the field, i.e. `alpha` is
+/// coprime with `p - 1`.
+fn is_permutation<F: PrimeField>(sbox: PoseidonSbox) -> bool {
+	let alpha = match sbox.0 {
+		-1 => return true,
+		alpha if alpha > 1 => alpha as u128,
+		_ => return false,
+	};
+
+	let p_mod_alpha = F::Params::MODULUS
+		.as_ref()
+		.iter()
+		.rev()
+		.fold(0u128, |rem, limb| ((rem << 64) + *limb as u128) % alpha);
+	let mut a = alpha;
+	let mut b = (p_mod_alpha + alpha - 1) % alpha;
+	while b != 0 {
+		let r = a % b;
+		a = b;
+		b = r;
+	}
+	a == 1
+}
+
+fn log2_modulus<F: PrimeField>() -> f64 {
+	F::Params::MODULUS
+		.as_ref()
+		.iter()
+		.rev()
+		.fold(0f64, |acc, limb| {
+			acc * 18446744073709551616.0 + *limb as f64
+		})
+		.log2()
+}
+
+/// Returns the rows and columns of every singular square submatrix.
+fn singular_submatrices<F: PrimeField>(matrix: &[Vec<F>]) -> Vec<(Vec<usize>, Vec<usize>)> {
+	let width = matrix.len();
+	let mut singular = Vec::new();
+	for size in 1..=width {
+		let subsets = combinations(width, size);
+		for rows in subsets.iter() {
+			for cols in subsets.iter() {
+				let submatrix: Vec<Vec<F>> = rows
+					.iter()
+					.map(|i| cols.iter().map(|j| matrix[*i][*j]).collect())
+					.collect();
+				if rank(submatrix) < size {
+					singular.push((rows.clone(), cols.clone()));
+				}
+			}
+		}
+	}
+	singular
+}
+
+/// Returns the dimension of the largest subspace `S` with `M * S = S` whose
+/// vectors are 0 on the first element, which is the one going through the
+/// sbox in partial rounds. Differences in `S` never activate that sbox, so
+/// any such subspace gives a subspace trail over all the partial rounds.
+///
+/// `S` is the kernel of the rows `e_0 * M^k` for `k < width`.
+fn invariant_subspace_dim<F: PrimeField>(matrix: &[Vec<F>]) -> usize {
+	let width = matrix.len();
+	let mut rows = Vec::with_capacity(width);
+	let mut row: Vec<F> = (0..width)
+		.map(|i| if i == 0 { F::one() } else { F::zero() })
+		.collect();
+	for _ in 0..width {
+		let next = (0..width)
+			.map(|j| {
+				row.iter()
+					.zip(matrix.iter())
+					.fold(F::zero(), |acc, (r, m)| acc + *r * m[j])
+			})
+			.collect();
+		rows.push(row);
+		row = next;
+	}
+	width - rank(rows)
+}
+
+/// Returns every subset of `size` elements of `0..n`, in lexicographic order.
+fn combinations(n: usize, size: usize) -> Vec<Vec<usize>> {
+	let mut subsets = Vec::new();
+	let mut subset: Vec<usize> = (0..size).collect();
+	loop {
+		subsets.push(subset.clone());
+
+		// Advance the last element that can still move right
+		let i = match (0..size).rev().find(|i| subset[*i] < n - size + i) {
+			Some(i) => i,
+			None => return subsets,
+		};
+		subset[i] += 1;
+		for j in i + 1..size {
+			subset[j] = subset[j - 1] + 1;
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use ark_bls12_381::Fr as BlsFr;
+	use ark_ed_on_bn254::{Fq, Fr as EdFr};
+
+	#[cfg(feature = "default_poseidon")]
+	#[test]
+	fn should_validate_bundled_parameters() {
+		use crate::utils::*;
+
+		let secure = vec![
+			bn254_x5_2::get_poseidon_bn254_x5_2::<Fq>().validate(DEFAULT_SECURITY_LEVEL),
+			bn254_x5_3::get_poseidon_bn254_x5_3::<Fq>().validate(DEFAULT_SECURITY_LEVEL),
+			bn254_x5_4::get_poseidon_bn254_x5_4::<Fq>().validate(DEFAULT_SECURITY_LEVEL),
+			bn254_x5_5::get_poseidon_bn254_x5_5::<Fq>().validate(DEFAULT_SECURITY_LEVEL),
+			bn254_x17_3::get_poseidon_bn254_x17_3::<Fq>().validate(DEFAULT_SECURITY_LEVEL),
+			bn254_x17_5::get_poseidon_bn254_x17_5::<Fq>().validate(DEFAULT_SECURITY_LEVEL),
+			bls381_x5_2::get_poseidon_bls381_x5_2::<BlsFr>().validate(DEFAULT_SECURITY_LEVEL),
+			bls381_x5_3::get_poseidon_bls381_x5_3::<BlsFr>().validate(DEFAULT_SECURITY_LEVEL),
+			bls381_x5_4::get_poseidon_bls381_x5_4::<BlsFr>().validate(DEFAULT_SECURITY_LEVEL),
+			bls381_x5_5::get_poseidon_bls381_x5_5::<BlsFr>().validate(DEFAULT_SECURITY_LEVEL),
+			bls381_x17_3::get_poseidon_bls381_x17_3::<BlsFr>().validate(DEFAULT_SECURITY_LEVEL),
+			bls381_x17_5::get_poseidon_bls381_x17_5::<BlsFr>().validate(DEFAULT_SECURITY_LEVEL),
+			bls381_x17_2::get_poseidon_bls381_x17_2::<BlsFr>().validate(DEFAULT_SECURITY_LEVEL),
+			bls381_x17_4::get_poseidon_bls381_x17_4::<BlsFr>().validate(DEFAULT_SECURITY_LEVEL),
+		];
+		for report in secure {
+			assert!(report.is_secure(), "{}", report);
+		}
+
+		// 3 divides p - 1 on both curves, and 3, 5 and 17 all divide it on the
+		// scalar field of ed_on_bn254, so those sboxes are not permutations
+		let not_permutations = vec![
+			bn254_x3_3::get_poseidon_bn254_x3_3::<Fq>().validate(DEFAULT_SECURITY_LEVEL),
+			bn254_x3_5::get_poseidon_bn254_x3_5::<Fq>().validate(DEFAULT_SECURITY_LEVEL),
+			bls381_x3_3::get_poseidon_bls381_x3_3::<BlsFr>().validate(DEFAULT_SECURITY_LEVEL),
+			bls381_x3_5::get_poseidon_bls381_x3_5::<BlsFr>().validate(DEFAULT_SECURITY_LEVEL),
+			ed_on_bn254_x3_3::get_poseidon_ed_on_bn254_x3_3::<EdFr>()
+				.validate(DEFAULT_SECURITY_LEVEL),
+			ed_on_bn254_x3_5::get_poseidon_ed_on_bn254_x3_5::<EdFr>()
+				.validate(DEFAULT_SECURITY_LEVEL),
+			ed_on_bn254_x5_3::get_poseidon_ed_on_bn254_x5_3::<EdFr>()
+				.validate(DEFAULT_SECURITY_LEVEL),
+			ed_on_bn254_x5_5::get_poseidon_ed_on_bn254_x5_5::<EdFr>()
+				.validate(DEFAULT_SECURITY_LEVEL),
+			ed_on_bn254_x17_3::get_poseidon_ed_on_bn254_x17_3::<EdFr>()
+				.validate(DEFAULT_SECURITY_LEVEL),
+			ed_on_bn254_x17_5::get_poseidon_ed_on_bn254_x17_5::<EdFr>()
+				.validate(DEFAULT_SECURITY_LEVEL),
+		];
+		for report in not_permutations {
+			assert!(!report.sbox_is_permutation);
+			assert!(report.rounds_ok(), "{}", report);
+			assert!(report.dimensions_ok);
+			assert!(report.singular_submatrices.is_empty());
+			assert_eq!(report.invariant_subspace_dim, 0);
+		}
+	}
+
+	#[test]
+	fn should_compute_required_rounds() {
+		assert_eq!(
+			required_rounds::<Fq>(PoseidonSbox(5), 3, DEFAULT_SECURITY_LEVEL).unwrap(),
+			(8, 56)
+		);
+		assert_eq!(
+			required_rounds::<Fq>(PoseidonSbox(3), 5, DEFAULT_SECURITY_LEVEL).unwrap(),
+			(8, 84)
+		);
+		assert_eq!(
+			required_rounds::<BlsFr>(PoseidonSbox(17), 3, DEFAULT_SECURITY_LEVEL).unwrap(),
+			(8, 31)
+		);
+		assert!(matches!(
+			required_rounds::<Fq>(PoseidonSbox(7), 3, DEFAULT_SECURITY_LEVEL),
+			Err(PoseidonError::InvalidSboxSize(7))
+		));
+	}
+
+	#[test]
+	fn should_reject_weak_parameters() {
+		let one = Fq::from(1u64);
+		let two = Fq::from(2u64);
+		let zero = Fq::from(0u64);
+		// the first element never mixes with the others, and the zeros make
+		// submatrices singular
+		let mds_matrix = vec![vec![one, zero, zero], vec![zero, two, one], vec![
+			zero, one, two,
+		]];
+		let params = PoseidonParameters::<Fq>::new(
+			vec![zero; (8 + 40) * 3],
+			mds_matrix,
+			8,
+			40,
+			3,
+			PoseidonSbox(5),
+		);
+
+		let report = params.validate(DEFAULT_SECURITY_LEVEL);
+		assert!(!report.is_secure());
+		assert!(!report.rounds_ok());
+		assert!(report.sbox_is_permutation);
+		assert!(report.dimensions_ok);
+		assert_eq!(report.invariant_subspace_dim, 2);
+		assert!(report.singular_submatrices.contains(&(vec![0], vec![1])));
+
+		let mut truncated = params;
+		truncated.round_keys.pop();
+		assert!(!truncated.validate(DEFAULT_SECURITY_LEVEL).dimensions_ok);
+	}
+}
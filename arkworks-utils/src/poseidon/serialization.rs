@@ -0,0 +1,145 @@
+use super::{sbox::PoseidonSbox, PoseidonParameters};
+use ark_ff::fields::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::vec::Vec;
+
+/// Version of the canonical encoding of `PoseidonParameters`, written first
+/// so the layout can change without misreading older encodings.
+pub const SERIALIZATION_VERSION: u8 = 1;
+
+/// Encodes the version, the width, the rounds, the S-box exponent, whether
+/// the parameters are optimized, then the round keys and the MDS matrix. The
+/// optimized constants are derived again when decoding rather than stored.
+impl<F: PrimeField> CanonicalSerialize for PoseidonParameters<F> {
+	fn serialize<W: Write>(&self, mut writer: W) -> Result<(), SerializationError> {
+		SERIALIZATION_VERSION.serialize(&mut writer)?;
+		self.width.serialize(&mut writer)?;
+		self.full_rounds.serialize(&mut writer)?;
+		self.partial_rounds.serialize(&mut writer)?;
+		(self.sbox.0 as u8).serialize(&mut writer)?;
+		self.optimized.is_some().serialize(&mut writer)?;
+		self.round_keys.serialize(&mut writer)?;
+		self.mds_matrix.serialize(&mut writer)
+	}
+
+	fn serialized_size(&self) -> usize {
+		6 + self.round_keys.serialized_size() + self.mds_matrix.serialized_size()
+	}
+}
+
+impl<F: PrimeField> CanonicalDeserialize for PoseidonParameters<F> {
+	fn deserialize<R: Read>(mut reader: R) -> Result<Self, SerializationError> {
+		let version = u8::deserialize(&mut reader)?;
+		if version != SERIALIZATION_VERSION {
+			return Err(SerializationError::InvalidData);
+		}
+
+		let width = u8::deserialize(&mut reader)?;
+		let full_rounds = u8::deserialize(&mut reader)?;
+		let partial_rounds = u8::deserialize(&mut reader)?;
+		let sbox = PoseidonSbox(u8::deserialize(&mut reader)? as i8);
+		sbox.check().map_err(|_| SerializationError::InvalidData)?;
+		let optimized = bool::deserialize(&mut reader)?;
+		let round_keys = Vec::<F>::deserialize(&mut reader)?;
+		let mds_matrix = Vec::<Vec<F>>::deserialize(&mut reader)?;
+
+		let params = Self::new(
+			round_keys,
+			mds_matrix,
+			full_rounds,
+			partial_rounds,
+			width,
+			sbox,
+		);
+		if !params.has_valid_dimensions() {
+			return Err(SerializationError::InvalidData);
+		}
+		if optimized {
+			return params
+				.optimized()
+				.map_err(|_| SerializationError::InvalidData);
+		}
+		Ok(params)
+	}
+}
+
+impl<F: PrimeField> PoseidonParameters<F> {
+	/// Whether there are `width` round keys per round and the MDS matrix is
+	/// `width` by `width`.
+	pub fn has_valid_dimensions(&self) -> bool {
+		let width = self.width as usize;
+		let num_rounds = self.full_rounds as usize + self.partial_rounds as usize;
+		width > 0
+			&& self.round_keys.len() == num_rounds * width
+			&& self.mds_matrix.len() == width
+			&& self.mds_matrix.iter().all(|row| row.len() == width)
+	}
+}
+
+#[cfg(all(test, feature = "poseidon_bn254_x5_3"))]
+mod test {
+	use super::*;
+	use crate::utils::bn254_x5_3::get_poseidon_bn254_x5_3;
+	use ark_ed_on_bn254::Fq;
+
+	fn assert_same(params: &PoseidonParameters<Fq>, decoded: &PoseidonParameters<Fq>) {
+		assert_eq!(decoded.round_keys, params.round_keys);
+		assert_eq!(decoded.mds_matrix, params.mds_matrix);
+		assert_eq!(decoded.full_rounds, params.full_rounds);
+		assert_eq!(decoded.partial_rounds, params.partial_rounds);
+		assert_eq!(decoded.width, params.width);
+		assert_eq!(decoded.sbox, params.sbox);
+		assert_eq!(decoded.optimized, params.optimized);
+	}
+
+	#[test]
+	fn should_serialize_and_deserialize() {
+		let params = get_poseidon_bn254_x5_3::<Fq>();
+		let mut bytes = Vec::new();
+		params.serialize(&mut bytes).unwrap();
+		assert_eq!(bytes.len(), params.serialized_size());
+		assert_eq!(bytes[0], SERIALIZATION_VERSION);
+		assert_same(
+			&params,
+			&PoseidonParameters::deserialize(&bytes[..]).unwrap(),
+		);
+
+		let optimized = params.optimized().unwrap();
+		let mut bytes = Vec::new();
+		optimized.serialize(&mut bytes).unwrap();
+		assert_same(
+			&optimized,
+			&PoseidonParameters::deserialize(&bytes[..]).unwrap(),
+		);
+	}
+
+	#[test]
+	fn should_reject_unknown_versions_dimensions_and_sboxes() {
+		let params = get_poseidon_bn254_x5_3::<Fq>();
+		let mut bytes = Vec::new();
+		params.serialize(&mut bytes).unwrap();
+
+		let mut unknown_version = bytes.clone();
+		unknown_version[0] = SERIALIZATION_VERSION + 1;
+		assert!(matches!(
+			PoseidonParameters::<Fq>::deserialize(&unknown_version[..]),
+			Err(SerializationError::InvalidData)
+		));
+
+		// The width is the second byte
+		let mut wrong_width = bytes.clone();
+		wrong_width[1] = 4;
+		assert!(matches!(
+			PoseidonParameters::<Fq>::deserialize(&wrong_width[..]),
+			Err(SerializationError::InvalidData)
+		));
+
+		// The sbox exponent is the fifth byte
+		let mut wrong_sbox = bytes;
+		wrong_sbox[4] = 7;
+		assert!(matches!(
+			PoseidonParameters::<Fq>::deserialize(&wrong_sbox[..]),
+			Err(SerializationError::InvalidData)
+		));
+	}
+}
@@ -0,0 +1,251 @@
+use crate::poseidon::{grain::PoseidonGrainLFSR, sbox::PoseidonSbox, PoseidonError};
+use ark_ff::fields::PrimeField;
+use ark_std::vec::Vec;
+
+/// The Poseidon2 permutation.
+///
+/// Full rounds add `width` round keys and mix the state with the external
+/// matrix, partial rounds add one round key to the first element and mix
+/// with the internal matrix `J + diag(mat_internal_diag_m_1)`, `J` being the
+/// all-ones matrix. The external matrix is fixed by the width, which must be
+/// 2, 3 or a multiple of 4.
+#[derive(Default, Clone, Debug, PartialEq)]
+pub struct Poseidon2Parameters<F: PrimeField> {
+	/// The round key constants, `width` per full round and one per partial
+	/// round
+	pub round_keys: Vec<F>,
+	/// The diagonal of the internal matrix, minus one
+	pub mat_internal_diag_m_1: Vec<F>,
+	/// Number of full SBox rounds
+	pub full_rounds: u8,
+	/// Number of partial rounds
+	pub partial_rounds: u8,
+	/// The size of the permutation, in field elements.
+	pub width: u8,
+	/// The S-box to apply in the sub words layer.
+	pub sbox: PoseidonSbox,
+}
+
+impl<F: PrimeField> Poseidon2Parameters<F> {
+	/// Fails with `PoseidonError::InvalidWidth` when the external matrix is
+	/// not defined for `width`.
+	pub fn new(
+		round_keys: Vec<F>,
+		mat_internal_diag_m_1: Vec<F>,
+		full_rounds: u8,
+		partial_rounds: u8,
+		width: u8,
+		sbox: PoseidonSbox,
+	) -> Result<Self, PoseidonError> {
+		if !Self::is_width_supported(width) {
+			return Err(PoseidonError::InvalidWidth(width));
+		}
+		Ok(Self {
+			round_keys,
+			mat_internal_diag_m_1,
+			full_rounds,
+			partial_rounds,
+			width,
+			sbox,
+		})
+	}
+
+	/// Draw the round constants of the reference script
+	/// (`poseidon2_rust_params.sage`) from the LFSR: `width` per full round
+	/// and a single one per partial round, in round order.
+	pub fn create_round_keys(
+		lfsr: &mut PoseidonGrainLFSR,
+		field_size: u16,
+		width: u8,
+		full_rounds: u8,
+		partial_rounds: u8,
+	) -> Vec<F> {
+		let num_keys = full_rounds as usize * width as usize + partial_rounds as usize;
+		lfsr.get_field_elements_rejection_sampling(field_size, num_keys)
+	}
+
+	/// Whether the external matrix is defined for `width`.
+	pub fn is_width_supported(width: u8) -> bool {
+		width == 2 || width == 3 || (width > 0 && width % 4 == 0)
+	}
+
+	/// Check that the constants fit the rounds and the width.
+	pub fn check(&self) -> Result<(), PoseidonError> {
+		let width = self.width as usize;
+		let num_keys = self.full_rounds as usize * width + self.partial_rounds as usize;
+		if !Self::is_width_supported(self.width) {
+			return Err(PoseidonError::InvalidWidth(self.width));
+		}
+		if self.full_rounds % 2 != 0
+			|| self.round_keys.len() != num_keys
+			|| self.mat_internal_diag_m_1.len() != width
+		{
+			return Err(PoseidonError::InvalidInputs);
+		}
+		Ok(())
+	}
+
+	/// Multiply `state` by the external matrix: `circ(2, 1)` and
+	/// `circ(2, 1, 1)` for widths 2 and 3, otherwise `circ(2 M4, M4, ..., M4)`
+	/// with `M4` the 4x4 matrix of the paper.
+	pub fn matmul_external(state: &mut [F]) {
+		match state.len() {
+			2 | 3 => {
+				let sum = state.iter().fold(F::zero(), |acc, a| acc + a);
+				state.iter_mut().for_each(|a| *a += sum);
+			}
+			_ => {
+				state.chunks_exact_mut(4).for_each(matmul_m4);
+				let mut sums = [F::zero(); 4];
+				for chunk in state.chunks_exact(4) {
+					for (sum, a) in sums.iter_mut().zip(chunk.iter()) {
+						*sum += a;
+					}
+				}
+				for chunk in state.chunks_exact_mut(4) {
+					for (a, sum) in chunk.iter_mut().zip(sums.iter()) {
+						*a += sum;
+					}
+				}
+			}
+		}
+	}
+
+	/// Multiply `state` by the internal matrix.
+	pub fn matmul_internal(&self, state: &mut [F]) {
+		let sum = state.iter().fold(F::zero(), |acc, a| acc + a);
+		for (a, d) in state.iter_mut().zip(self.mat_internal_diag_m_1.iter()) {
+			*a = *a * d + sum;
+		}
+	}
+}
+
+/// Multiply 4 elements by
+/// `[[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]]`, with the
+/// additions of the reference implementation.
+fn matmul_m4<F: PrimeField>(x: &mut [F]) {
+	let t0 = x[0] + x[1];
+	let t1 = x[2] + x[3];
+	let t2 = x[1].double() + t1;
+	let t3 = x[3].double() + t0;
+	let t4 = t1.double().double() + t3;
+	let t5 = t0.double().double() + t2;
+	let t6 = t3 + t5;
+	let t7 = t2 + t4;
+	x[0] = t6;
+	x[1] = t5;
+	x[2] = t7;
+	x[3] = t4;
+}
+
+#[cfg(all(test, feature = "default_poseidon2"))]
+mod test {
+	use super::*;
+	use crate::utils::{
+		poseidon2_bls381_x5_2::get_poseidon2_bls381_x5_2,
+		poseidon2_bls381_x5_3::get_poseidon2_bls381_x5_3,
+		poseidon2_bls381_x5_4::get_poseidon2_bls381_x5_4,
+		poseidon2_bn254_x5_2::get_poseidon2_bn254_x5_2,
+		poseidon2_bn254_x5_3::get_poseidon2_bn254_x5_3,
+		poseidon2_bn254_x5_4::get_poseidon2_bn254_x5_4,
+	};
+	use ark_bls12_381::Fr as BlsFr;
+	use ark_bn254::Fr;
+
+	fn regenerate_round_keys<F: PrimeField>(params: &Poseidon2Parameters<F>) -> Vec<F> {
+		let field_size = F::size_in_bits() as u16;
+		let mut lfsr = PoseidonGrainLFSR::new(
+			false,
+			field_size,
+			params.width,
+			params.full_rounds,
+			params.partial_rounds,
+		);
+		Poseidon2Parameters::create_round_keys(
+			&mut lfsr,
+			field_size,
+			params.width,
+			params.full_rounds,
+			params.partial_rounds,
+		)
+	}
+
+	#[test]
+	fn should_match_generated_round_keys() {
+		for params in [
+			get_poseidon2_bn254_x5_2::<Fr>(),
+			get_poseidon2_bn254_x5_3::<Fr>(),
+			get_poseidon2_bn254_x5_4::<Fr>(),
+		] {
+			assert!(params.check().is_ok());
+			assert_eq!(regenerate_round_keys(&params), params.round_keys);
+		}
+		for params in [
+			get_poseidon2_bls381_x5_2::<BlsFr>(),
+			get_poseidon2_bls381_x5_3::<BlsFr>(),
+			get_poseidon2_bls381_x5_4::<BlsFr>(),
+		] {
+			assert!(params.check().is_ok());
+			assert_eq!(regenerate_round_keys(&params), params.round_keys);
+		}
+	}
+
+	#[test]
+	fn should_multiply_by_external_matrix() {
+		let m4 = [[5u64, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+		let state: Vec<Fr> = (1..=8u64).map(Fr::from).collect();
+		let dense = |i: usize| {
+			(0..8).fold(Fr::from(0u64), |acc, j| {
+				let factor = if i / 4 == j / 4 { 2 } else { 1 };
+				acc + Fr::from(factor * m4[i % 4][j % 4]) * state[j]
+			})
+		};
+
+		let mut res = state.clone();
+		Poseidon2Parameters::matmul_external(&mut res);
+		assert_eq!(res, (0..8).map(dense).collect::<Vec<_>>());
+
+		let mut res = state[..4].to_vec();
+		matmul_m4(&mut res);
+		let expected: Vec<Fr> = m4
+			.iter()
+			.map(|row| {
+				row.iter()
+					.zip(state.iter())
+					.fold(Fr::from(0u64), |acc, (m, a)| acc + Fr::from(*m) * a)
+			})
+			.collect();
+		assert_eq!(res, expected);
+	}
+
+	#[test]
+	fn should_check_parameters() {
+		let params = get_poseidon2_bn254_x5_4::<Fr>();
+		assert!(params.check().is_ok());
+
+		let mut wrong_width = params.clone();
+		wrong_width.width = 5;
+		assert!(matches!(
+			wrong_width.check(),
+			Err(PoseidonError::InvalidWidth(5))
+		));
+		assert!(matches!(
+			Poseidon2Parameters::new(
+				params.round_keys.clone(),
+				params.mat_internal_diag_m_1.clone(),
+				params.full_rounds,
+				params.partial_rounds,
+				5,
+				params.sbox,
+			),
+			Err(PoseidonError::InvalidWidth(5))
+		));
+
+		let mut missing_key = params;
+		missing_key.round_keys.pop();
+		assert!(matches!(
+			missing_key.check(),
+			Err(PoseidonError::InvalidInputs)
+		));
+	}
+}
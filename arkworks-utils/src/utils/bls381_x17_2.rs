@@ -0,0 +1,118 @@
+use super::parse_matrix;
+use crate::{
+	poseidon::{sbox::PoseidonSbox, PoseidonParameters},
+	utils::parse_vec,
+	Vec,
+};
+use ark_ff::PrimeField;
+// sage generate_parameters_grain.sage 1 0 255 2 8 31
+// 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001
+pub const FULL_ROUNDS: u8 = 8;
+pub const PARTIAL_ROUNDS: u8 = 31;
+pub const WIDTH: u8 = 2;
+pub const SBOX: PoseidonSbox = PoseidonSbox(17);
+
+pub const ROUND_CONSTS: [&str; 78] = [
+	"0x46a1d3c7c763bd495afbd75a24a26798b3aa965134ca72db2fc5b81d6cfb8b6e",
+	"0x562e4f3f3b8a9c0e0018e477ec196b2782fed67558a72b35ebbe07c51065ab2c",
+	"0x6370f94cb50447ae39394d5b53172d9c10241a0767ed1b7bd86880c64d4a479d",
+	"0x60f42e7b60194a4d572b7f6ca67c9a7a8b882555cd1a0459666f647dcb354cb5",
+	"0x3a9fcb6dfa5d5574fc879797976e95017f88958d18238452a500726ebdae2496",
+	"0x048813a0d336ca0b587e7e7a18384974015d4f9da8be444acb317c99bd43d790",
+	"0x13341199051f27d7f365720a8c53261ae3085ecf49c32c8e7b3b054df86aa05b",
+	"0x3f0bf5edc1e9fca8dd86c105909f53310a63b24995651889c9e5b870d9e67893",
+	"0x2ebf63c41c8f337e42c914f1cf60fdca053ca995ef1992bafbce0cd0305b7762",
+	"0x2225718640122ced946e9f7bb7c6c65c59bac7b6d42c8e7bb32b23f6367ac27b",
+	"0x4bb6972a6107267f3f9182688c39703cc007ff64782dcbc986b5ff14e6d17c59",
+	"0x0ace6f1c2d996b92dfce2291f27bd3efdeaf1008b47bc73bc964f233c647bad5",
+	"0x07bbd1f8074925c3497cf3eb3302af9e8f9a17931e762170a5650f0f6a6e6677",
+	"0x2564957724193561b926d96a24a3fbc2dcd4cc030998ea239f3ce1e721204c24",
+	"0x28845ec84012d260c8a87d4c7790ec015069daf047857b2ccf68f431b066921f",
+	"0x31d4530714d6cfd2fffe42b9b2131f1c0525ab52ab024bcc2914f68a50ac2661",
+	"0x6e6b666059c842cf6c59e06b17c6df4f74f03ce0705d6c00992b29e4fbc04500",
+	"0x4ad3982bd98b0d6ed764cc5dcaacce01dda8ff1c1a18f5d454a363b2ff6d8022",
+	"0x6fcec5e25f5ccec29eb24b7944746037a89ce7c11547e41fd520fbca70403f3b",
+	"0x6996967934bbf44e443d3c61ea028525328fa4d3ed7076710e1f17ce2344023b",
+	"0x291f8e95f6dd5c47dda65e42525f7eb0b2d9de2361c7df812767e79e52fa616f",
+	"0x459099e74a9bb7c0fba2545fe26c13a7dd6d8a928e92463dc0d2184167cda7ae",
+	"0x2ea6a3963b34890dfc657e9069bc496d549f5cf4ce292d67ba1c0c4ea4c06e9a",
+	"0x676f0d00c45eca6fab0ef2082c115c6001f88894482f16418c9f94f332b08f9e",
+	"0x2eb0f428e1087e8ab8196d9bd89811cb6066b982de391114a7f0b4a246f9b130",
+	"0x145201cc9e7ef341c66e8b16e76f2b9638deb94aa90da5f20927d5e1c13d5b53",
+	"0x239cd70a133a9790737b5cbd4efbd1947e2b197e2d55909882253dcb43b9a9e0",
+	"0x05345d14548d24011add5e0f7d669661be9d5250ea44cf266b6c87f60de81057",
+	"0x612e5797894bb231f266156944fe4d50feb55c7f06e6670c6e1a8916b17a91da",
+	"0x33d52c45728dbb360bae142a2686d7e93ce2d7df36168d11fd4a2d3c3d790778",
+	"0x49cf3bb2b4b2e3ee4eba2d31df7f4abc39e66f73bb5640f1aa480a25becc356e",
+	"0x57c6534195afc58567a0fae8727a6671ea24cf96a2f004c35a4df811f3d12fef",
+	"0x32500587ff91c78f54f89544f340241a629dd770f7a0a911eec79a13cd65beeb",
+	"0x24f44537ec3513914209421cb857acc604e0d0d2da830c3e061033ffdd23fd8c",
+	"0x354ab90a2934d06fe20c136e02f90f54275aa65c6e68489e18b359148dd52dc8",
+	"0x4aef20ffe16ee0872f4d96ab7f683cb36373708403c474ec69d9907a0e6df538",
+	"0x4defdb8c77773cc44b8fbc5c0029cf5455130c1db5761b29c26639f4886bf34c",
+	"0x3f78bc61c0e63e81db60c707611135d5e0f339ea4840356549944b978a93f2ad",
+	"0x24984a43019cc60d869b0beee53f0312af83035453d50207a9dbdb29275ad0f7",
+	"0x1b737fc5211c3377e68b5c698129907734b4b6dfc971d5e4057a5d0e67319b26",
+	"0x005b7af3a68435ad77872343733f789e47125e8eba2347026a8f43ef0064ff96",
+	"0x49ef617f8656c09b329022b886db69a846c7aa8b437daf56c9c4bfe88f2fba79",
+	"0x51d2b68b959a75ab3cffe929ce77ba0aecd95fa52d8c72bf79648205625bb06a",
+	"0x415abb0e0126a72bb36d42ede9042c1f7622dc2313dc08d15e8875fc5964c433",
+	"0x11d0a72b292c2f52d73f34af85f17a391225a40875582bf981b70e93e77ae231",
+	"0x3453dafd6a021678b3e8ba98ff5d3253c4951da9d6cb30558c068755bab0a720",
+	"0x64cb5af399ac72d8a83a06b041ad656bd58b020da00bf4d4caa97fa64623afbc",
+	"0x61a867f37b29262e797b052d70a33053fb53351bb17ee54850db394859d959a0",
+	"0x142eb903791f3ec9cb642c687ea36197ca898bf47e62c2626ef6a2f2e6ed6a31",
+	"0x154b0584635c58a70e8fc3c55af64951d4bcb6966056320c154a213c68bddd15",
+	"0x4169cc0067f7435d9fc3919e2a361c188e5de198ca061837a2b5bfe424495d90",
+	"0x657f8bf023b888dda601479a35830bfb82f3c33acec87ccf98828a462a203797",
+	"0x4ad7ac59de2e554f99d0a16e1554fa29c01012db7fd1d2fa2dd29f2783b1b05a",
+	"0x2a44e78184d001d5ac7653900c12b6a045aeefd0976c701d20d0b0d2470ac3b7",
+	"0x25f3708699b36a8a55b2f7766ebe23658efe652f8649232f70b09e278e09afd3",
+	"0x2540541bb13aca9c164722470981baa487577cdf5f08863e8eac909a63619de3",
+	"0x23df43ba6ce4ff94d4f4f5cb126fa4cd11838e6c94099338ed59aebbff98890e",
+	"0x46059cc83dc37d7504eece1c82d618c438589100c40c3139a7e0cbf505a8a766",
+	"0x3e9d1af58aaa9e9169ae664a9e6c974a404b7324a0d12599463978fb675c32a2",
+	"0x4cbd2402418fd347a810324aae1d77d5520dda403a0e2639e19aac6bf054e047",
+	"0x2bd3eef1140fbad438dde061f5ab1af61baab1d5f1a91ea11c1cca1e0090fbb6",
+	"0x135e59276387235f0a710fc92bde60fae2a41c24fca92bfcd3a94e499ba1fc99",
+	"0x5d1d926ab28b213e524d74d73b92d3b7ef3e55867ab7c2f73062c4543fc1db9c",
+	"0x1ed691a662d02f027af971d0ab2f9ffd6dbd496e6399abfbfba43afd3903a1a8",
+	"0x46cbe73bc99f20a7a9271a13ed358e66a03bcd78a2698f4c5028ff8ebd74b5ca",
+	"0x089a3d960eed35c4f96e2f7c5d157f523273482bc03fd77eef40ea7e3ca3b4c5",
+	"0x0886dba6c3b86c0163db7567ec0cada885f1a3dbbb824ab7e92666ab738096d7",
+	"0x16cafe5725fa5510f309fecb0b79e3bfd8344dca541cd75c41f0f103c1ee6c9b",
+	"0x25d6507076f085d47111e81938e7178e0970781efa0cf2509abbfdde9431dcb4",
+	"0x68b27e1dfc68057a07b626716378038d788c7b9b5b39d5932f423fe108e8afc6",
+	"0x2c768f5e830a33ebcd05c7bd05fabf7b26c1d72bfe4b2b6089915b7a68722876",
+	"0x0377ab5ffecd8dac6482da8ed7fd7bce25cff19511f18fba60e9af315d14cfec",
+	"0x71a16a819fa0a260d8253b7c172ca7859ef9706f76d95cb65e1aea9479279b66",
+	"0x39cd3718b15c3012a307d6741af2ec56d714445894f15ed12cc3b15d5392bccb",
+	"0x11da24fbaa57b387b4bee9a5f68ca2e315e5ecf9880656e65b6c497d301b8383",
+	"0x1cc39e52195cf489160480b705d88ad3525967405ce9f9a1542a601362395bf9",
+	"0x315b40dceb3439dfbf40035972ae6324066a9c90e64c44a09486e55ca60002e4",
+	"0x6cb5200ce2316db0cc7b62c6a55b6cfe0629b0f487d5f86a85167471a84f5485",
+];
+
+pub const MDS_ENTRIES: [[&str; 2]; 2] = [
+	[
+		"0x43405a35bfee89e5b5f4b71b0202e748809f06b488eff8ae5c6b6bc930af7f8d",
+		"0x389aecb209e3a006996eab2f34678bf5e3dfcb2fbaa30dd3f69fc0f492c0c18b",
+	],
+	[
+		"0x3d4736df395e61cd754c2cbe204c29484812bed666dcded0c481c6dadd7010e0",
+		"0x467c6579918c19918149d36a7b9faad1e8c0894923bbfa8681ac9a221b20309b",
+	],
+];
+
+pub fn get_rounds_poseidon_bls381_x17_2<F: PrimeField>() -> Vec<F> {
+	parse_vec(ROUND_CONSTS.to_vec())
+}
+pub fn get_mds_poseidon_bls381_x17_2<F: PrimeField>() -> Vec<Vec<F>> {
+	parse_matrix(MDS_ENTRIES.iter().map(|x| x.to_vec()).collect::<Vec<_>>())
+}
+
+pub fn get_poseidon_bls381_x17_2<F: PrimeField>() -> PoseidonParameters<F> {
+	let rounds = get_rounds_poseidon_bls381_x17_2();
+	let mds = get_mds_poseidon_bls381_x17_2();
+	PoseidonParameters::<F>::new(rounds, mds, FULL_ROUNDS, PARTIAL_ROUNDS, WIDTH, SBOX)
+}
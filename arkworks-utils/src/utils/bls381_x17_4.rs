@@ -0,0 +1,212 @@
+use super::parse_matrix;
+use crate::{
+	poseidon::{sbox::PoseidonSbox, PoseidonParameters},
+	utils::parse_vec,
+	Vec,
+};
+use ark_ff::PrimeField;
+// sage generate_parameters_grain.sage 1 0 255 4 8 31
+// 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001
+pub const FULL_ROUNDS: u8 = 8;
+pub const PARTIAL_ROUNDS: u8 = 31;
+pub const WIDTH: u8 = 4;
+pub const SBOX: PoseidonSbox = PoseidonSbox(17);
+
+pub const ROUND_CONSTS: [&str; 156] = [
+	"0x171ba7beb3ebf0d1b5bea43b00e1b158854661c74d854ce2847487b008b01116",
+	"0x0c5121da2850a2937bd442cccb6ddb33382cad540882a5ec1be24029235fb192",
+	"0x07510d40d45385ab6915f6a94aab6df17c65fe85611f1cec71cb65d18668daa2",
+	"0x1af030ca1c6523a0aebacdf28e1e90b35b6a125862736f3a408e5c3f36884480",
+	"0x2c544c8b6f047dab66de126af3a3c7e48931897853b69375b0fc0680f1c3ec1e",
+	"0x17dae155c9eef5efcb5d9c80355345395a1667af1b22bdf1b6bb7a2ba294471b",
+	"0x1ac1b84b091248b3f0fd6db671662c70f12370a37a261ca750075c1a732aec31",
+	"0x149a38970b56abb2a4f24e377c1256fdd30d5332545a9e2d38fcbe8ee4e2fab9",
+	"0x72bcf9e3c48d6ef43a39e31d116d71095b9ee64b9115674f3fa5e1b717c11be8",
+	"0x1c14c1c12cbfd2e9646304faa83640d0b9091016d9e5f2275b15a91eec570fa5",
+	"0x621758a15cad93fa0d414d9d6eba774938c7abc3d16fb26fd09a96ee57f8b636",
+	"0x2f6ff4fb4aff14fc6f7384d08ecb36af41670bb9157479aea1d7a9553ca1a2d5",
+	"0x5902488a185d5882298e0c3c9a02b08dfc79b8085599880f67235b3879a9fbb2",
+	"0x0c7796b7b2aa98587f0acc5229f54ad42c18e7f21d5eeca5790b2411e7a1771c",
+	"0x24e62080a5c2934eda39152306f3509db9c638142a55662ca187303f15786e29",
+	"0x2980d726929eea885882b631ea5fb23d28e0695097abd8c9df2b6c5d08b739ba",
+	"0x22ff58b2a6274dcea01c97958f61877b6201d321ff88e5876bc0cbc9e6f9cd5e",
+	"0x5ccd3a5bff697becad73f6adb00f4363cbdaaae0bb79cb7b3249edd9dc5055ad",
+	"0x2624b4e400f66bd321e784ec08b60b5ad5b37983d2345dc970caf47809b9cf54",
+	"0x1f03e9ba34527370156de2cd5cf5b4aa439610b405560239ea522ba46235da76",
+	"0x597444b55c9ac7b6a0492d9791afb197c22ea89b3ce53fec9f88f7c40623797b",
+	"0x1c75879201e93762388cab18c14c22bb6c67888a324c14061fa48332f95d3c78",
+	"0x4da94547ce29daaad5c71a9ca11ecf8ece8538171d031783bc99aeac41364828",
+	"0x3bcb0aebfda55c4d887d8e187c5df95c081d45cc951828d4f4262cbbe0c439af",
+	"0x50827865824e146dad5f4729dac6154daf5ee728e11ef5ecae98ad288b765db6",
+	"0x1a832ddc6a14e40c3f05b31135daaa26c4cd3b1c88990b04363969f39efded95",
+	"0x583dbef7246d1677f4c9969e20d76db97e504539c9876e9bd2007d5486ba7191",
+	"0x5eb8723d3fb7705d8a23251b215fb54060103f9327614e88d32aa38b714030c8",
+	"0x3f142f752a7ef744ed048a5c07bb458a61576568a768f94d8543adf7e02cddea",
+	"0x672b3e39d4789433fe2815c39e666e03d8a6f9ec4bb053f625b28c091363ffff",
+	"0x0b76007c207f5660467aab71144f63b6ab80378bc9bd925fac46fb4690f4317e",
+	"0x667acc1b9fa3151039e80daf954e415b1f990d8efab910f3fee8f85aedec7d89",
+	"0x08c384179f278bec61e6c212a4b3774575d576d0476530a9dc025dfaf2189089",
+	"0x165b5a4c0aeefd5c306ea62d306b6a618157bef5b8ca3a6c83cf3d003560fce0",
+	"0x3dbb9f1d10e92ea007926992e0a3cce8662db1faae624ce4020c8592c374ee2b",
+	"0x3eca54d34bf26434ee2ccd1c7c41cf3955960b8764779b91ba5e677200ef60af",
+	"0x5c2cf9bab6bfa2a8561dedbd7e71dd8e9b8bdbd56ee22186310569de5118a910",
+	"0x173f1ffaa30e1105f13228079d03df405c51946e625ad290d96f5b135b8322a1",
+	"0x39c5650a1c90e19d0b02553374c8f21b71046721c088c9311a7f8f7c6a97fd4f",
+	"0x503f4341ec75252b63fdf2c4e9a2b67d3b92ad3605b75b3bfb7d8447eadac5ec",
+	"0x109a24f46d1a2cdaeaae3ddfadf61cf19a3219e208ce64941b6585254bcefeef",
+	"0x10df136f7a5986b7212ff90f98c650adea008c280bd4d306d8c0412df18b9cbe",
+	"0x421be564d59ef95a08f4b3916118a0073a2ef18aaf206523c23b27ca0d477ee0",
+	"0x2224fd73d381f17d0c33121079e54540323736d5f08c3d68913db7f0ded33d50",
+	"0x28aef937082015cfda242be253899f49d6ec9d414310744b78370c8db5060b3d",
+	"0x086af993b7484174ce4ce554e07d53a54876b364409be7e369ce9cf843c798c8",
+	"0x0c584417812b4811d134a02aa39d4736e7bc736fac38c6ab3c0882a0747ecea6",
+	"0x14f5ccd6b545b5a0d29618c67420b10ba9ce51533e374e9afe328a1a667686b6",
+	"0x216bbe89a90a724bc27945744e102d0dfc2de2645035a685f5b288579dc2ace0",
+	"0x07e8985a57ff9e3daf3d6c6fa74aae69adca9a177f02488fa1bb3055de0c868f",
+	"0x1e1319f94496533a2b6ba846df729afb225ee100085a9bb67b84924edf060af3",
+	"0x331c0f032abdc31813d40e7153dd5080cf957410ec9d0f8aa6802480ebab7184",
+	"0x46a6034dde5dc2c7b54a2ac1ad5ea9a22b47292b486ba3e90908f6313b98f7fc",
+	"0x33bb5aada4e988a8858d90e2cacb6fa8fa221910263ffa1c0fc85b73fb252491",
+	"0x380bfa5abd292d5da8f565ee39dd3ca6510f83feb6726bfd6ead1c1a4f137cc6",
+	"0x36ad43e6d1cdd9982d356dbf621b43738d855d2af418c2171e458ef6f80b50d5",
+	"0x5d591b27024cd5c619fc4406508527b834761bdaf4866ca3421fb4410fa6fb72",
+	"0x539b08a99d6e83019a02223e2de27fa39b9bb66353e0105bec631d977de4bdb4",
+	"0x64cfb91edeeff705594dc74d115d01d5598fcdbc0f52eb101179729f08bd558c",
+	"0x6975e3a7bd378adaafdd31e3e4c0adb88f6643acf89465da43f099ca75a403ac",
+	"0x6bf572285dc664096544d3cdd69fd626d3076aad541ae56fd18d4d5509f7f25e",
+	"0x39dbcd13ce569cffc606a2e153931cb618afb7e8af04eff0a6eb5399e7bacd1b",
+	"0x50734798715b721645de1bfca9397ae3097ab0dab2c417a0bf9a6f88ce2f7313",
+	"0x047818d25037109a4f5b673f78feefd75071493038dfaaebb965294a886d6783",
+	"0x0e071f8a6e7db8da4e622c68893d170bffd78315e67e16d81734cf207691ce7d",
+	"0x236444497ba06715b14e0c40308d643cd5a32b263933bb476a613f1513bf931e",
+	"0x38439f7d622f28265786a5981e476b1abdbddcea42d0826b40a361c08439f84b",
+	"0x31576291350b84cdaef9639d7ca6f66014f43bc0a48e9d73d5b86e93c338543f",
+	"0x58a9289a01c0ed6110730f6df17d8f1fb1e9fea797f995844abda86e217bf779",
+	"0x157070b488538897cda48300696c7e5521b775728587122003db6cd6f6126e6a",
+	"0x6f49a0961151d20869204e0d541f2c4789d09201dec60f1a030e64c331912bbf",
+	"0x619a38dd473dd3b9c6fe74c2c0bef808f249d7b35cfe844c97ba216353d31435",
+	"0x32f1ca8ed481ce05caf48d89eea394a4770bb256c0586f8933b1c66439dbc94f",
+	"0x2de44f3dbced825cb3da341eecfd95e427fdd39572e7b289c08a275267331b83",
+	"0x4db08f2bf190bc484d209df743019c9699fd9ced41e3d82ef5a61fcbd88e7f34",
+	"0x349e4a8fc82ced4c38be66aaa4124c0a635818be5503e5ef986056c0aece6dde",
+	"0x290166e6467f217a974f6a0912c53280dc872af7dfa9f5b8acc84f4b6ebf5320",
+	"0x06149a7f84d19655543f3d1fcfeb4d40d453900c82c2593368765fd4749405a3",
+	"0x6881041febc4d1fe640ee5a7e3998550a7d24fea98dc17994a13f9c38620b742",
+	"0x1bfa9a316f68d126a996d5145d39614cae66229553622718b7bc86c61dfd2210",
+	"0x5685fb99468a41b44a35597d75c360915c3b1c92528c0d4895dc9e37570042fa",
+	"0x1b69b289a3a5a28a7544c0a2495816458015ceb9bbfac3a099d9a04ff5ac3bb1",
+	"0x0aecd7bed882054abb12ec946546bde712788ad6c9ab288de194ba87fabe6a6f",
+	"0x6f5f100323fdf22505ae5349bb9fcf9c50cec38d0a38f256f52cef2e7627f4a4",
+	"0x18b193e4078d125fa78f005290b124397b6d497cfacba06abe8073ff53de546b",
+	"0x5590d0eeec9155d86cd82ab8b5c48df2222464f9c27092986a1002cc756e68a3",
+	"0x17825362daef0b04f7a440478130426b9887c1ce8462a7dd03c8816ddae94fc8",
+	"0x3dc4222f792e99161bee750d797372b9a52314ea438df0836cbd4feee955751f",
+	"0x66cf27153c86b5ff3963807635a7f23266ea4e533d1ce52a43d5460336ef1dac",
+	"0x24f6f01bd88b47dbaea4f84418bc68224b83308734f2ee28a0176f02360decab",
+	"0x0415ea15c78ada9e64a7fbdb69d9bcf429f3861750c148999c211e82fae55bac",
+	"0x33265a83adc13996ad6d05eb4b6548feb9810cec4903f6d2af3bdc8b8c535e69",
+	"0x48b46ceb7bd2b5ae1808e33da5de35faa05adc6ed8c05278585263390f1c7f55",
+	"0x72bc7f0dc61781e64b3244034c519406748fe728e1d3be410d576a17789ea0c9",
+	"0x556bd52c649a0506d5841b93126039d0aa258fd795b1877ce7b9cd511dbfb93b",
+	"0x1187624f9f082c0dd7c98792c85ea5d45630e367580b13a54b9d623c5380d80b",
+	"0x1704283d3da1dad3c21f135bc5f214a72fea89375f04c8d5ea4d1afa5a10c84e",
+	"0x56df95f5b51a6d8b924cd9dccedbb476c12f1da52a7f7ab564a850dfe4ea0bb8",
+	"0x698b69df0f6e3d2df69ece6b84749038bac8630e9437bf9b7842cfb533823d3a",
+	"0x4ddef0988c45f5643b92a36cd6be4bb6407bdce0deca48133fc6cd7a80b3337c",
+	"0x596aca69c4fc5928efabc539e61a2f74bcdd9f577024a4f2c2ff65e67141658c",
+	"0x57f58657b212de5a8240b264b9f8b59870be875da46beefcd5e5ccb0f25fa9b5",
+	"0x49edac6ebbbbc5a64d64bff50cb3048a6d3621b0a816a7af56e5a30ffbff35fd",
+	"0x737429f8cbbd079ecc7e1598d2a8ddef7a99a829acd16babd9372d41eaf1b55f",
+	"0x72bc2fb3fa58ed1f111b932efc3813a8705e6735e40b3486754de19d9a27a008",
+	"0x0741250af818143fcc68f927ae2439536476774c6146ff7972d750a2774e024d",
+	"0x35e6116317a562c0fa7478ff58f1cb1f8f00c62bea1ee47788c3d758923bbf65",
+	"0x21219b68633042d431b6129d91de801bc757699ff5b07462b4ea5e7526a01155",
+	"0x6df00481f656276d3e1071a94e89cb50a8e9510c55d3df6841aa6af7383da5d8",
+	"0x53a6665afb0a2676b62403a3b4e8171ed8146344808b722759f6343e3c14dc2e",
+	"0x1bd9900ed6f6faa37e77525a6d533427429c0f13df8e93268341a280de489f05",
+	"0x105ae190644d6615bb84572d87454a721b3cf38eb5b76ff34354b4f1bd397d51",
+	"0x362b85ba41775de9adc7ecc408921dfa09883d83a1e275c9d55b669602732741",
+	"0x71dcb83e2986b9f4bee68e0e7f531eed4b13f030a44d483099861e14dc1a0d6c",
+	"0x14af28f47529b6e24f29621f79a09e52236a372ca73c760f96de5ea83bad2b4f",
+	"0x4371b8e5b7d0c30b2e2bed9f99246e0c102354e808defbda37fd0bb9abaf49aa",
+	"0x0c1822ede8fe2688d846e1c79f6b4946bfd9b183cd04dc18421865020d91bbe9",
+	"0x229d09be9b38efbe9430116a0a515bada6d27c600dabeba5be68cf10dd4fd9b7",
+	"0x5a06e7b7f9a7cf0a05ef141fc45f505f638a5d7d1eeb7f42fa4b4fb45d9761f8",
+	"0x5e03aab241fdec772d099ceee86c86777c996b359123cf52983ad3ac0c6c9a20",
+	"0x004fce943f838c3f2509c4ec743a5433e38c949198b94fd6cad76ec32dccd68b",
+	"0x58a5a9e5c3ecae5075c1f404d09163928f0624fcfb2ac1878e096fa4e8177dbf",
+	"0x604c740495794c899967e297fc5b8d09e85d420d95946f8a3d2e89ece31127aa",
+	"0x0e5da33c381fbb884df76154262afb8967005a3bd0238985efc82b4462712206",
+	"0x6d472df1d7dcc240c35a5ebacb0872007171b44fe81ed54dcc2a33cf7f5fe46d",
+	"0x239a8bfdf8717a29ee841a891c1a53ccf4e284de9d491038607c65526e70fcec",
+	"0x40abb119a1829e859d64e14e1587c7781e3bc398396a2d6c7a7ff2e620ca4c9e",
+	"0x4e0008fc98d616493fc8c78d91fd305bb31e919e22ce5633e5f432ce39b5db0d",
+	"0x3327a3f3a5b36621d1677ee8adbc7e677afc2758ba5847b0f0937b6cc58b1086",
+	"0x6b51309016441b91a85a08e2896f561ea49e9e984fb9e08e83812bd6b74a2ca2",
+	"0x5bcdef51dfe774a95639e8791f4d83a5d6b006a3ab56542b8d7e2959453ac77f",
+	"0x21f66f8387a0c9d4f3c1c10155595e5a601492b8a11f43e82453d095145648cc",
+	"0x469b6f247d1781b24bd7bab2445c064c927162a7e3b6d84c8c119e6fae61dbaf",
+	"0x1badab45a09eb61b244d640c813b7c62b620cab90668cddb66380bf511bd0148",
+	"0x25c6b9b6156dbbc23e792104eeb7dfbe1f573e90f1a2edb3fba04a813922e2f9",
+	"0x04b6daa3c2b0c89fc7221aed702e0b561c1acd6bb13662da4ec8437ad0a8cf5e",
+	"0x476eb7e867a6b7dc244742ff3461d73dcac0e62c818d38c3f2d76d30cd6d1e9d",
+	"0x2c54149c85374a3ebc1e8af1d607c5ee039eded2b3de8f33f324649cc4956766",
+	"0x65a1097f86f37bcaac7aa88748aa8b0db8e7a12bf6c3439398b5bbb7cc2e4dc3",
+	"0x2cc5b2d4814cf07ba25c47b48f0b63acddaa54650d98c57fcb88a57634629bd8",
+	"0x67aa80936255fa8a7cf2eeab76eb0cd4ded1adf223d2a100bd2d4af438b4c1c2",
+	"0x733020381c646741f4f93ca955560c70d80ce6214b94d6c8e7076bfca136eeed",
+	"0x57991d3d83033f1e117512b29ef94c6efedb0918dd48cd08b7d34518a2f9d6ab",
+	"0x297fb906d8ea359756a86bf5611c847b6cd7cd1a0b059b78f5388b68f4d9ba0e",
+	"0x2917754c2c945f3dffbe8df2acd3dc9db5b056f5418a192df9b640cda85c9eaa",
+	"0x71c08323b1691170616bc7885041c57d86bf1c498bee380c2b1095bc0f4178f4",
+	"0x3464aaca43be5c40bcb58905c6c63efbab04063c7d110eb074c16ff8983e8019",
+	"0x0abdfb976ae39a8d9a119f7853b3d2ee46ab0b0a2853ec0d71176255d2ae8ac3",
+	"0x09deaf1a791630ec6944a557025745c6b9780c50217f2c90f4986107bc05ad03",
+	"0x12e6d3d9abcf211d9168b171246d50959df4773a88fde70361c6593ad623270f",
+	"0x192a1153ca291ae48d97b7ef2c7d582784132c8569b61c1445c4c878185a70b6",
+	"0x198a2a20494ee651938d2886c62998f3886bf0cd62e9b9ff85ca1849319081e6",
+	"0x1c32e27124222cd712987ae2439020c9c3ab34b15367440ded079af29f0aa83d",
+	"0x5239ce4860d43a5bb06245ee2d3e1c687055830fa9bec492cc8d3fe3b05c88e0",
+	"0x510a9ce615260d92686c3256cac48db027f9f757605ae8f83abbb8210ed587e1",
+	"0x2a739a3eb6b7f3356d7708d2f6a248c4875f93a7046e4b92a8a2aac744448b45",
+];
+
+pub const MDS_ENTRIES: [[&str; 4]; 4] = [
+	[
+		"0x11200b61dde3c63e28e048421419ea35c64291bc178d2d9e69926953c07e4074",
+		"0x1bf3c6500305289a11cc604d3ccb6cafd1c0715afa41fc0c2d8ba9a9d57e893c",
+		"0x3b5258f92c02760aa4f1b9eb2622668fa4e423d1ca7f47d6862d322c979ba80e",
+		"0x51b09d00f1700f3fd94100348309e7fec266e7f9ef9cc9579b965ea537f1be5d",
+	],
+	[
+		"0x7258d3d463c88adb6ae90499dafe721d857def4944503fb84039db64d756d1ee",
+		"0x2fa87c323b9092ae48f53f65035298c2d9425d928c21e7da20a4bc9870bbdb98",
+		"0x201db712a6a473c1ae122ed93956e2a9ca33223221bdba6ae792733dfed02192",
+		"0x2c3fb4033e352fb60c40820de25c574575c8dfe8c9dd4b711ce633055161cbdb",
+	],
+	[
+		"0x685d279467bc3ae210a4203e9bc74f3d4ae633e67cdddbd2bb91cbbaa4dc7bb7",
+		"0x10aa9d665f6b7ad19f115cf36e8813be16c13365fdd1a10600c473af5e4cc7e7",
+		"0x327df7f43b86cd05655c9a5360e946dccdd95d22a7076a22c5fc15e82cd0cbf2",
+		"0x01e5d144eb1b59b9b88e0245d6f08a1659a89db22ce17417e5f12f94ed1fab45",
+	],
+	[
+		"0x3b1d640f568b8034140e08f61adb043e58dea34b3d4f632a47ac8be4ab055094",
+		"0x1299c3a02ee7efb625ab656ca8e657765046d374bd5256c598dc1f122fec8d37",
+		"0x04c2b00a6e742972206702b1f9dc5ac4b3061604bcce7b2cf8813afb80095276",
+		"0x4e64b54eb940c8c7511045d4bc90ccfe6adf4e6ccc1f9300eb7964caee4178aa",
+	],
+];
+
+pub fn get_rounds_poseidon_bls381_x17_4<F: PrimeField>() -> Vec<F> {
+	parse_vec(ROUND_CONSTS.to_vec())
+}
+pub fn get_mds_poseidon_bls381_x17_4<F: PrimeField>() -> Vec<Vec<F>> {
+	parse_matrix(MDS_ENTRIES.iter().map(|x| x.to_vec()).collect::<Vec<_>>())
+}
+
+pub fn get_poseidon_bls381_x17_4<F: PrimeField>() -> PoseidonParameters<F> {
+	let rounds = get_rounds_poseidon_bls381_x17_4();
+	let mds = get_mds_poseidon_bls381_x17_4();
+	PoseidonParameters::<F>::new(rounds, mds, FULL_ROUNDS, PARTIAL_ROUNDS, WIDTH, SBOX)
+}
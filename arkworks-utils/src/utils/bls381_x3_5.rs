@@ -19,7 +19,7 @@ use ark_ff::PrimeField;
 // sage generate_parameters_grain.sage 1 0 255 5 8 85
 // 0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001
 pub const FULL_ROUNDS: u8 = 8;
-pub const PARTIAL_ROUNDS: u8 = 60;
+pub const PARTIAL_ROUNDS: u8 = 85;
 pub const WIDTH: u8 = 5;
 pub const SBOX: PoseidonSbox = PoseidonSbox(3);
 
@@ -6,7 +6,7 @@ use crate::{
 };
 use ark_ff::PrimeField;
 pub const FULL_ROUNDS: u8 = 8;
-pub const PARTIAL_ROUNDS: u8 = 60;
+pub const PARTIAL_ROUNDS: u8 = 35;
 pub const WIDTH: u8 = 5;
 pub const SBOX: PoseidonSbox = PoseidonSbox(17);
 
@@ -6,7 +6,7 @@ use crate::{
 };
 use ark_ff::PrimeField;
 pub const FULL_ROUNDS: u8 = 8;
-pub const PARTIAL_ROUNDS: u8 = 57;
+pub const PARTIAL_ROUNDS: u8 = 84;
 pub const WIDTH: u8 = 3;
 pub const SBOX: PoseidonSbox = PoseidonSbox(3);
 
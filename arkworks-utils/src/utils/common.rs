@@ -1,4 +1,6 @@
-use crate::{mimc::Rounds as MiMCRounds, poseidon::PoseidonParameters};
+use crate::{
+	mimc::Rounds as MiMCRounds, poseidon::PoseidonParameters, poseidon2::Poseidon2Parameters,
+};
 use ark_crypto_primitives::SNARK;
 use ark_ec::PairingEngine;
 use ark_ff::fields::PrimeField;
@@ -13,10 +15,14 @@ impl crate::mimc::Rounds for MiMCRounds_220_3 {
 	const WIDTH: usize = 3;
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Curve {
 	Bls381,
 	Bn254,
+	/// The scalar field of BabyJubJub. None of x^3, x^5 and x^17 is a
+	/// permutation of this field, so its parameter sets are not secure and
+	/// should only be used for testing and benchmarks.
+	EdOnBn254,
 }
 
 #[cfg(all(feature = "poseidon_bls381_x3_3", feature = "poseidon_bn254_x3_3"))]
@@ -25,6 +31,10 @@ pub fn setup_params_x3_3<F: PrimeField>(curve: Curve) -> PoseidonParameters<F> {
 	match curve {
 		Curve::Bls381 => crate::utils::bls381_x3_3::get_poseidon_bls381_x3_3(),
 		Curve::Bn254 => crate::utils::bn254_x3_3::get_poseidon_bn254_x3_3(),
+		#[cfg(feature = "poseidon_ed_on_bn254_x3_3")]
+		Curve::EdOnBn254 => crate::utils::ed_on_bn254_x3_3::get_poseidon_ed_on_bn254_x3_3(),
+		#[cfg(not(feature = "poseidon_ed_on_bn254_x3_3"))]
+		Curve::EdOnBn254 => panic!("the poseidon_ed_on_bn254_x3_3 feature is not enabled"),
 	}
 }
 
@@ -34,6 +44,10 @@ pub fn setup_params_x3_5<F: PrimeField>(curve: Curve) -> PoseidonParameters<F> {
 	match curve {
 		Curve::Bls381 => crate::utils::bls381_x3_5::get_poseidon_bls381_x3_5(),
 		Curve::Bn254 => crate::utils::bn254_x3_5::get_poseidon_bn254_x3_5(),
+		#[cfg(feature = "poseidon_ed_on_bn254_x3_5")]
+		Curve::EdOnBn254 => crate::utils::ed_on_bn254_x3_5::get_poseidon_ed_on_bn254_x3_5(),
+		#[cfg(not(feature = "poseidon_ed_on_bn254_x3_5"))]
+		Curve::EdOnBn254 => panic!("the poseidon_ed_on_bn254_x3_5 feature is not enabled"),
 	}
 }
 
@@ -43,28 +57,36 @@ pub fn setup_params_x5_3<F: PrimeField>(curve: Curve) -> PoseidonParameters<F> {
 	match curve {
 		Curve::Bls381 => crate::utils::bls381_x5_3::get_poseidon_bls381_x5_3(),
 		Curve::Bn254 => crate::utils::bn254_x5_3::get_poseidon_bn254_x5_3(),
+		#[cfg(feature = "poseidon_ed_on_bn254_x5_3")]
+		Curve::EdOnBn254 => crate::utils::ed_on_bn254_x5_3::get_poseidon_ed_on_bn254_x5_3(),
+		#[cfg(not(feature = "poseidon_ed_on_bn254_x5_3"))]
+		Curve::EdOnBn254 => panic!("the poseidon_ed_on_bn254_x5_3 feature is not enabled"),
 	}
 }
 
-#[cfg(feature = "poseidon_bn254_x5_2")]
+#[cfg(all(feature = "poseidon_bls381_x5_2", feature = "poseidon_bn254_x5_2"))]
 pub fn setup_params_x5_2<F: PrimeField>(curve: Curve) -> PoseidonParameters<F> {
 	// Making params for poseidon in merkle tree
 	match curve {
-		Curve::Bls381 => {
-			unimplemented!("we don't hava parameters for bls381 curve yet");
-		}
+		Curve::Bls381 => crate::utils::bls381_x5_2::get_poseidon_bls381_x5_2(),
 		Curve::Bn254 => crate::utils::bn254_x5_2::get_poseidon_bn254_x5_2(),
+		Curve::EdOnBn254 => panic!(
+			"there are no poseidon x5_2 parameters for the ed_on_bn254 curve, \
+			 registry::poseidon_params returns an error instead"
+		),
 	}
 }
 
-#[cfg(feature = "poseidon_bn254_x5_4")]
+#[cfg(all(feature = "poseidon_bls381_x5_4", feature = "poseidon_bn254_x5_4"))]
 pub fn setup_params_x5_4<F: PrimeField>(curve: Curve) -> PoseidonParameters<F> {
 	// Making params for poseidon in merkle tree
 	match curve {
-		Curve::Bls381 => {
-			unimplemented!("we don't hava parameters for bls381 curve yet");
-		}
+		Curve::Bls381 => crate::utils::bls381_x5_4::get_poseidon_bls381_x5_4(),
 		Curve::Bn254 => crate::utils::bn254_x5_4::get_poseidon_bn254_x5_4(),
+		Curve::EdOnBn254 => panic!(
+			"there are no poseidon x5_4 parameters for the ed_on_bn254 curve, \
+			 registry::poseidon_params returns an error instead"
+		),
 	}
 }
 
@@ -74,6 +96,10 @@ pub fn setup_params_x5_5<F: PrimeField>(curve: Curve) -> PoseidonParameters<F> {
 	match curve {
 		Curve::Bls381 => crate::utils::bls381_x5_5::get_poseidon_bls381_x5_5(),
 		Curve::Bn254 => crate::utils::bn254_x5_5::get_poseidon_bn254_x5_5(),
+		#[cfg(feature = "poseidon_ed_on_bn254_x5_5")]
+		Curve::EdOnBn254 => crate::utils::ed_on_bn254_x5_5::get_poseidon_ed_on_bn254_x5_5(),
+		#[cfg(not(feature = "poseidon_ed_on_bn254_x5_5"))]
+		Curve::EdOnBn254 => panic!("the poseidon_ed_on_bn254_x5_5 feature is not enabled"),
 	}
 }
 
@@ -83,6 +109,10 @@ pub fn setup_params_x17_3<F: PrimeField>(curve: Curve) -> PoseidonParameters<F>
 	match curve {
 		Curve::Bls381 => crate::utils::bls381_x17_3::get_poseidon_bls381_x17_3(),
 		Curve::Bn254 => crate::utils::bn254_x17_3::get_poseidon_bn254_x17_3(),
+		#[cfg(feature = "poseidon_ed_on_bn254_x17_3")]
+		Curve::EdOnBn254 => crate::utils::ed_on_bn254_x17_3::get_poseidon_ed_on_bn254_x17_3(),
+		#[cfg(not(feature = "poseidon_ed_on_bn254_x17_3"))]
+		Curve::EdOnBn254 => panic!("the poseidon_ed_on_bn254_x17_3 feature is not enabled"),
 	}
 }
 
@@ -92,6 +122,46 @@ pub fn setup_params_x17_5<F: PrimeField>(curve: Curve) -> PoseidonParameters<F>
 	match curve {
 		Curve::Bls381 => crate::utils::bls381_x17_5::get_poseidon_bls381_x17_5(),
 		Curve::Bn254 => crate::utils::bn254_x17_5::get_poseidon_bn254_x17_5(),
+		#[cfg(feature = "poseidon_ed_on_bn254_x17_5")]
+		Curve::EdOnBn254 => crate::utils::ed_on_bn254_x17_5::get_poseidon_ed_on_bn254_x17_5(),
+		#[cfg(not(feature = "poseidon_ed_on_bn254_x17_5"))]
+		Curve::EdOnBn254 => panic!("the poseidon_ed_on_bn254_x17_5 feature is not enabled"),
+	}
+}
+
+// There is no width 5 Poseidon2 set: the external matrix of Poseidon2 is
+// only defined for widths 2, 3 and multiples of 4, and the reference
+// implementation has no width 5 instance to check one against.
+#[cfg(all(feature = "poseidon2_bls381_x5_2", feature = "poseidon2_bn254_x5_2"))]
+pub fn setup_poseidon2_params_x5_2<F: PrimeField>(curve: Curve) -> Poseidon2Parameters<F> {
+	match curve {
+		Curve::Bls381 => crate::utils::poseidon2_bls381_x5_2::get_poseidon2_bls381_x5_2(),
+		Curve::Bn254 => crate::utils::poseidon2_bn254_x5_2::get_poseidon2_bn254_x5_2(),
+		Curve::EdOnBn254 => {
+			panic!("there are no poseidon2 x5_2 parameters for the ed_on_bn254 curve")
+		}
+	}
+}
+
+#[cfg(all(feature = "poseidon2_bls381_x5_3", feature = "poseidon2_bn254_x5_3"))]
+pub fn setup_poseidon2_params_x5_3<F: PrimeField>(curve: Curve) -> Poseidon2Parameters<F> {
+	match curve {
+		Curve::Bls381 => crate::utils::poseidon2_bls381_x5_3::get_poseidon2_bls381_x5_3(),
+		Curve::Bn254 => crate::utils::poseidon2_bn254_x5_3::get_poseidon2_bn254_x5_3(),
+		Curve::EdOnBn254 => {
+			panic!("there are no poseidon2 x5_3 parameters for the ed_on_bn254 curve")
+		}
+	}
+}
+
+#[cfg(all(feature = "poseidon2_bls381_x5_4", feature = "poseidon2_bn254_x5_4"))]
+pub fn setup_poseidon2_params_x5_4<F: PrimeField>(curve: Curve) -> Poseidon2Parameters<F> {
+	match curve {
+		Curve::Bls381 => crate::utils::poseidon2_bls381_x5_4::get_poseidon2_bls381_x5_4(),
+		Curve::Bn254 => crate::utils::poseidon2_bn254_x5_4::get_poseidon2_bn254_x5_4(),
+		Curve::EdOnBn254 => {
+			panic!("there are no poseidon2 x5_4 parameters for the ed_on_bn254 curve")
+		}
 	}
 }
 
@@ -101,7 +171,8 @@ pub fn setup_mimc_220<F: PrimeField>(curve: Curve) -> crate::mimc::MiMCParameter
 		Curve::Bls381 => {
 			unimplemented!();
 		}
-		Curve::Bn254 => crate::mimc::MiMCParameters::<F>::new(
+		// The MiMC constants are the ed_on_bn254 ones, used for both
+		Curve::Bn254 | Curve::EdOnBn254 => crate::mimc::MiMCParameters::<F>::new(
 			F::zero(),
 			MiMCRounds_220_3::ROUNDS,
 			MiMCRounds_220_3::WIDTH,
@@ -1,5 +1,17 @@
+use super::parse_matrix;
+use crate::{
+	poseidon::{sbox::PoseidonSbox, PoseidonParameters},
+	utils::parse_vec,
+	Vec,
+};
+use ark_ff::PrimeField;
 // sage generate_parameters_grain.sage 1 0 254 3 8 33
 // 0x60c89ce5c263405370a08b6d0302b0bab3eedb83920ee0a677297dc392126f1
+pub const FULL_ROUNDS: u8 = 8;
+pub const PARTIAL_ROUNDS: u8 = 33;
+pub const WIDTH: u8 = 3;
+pub const SBOX: PoseidonSbox = PoseidonSbox(17);
+
 pub const ROUND_CONSTS: [&str; 123] = [
 	"0x014e086a0e26de2206c607c19c294d9a4f1271642ec3e6fa25d75b34d3edd896",
 	"0x015506a250a1fb6f5fc65a3386f33a93dc53b26aa4f495fe907bb37d802c7b5f",
@@ -142,3 +154,16 @@ pub const MDS_ENTRIES: [[&str; 3]; 3] = [
 		"0x033de9bed682bf475337b9a8bd5f0538d6ca417bb55d4a5c0b93d814bd732340",
 	],
 ];
+
+pub fn get_rounds_poseidon_ed_on_bn254_x17_3<F: PrimeField>() -> Vec<F> {
+	parse_vec(ROUND_CONSTS.to_vec())
+}
+pub fn get_mds_poseidon_ed_on_bn254_x17_3<F: PrimeField>() -> Vec<Vec<F>> {
+	parse_matrix(MDS_ENTRIES.iter().map(|x| x.to_vec()).collect::<Vec<_>>())
+}
+
+pub fn get_poseidon_ed_on_bn254_x17_3<F: PrimeField>() -> PoseidonParameters<F> {
+	let rounds = get_rounds_poseidon_ed_on_bn254_x17_3();
+	let mds = get_mds_poseidon_ed_on_bn254_x17_3();
+	PoseidonParameters::<F>::new(rounds, mds, FULL_ROUNDS, PARTIAL_ROUNDS, WIDTH, SBOX)
+}
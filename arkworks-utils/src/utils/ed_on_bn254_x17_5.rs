@@ -1,5 +1,17 @@
+use super::parse_matrix;
+use crate::{
+	poseidon::{sbox::PoseidonSbox, PoseidonParameters},
+	utils::parse_vec,
+	Vec,
+};
+use ark_ff::PrimeField;
 // sage generate_parameters_grain.sage 1 0 254 5 8 35
 // 0x60c89ce5c263405370a08b6d0302b0bab3eedb83920ee0a677297dc392126f1
+pub const FULL_ROUNDS: u8 = 8;
+pub const PARTIAL_ROUNDS: u8 = 35;
+pub const WIDTH: u8 = 5;
+pub const SBOX: PoseidonSbox = PoseidonSbox(17);
+
 pub const ROUND_CONSTS: [&str; 215] = [
 	"0x04f7b92e174673f12ad94305e211dbc74505cea331c8270bbc0436cfd3f9203d",
 	"0x05fd9a940ebf8c4f6365146a71cc4d60792afcfe5edfb289ef3a5e0ee7171df5",
@@ -254,3 +266,16 @@ pub const MDS_ENTRIES: [[&str; 5]; 5] = [
 		"0x0073da57ecc6a440c5e434781ef956983676777e841b7d31c4c9df269e16d294",
 	],
 ];
+
+pub fn get_rounds_poseidon_ed_on_bn254_x17_5<F: PrimeField>() -> Vec<F> {
+	parse_vec(ROUND_CONSTS.to_vec())
+}
+pub fn get_mds_poseidon_ed_on_bn254_x17_5<F: PrimeField>() -> Vec<Vec<F>> {
+	parse_matrix(MDS_ENTRIES.iter().map(|x| x.to_vec()).collect::<Vec<_>>())
+}
+
+pub fn get_poseidon_ed_on_bn254_x17_5<F: PrimeField>() -> PoseidonParameters<F> {
+	let rounds = get_rounds_poseidon_ed_on_bn254_x17_5();
+	let mds = get_mds_poseidon_ed_on_bn254_x17_5();
+	PoseidonParameters::<F>::new(rounds, mds, FULL_ROUNDS, PARTIAL_ROUNDS, WIDTH, SBOX)
+}
@@ -1,5 +1,17 @@
+use super::parse_matrix;
+use crate::{
+	poseidon::{sbox::PoseidonSbox, PoseidonParameters},
+	utils::parse_vec,
+	Vec,
+};
+use ark_ff::PrimeField;
 // sage generate_parameters_grain.sage 1 0 254 3 8 84
 // 0x60c89ce5c263405370a08b6d0302b0bab3eedb83920ee0a677297dc392126f1
+pub const FULL_ROUNDS: u8 = 8;
+pub const PARTIAL_ROUNDS: u8 = 84;
+pub const WIDTH: u8 = 3;
+pub const SBOX: PoseidonSbox = PoseidonSbox(3);
+
 pub const ROUND_CONSTS: [&str; 276] = [
 	"0x04a56b4ade4374e4899c7715f6b746542a57ff62d2738d39fe1b514102b5797b",
 	"0x02262836daa3968e649cc0a5dedbc3604c4c54f8a1cb4c7552f18a81c01207d1",
@@ -295,3 +307,16 @@ pub const MDS_ENTRIES: [[&str; 3]; 3] = [
 		"0x0236ecd8ba669ed98cc4c856a1c47ae69c7b6042e2395ce2b178bb587e30ad56",
 	],
 ];
+
+pub fn get_rounds_poseidon_ed_on_bn254_x3_3<F: PrimeField>() -> Vec<F> {
+	parse_vec(ROUND_CONSTS.to_vec())
+}
+pub fn get_mds_poseidon_ed_on_bn254_x3_3<F: PrimeField>() -> Vec<Vec<F>> {
+	parse_matrix(MDS_ENTRIES.iter().map(|x| x.to_vec()).collect::<Vec<_>>())
+}
+
+pub fn get_poseidon_ed_on_bn254_x3_3<F: PrimeField>() -> PoseidonParameters<F> {
+	let rounds = get_rounds_poseidon_ed_on_bn254_x3_3();
+	let mds = get_mds_poseidon_ed_on_bn254_x3_3();
+	PoseidonParameters::<F>::new(rounds, mds, FULL_ROUNDS, PARTIAL_ROUNDS, WIDTH, SBOX)
+}
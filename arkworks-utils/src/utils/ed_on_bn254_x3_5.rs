@@ -1,5 +1,17 @@
+use super::parse_matrix;
+use crate::{
+	poseidon::{sbox::PoseidonSbox, PoseidonParameters},
+	utils::parse_vec,
+	Vec,
+};
+use ark_ff::PrimeField;
 // sage generate_parameters_grain.sage 1 0 254 5 8 85
 // 0x60c89ce5c263405370a08b6d0302b0bab3eedb83920ee0a677297dc392126f1
+pub const FULL_ROUNDS: u8 = 8;
+pub const PARTIAL_ROUNDS: u8 = 85;
+pub const WIDTH: u8 = 5;
+pub const SBOX: PoseidonSbox = PoseidonSbox(3);
+
 pub const ROUND_CONSTS: [&str; 465] = [
 	"0x04c8d7fc9d01ff7984fc9e46b49b5397897381d9929b7f56cce0c0655a1d750e",
 	"0x0105070fcdc1c44db10e9259e40a61c33d7e437182f9015d6d584698344b47d7",
@@ -504,3 +516,16 @@ pub const MDS_ENTRIES: [[&str; 5]; 5] = [
 		"0x02a805755be0353f7872108930fd60c25487b9a25022f65e530c5c2dd7deb350",
 	],
 ];
+
+pub fn get_rounds_poseidon_ed_on_bn254_x3_5<F: PrimeField>() -> Vec<F> {
+	parse_vec(ROUND_CONSTS.to_vec())
+}
+pub fn get_mds_poseidon_ed_on_bn254_x3_5<F: PrimeField>() -> Vec<Vec<F>> {
+	parse_matrix(MDS_ENTRIES.iter().map(|x| x.to_vec()).collect::<Vec<_>>())
+}
+
+pub fn get_poseidon_ed_on_bn254_x3_5<F: PrimeField>() -> PoseidonParameters<F> {
+	let rounds = get_rounds_poseidon_ed_on_bn254_x3_5();
+	let mds = get_mds_poseidon_ed_on_bn254_x3_5();
+	PoseidonParameters::<F>::new(rounds, mds, FULL_ROUNDS, PARTIAL_ROUNDS, WIDTH, SBOX)
+}
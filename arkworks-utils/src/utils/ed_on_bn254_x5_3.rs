@@ -1,5 +1,17 @@
+use super::parse_matrix;
+use crate::{
+	poseidon::{sbox::PoseidonSbox, PoseidonParameters},
+	utils::parse_vec,
+	Vec,
+};
+use ark_ff::PrimeField;
 // sage generate_parameters_grain.sage 1 0 254 3 8 57
 // 0x60c89ce5c263405370a08b6d0302b0bab3eedb83920ee0a677297dc392126f1
+pub const FULL_ROUNDS: u8 = 8;
+pub const PARTIAL_ROUNDS: u8 = 57;
+pub const WIDTH: u8 = 3;
+pub const SBOX: PoseidonSbox = PoseidonSbox(5);
+
 pub const ROUND_CONSTS: [&str; 195] = [
 	"0x00f1445235f2148c5986587169fc1bcd887b08d4d00868df5696fff40956e864",
 	"0x0319d062072bef7ecca5eac06f97d4d55952c175ab6b03eae64b44c7dbf11cfa",
@@ -214,3 +226,16 @@ pub const MDS_ENTRIES: [[&str; 3]; 3] = [
 		"0x0140865fd79f266cb7cc99a99fee9150af1e6421b869f813de90a4e5606bfd75",
 	],
 ];
+
+pub fn get_rounds_poseidon_ed_on_bn254_x5_3<F: PrimeField>() -> Vec<F> {
+	parse_vec(ROUND_CONSTS.to_vec())
+}
+pub fn get_mds_poseidon_ed_on_bn254_x5_3<F: PrimeField>() -> Vec<Vec<F>> {
+	parse_matrix(MDS_ENTRIES.iter().map(|x| x.to_vec()).collect::<Vec<_>>())
+}
+
+pub fn get_poseidon_ed_on_bn254_x5_3<F: PrimeField>() -> PoseidonParameters<F> {
+	let rounds = get_rounds_poseidon_ed_on_bn254_x5_3();
+	let mds = get_mds_poseidon_ed_on_bn254_x5_3();
+	PoseidonParameters::<F>::new(rounds, mds, FULL_ROUNDS, PARTIAL_ROUNDS, WIDTH, SBOX)
+}
@@ -8,6 +8,7 @@ use ethabi::{encode, Token};
 use tiny_keccak::{Hasher, Keccak};
 
 pub mod common;
+pub mod registry;
 
 use crate::Vec;
 
@@ -28,23 +29,57 @@ pub mod bn254_x5_5;
 #[cfg(feature = "poseidon_bn254_x5_5")]
 pub mod bn254_x5_5_result;
 
+#[cfg(feature = "poseidon_bls381_x17_2")]
+pub mod bls381_x17_2;
 #[cfg(feature = "poseidon_bls381_x17_3")]
 pub mod bls381_x17_3;
+#[cfg(feature = "poseidon_bls381_x17_4")]
+pub mod bls381_x17_4;
 #[cfg(feature = "poseidon_bls381_x17_5")]
 pub mod bls381_x17_5;
 #[cfg(feature = "poseidon_bls381_x3_3")]
 pub mod bls381_x3_3;
 #[cfg(feature = "poseidon_bls381_x3_5")]
 pub mod bls381_x3_5;
+#[cfg(feature = "poseidon_bls381_x5_2")]
+pub mod bls381_x5_2;
 #[cfg(feature = "poseidon_bls381_x5_3")]
 pub mod bls381_x5_3;
+#[cfg(feature = "poseidon_bls381_x5_4")]
+pub mod bls381_x5_4;
 #[cfg(feature = "poseidon_bls381_x5_5")]
 pub mod bls381_x5_5;
-#[cfg(feature = "poseidon_bls381_x3_3")]
+#[cfg(feature = "poseidon_bn254_x3_3")]
 pub mod bn254_x3_3;
-#[cfg(feature = "poseidon_bls381_x3_5")]
+#[cfg(feature = "poseidon_bn254_x3_5")]
 pub mod bn254_x3_5;
 
+#[cfg(feature = "poseidon2_bls381_x5_2")]
+pub mod poseidon2_bls381_x5_2;
+#[cfg(feature = "poseidon2_bls381_x5_3")]
+pub mod poseidon2_bls381_x5_3;
+#[cfg(feature = "poseidon2_bls381_x5_4")]
+pub mod poseidon2_bls381_x5_4;
+#[cfg(feature = "poseidon2_bn254_x5_2")]
+pub mod poseidon2_bn254_x5_2;
+#[cfg(feature = "poseidon2_bn254_x5_3")]
+pub mod poseidon2_bn254_x5_3;
+#[cfg(feature = "poseidon2_bn254_x5_4")]
+pub mod poseidon2_bn254_x5_4;
+
+#[cfg(feature = "poseidon_ed_on_bn254_x17_3")]
+pub mod ed_on_bn254_x17_3;
+#[cfg(feature = "poseidon_ed_on_bn254_x17_5")]
+pub mod ed_on_bn254_x17_5;
+#[cfg(feature = "poseidon_ed_on_bn254_x3_3")]
+pub mod ed_on_bn254_x3_3;
+#[cfg(feature = "poseidon_ed_on_bn254_x3_5")]
+pub mod ed_on_bn254_x3_5;
+#[cfg(feature = "poseidon_ed_on_bn254_x5_3")]
+pub mod ed_on_bn254_x5_3;
+#[cfg(feature = "poseidon_ed_on_bn254_x5_5")]
+pub mod ed_on_bn254_x5_5;
+
 pub fn to_field_elements<F: PrimeField>(bytes: &[u8]) -> Result<Vec<F>, Error> {
 	let max_size_bytes = F::BigInt::NUM_LIMBS * 8;
 
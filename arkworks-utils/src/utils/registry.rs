@@ -0,0 +1,334 @@
+use super::common::Curve;
+use crate::{poseidon::PoseidonParameters, Vec};
+use ark_ff::{fields::PrimeField, BigInteger, FpParameters};
+use ark_std::error::Error as ArkError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryError {
+	/// No Poseidon parameter set is compiled in for the curve, exponent and
+	/// width.
+	Unavailable { curve: Curve, alpha: i8, width: u8 },
+	/// The requested field is not the scalar field of the curve.
+	FieldMismatch { curve: Curve },
+}
+
+impl core::fmt::Display for RegistryError {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		match self {
+			RegistryError::Unavailable {
+				curve,
+				alpha,
+				width,
+			} => write!(
+				f,
+				"no poseidon parameters for {:?} with x^{} and width {}",
+				curve, alpha, width
+			),
+			RegistryError::FieldMismatch { curve } => {
+				write!(f, "field is not the scalar field of {:?}", curve)
+			}
+		}
+	}
+}
+
+impl ArkError for RegistryError {}
+
+/// Whether `F` is the scalar field of `curve`, the field its parameter sets
+/// were generated for.
+pub fn is_curve_field<F: PrimeField>(curve: Curve) -> bool {
+	let modulus = F::Params::MODULUS.to_bytes_le();
+	let curve_modulus = match curve {
+		Curve::Bls381 => <ark_bls12_381::Fr as PrimeField>::Params::MODULUS.to_bytes_le(),
+		Curve::Bn254 => <ark_bn254::Fr as PrimeField>::Params::MODULUS.to_bytes_le(),
+		Curve::EdOnBn254 => <ark_ed_on_bn254::Fr as PrimeField>::Params::MODULUS.to_bytes_le(),
+	};
+	modulus == curve_modulus
+}
+
+/// Defines `available_poseidon_params` and `poseidon_params` from one list of
+/// parameter sets, each behind the feature that compiles its table in.
+macro_rules! poseidon_registry {
+	($(($feature:literal, $curve:ident, $alpha:literal, $width:literal, $get:path)),* $(,)?) => {
+		/// Returns the curve, exponent and width of every parameter set
+		/// compiled in.
+		pub fn available_poseidon_params() -> Vec<(Curve, i8, u8)> {
+			#[allow(unused_mut)]
+			let mut available = Vec::new();
+			$(
+				#[cfg(feature = $feature)]
+				available.push((Curve::$curve, $alpha, $width));
+			)*
+			available
+		}
+
+		/// Returns the parameter set for the curve, exponent and width, or an
+		/// error if it is not compiled in or `F` is not the scalar field of
+		/// the curve.
+		pub fn poseidon_params<F: PrimeField>(
+			curve: Curve,
+			alpha: i8,
+			width: u8,
+		) -> Result<PoseidonParameters<F>, RegistryError> {
+			if !is_curve_field::<F>(curve) {
+				return Err(RegistryError::FieldMismatch { curve });
+			}
+			match (curve, alpha, width) {
+				$(
+					#[cfg(feature = $feature)]
+					(Curve::$curve, $alpha, $width) => Ok($get()),
+				)*
+				_ => Err(RegistryError::Unavailable {
+					curve,
+					alpha,
+					width,
+				}),
+			}
+		}
+	};
+}
+
+poseidon_registry![
+	(
+		"poseidon_bls381_x3_3",
+		Bls381,
+		3,
+		3,
+		super::bls381_x3_3::get_poseidon_bls381_x3_3
+	),
+	(
+		"poseidon_bls381_x3_5",
+		Bls381,
+		3,
+		5,
+		super::bls381_x3_5::get_poseidon_bls381_x3_5
+	),
+	(
+		"poseidon_bls381_x5_2",
+		Bls381,
+		5,
+		2,
+		super::bls381_x5_2::get_poseidon_bls381_x5_2
+	),
+	(
+		"poseidon_bls381_x5_3",
+		Bls381,
+		5,
+		3,
+		super::bls381_x5_3::get_poseidon_bls381_x5_3
+	),
+	(
+		"poseidon_bls381_x5_4",
+		Bls381,
+		5,
+		4,
+		super::bls381_x5_4::get_poseidon_bls381_x5_4
+	),
+	(
+		"poseidon_bls381_x5_5",
+		Bls381,
+		5,
+		5,
+		super::bls381_x5_5::get_poseidon_bls381_x5_5
+	),
+	(
+		"poseidon_bls381_x17_2",
+		Bls381,
+		17,
+		2,
+		super::bls381_x17_2::get_poseidon_bls381_x17_2
+	),
+	(
+		"poseidon_bls381_x17_3",
+		Bls381,
+		17,
+		3,
+		super::bls381_x17_3::get_poseidon_bls381_x17_3
+	),
+	(
+		"poseidon_bls381_x17_4",
+		Bls381,
+		17,
+		4,
+		super::bls381_x17_4::get_poseidon_bls381_x17_4
+	),
+	(
+		"poseidon_bls381_x17_5",
+		Bls381,
+		17,
+		5,
+		super::bls381_x17_5::get_poseidon_bls381_x17_5
+	),
+	(
+		"poseidon_bn254_x3_3",
+		Bn254,
+		3,
+		3,
+		super::bn254_x3_3::get_poseidon_bn254_x3_3
+	),
+	(
+		"poseidon_bn254_x3_5",
+		Bn254,
+		3,
+		5,
+		super::bn254_x3_5::get_poseidon_bn254_x3_5
+	),
+	(
+		"poseidon_bn254_x5_2",
+		Bn254,
+		5,
+		2,
+		super::bn254_x5_2::get_poseidon_bn254_x5_2
+	),
+	(
+		"poseidon_bn254_x5_3",
+		Bn254,
+		5,
+		3,
+		super::bn254_x5_3::get_poseidon_bn254_x5_3
+	),
+	(
+		"poseidon_bn254_x5_4",
+		Bn254,
+		5,
+		4,
+		super::bn254_x5_4::get_poseidon_bn254_x5_4
+	),
+	(
+		"poseidon_bn254_x5_5",
+		Bn254,
+		5,
+		5,
+		super::bn254_x5_5::get_poseidon_bn254_x5_5
+	),
+	(
+		"poseidon_bn254_x17_3",
+		Bn254,
+		17,
+		3,
+		super::bn254_x17_3::get_poseidon_bn254_x17_3
+	),
+	(
+		"poseidon_bn254_x17_5",
+		Bn254,
+		17,
+		5,
+		super::bn254_x17_5::get_poseidon_bn254_x17_5
+	),
+	(
+		"poseidon_ed_on_bn254_x3_3",
+		EdOnBn254,
+		3,
+		3,
+		super::ed_on_bn254_x3_3::get_poseidon_ed_on_bn254_x3_3
+	),
+	(
+		"poseidon_ed_on_bn254_x3_5",
+		EdOnBn254,
+		3,
+		5,
+		super::ed_on_bn254_x3_5::get_poseidon_ed_on_bn254_x3_5
+	),
+	(
+		"poseidon_ed_on_bn254_x5_3",
+		EdOnBn254,
+		5,
+		3,
+		super::ed_on_bn254_x5_3::get_poseidon_ed_on_bn254_x5_3
+	),
+	(
+		"poseidon_ed_on_bn254_x5_5",
+		EdOnBn254,
+		5,
+		5,
+		super::ed_on_bn254_x5_5::get_poseidon_ed_on_bn254_x5_5
+	),
+	(
+		"poseidon_ed_on_bn254_x17_3",
+		EdOnBn254,
+		17,
+		3,
+		super::ed_on_bn254_x17_3::get_poseidon_ed_on_bn254_x17_3
+	),
+	(
+		"poseidon_ed_on_bn254_x17_5",
+		EdOnBn254,
+		17,
+		5,
+		super::ed_on_bn254_x17_5::get_poseidon_ed_on_bn254_x17_5
+	),
+];
+
+#[cfg(all(test, feature = "default_poseidon"))]
+mod test {
+	use super::*;
+	use crate::utils::common::setup_params_x5_3;
+	use ark_bls12_381::Fr as BlsFr;
+	use ark_bn254::Fr;
+	use ark_ed_on_bn254::Fr as EdOnBnFr;
+
+	fn assert_params<F: PrimeField>(curve: Curve, alpha: i8, width: u8) {
+		let params = poseidon_params::<F>(curve, alpha, width).unwrap();
+		assert_eq!(params.sbox.0, alpha);
+		assert_eq!(params.width, width);
+	}
+
+	#[test]
+	fn should_list_and_return_available_params() {
+		let available = available_poseidon_params();
+		assert!(available.contains(&(Curve::Bn254, 5, 3)));
+		assert!(available.contains(&(Curve::Bls381, 5, 5)));
+		assert!(available.contains(&(Curve::EdOnBn254, 17, 3)));
+
+		for (curve, alpha, width) in available {
+			match curve {
+				Curve::Bls381 => assert_params::<BlsFr>(curve, alpha, width),
+				Curve::Bn254 => assert_params::<Fr>(curve, alpha, width),
+				Curve::EdOnBn254 => assert_params::<EdOnBnFr>(curve, alpha, width),
+			}
+		}
+
+		let params = poseidon_params::<Fr>(Curve::Bn254, 5, 3).unwrap();
+		assert_eq!(
+			params.round_keys,
+			setup_params_x5_3::<Fr>(Curve::Bn254).round_keys
+		);
+	}
+
+	#[test]
+	fn should_fail_for_missing_params() {
+		assert_eq!(
+			poseidon_params::<Fr>(Curve::Bn254, 7, 3).err(),
+			Some(RegistryError::Unavailable {
+				curve: Curve::Bn254,
+				alpha: 7,
+				width: 3
+			})
+		);
+		assert!(poseidon_params::<Fr>(Curve::Bn254, 5, 9).is_err());
+	}
+
+	#[test]
+	fn should_fail_for_mismatched_field() {
+		// The BN254 scalar field is the base field of ed_on_bn254
+		assert!(poseidon_params::<ark_ed_on_bn254::Fq>(Curve::Bn254, 5, 3).is_ok());
+
+		assert_eq!(
+			poseidon_params::<BlsFr>(Curve::Bn254, 5, 3).err(),
+			Some(RegistryError::FieldMismatch {
+				curve: Curve::Bn254
+			})
+		);
+		assert_eq!(
+			poseidon_params::<Fr>(Curve::Bls381, 5, 3).err(),
+			Some(RegistryError::FieldMismatch {
+				curve: Curve::Bls381
+			})
+		);
+		assert_eq!(
+			poseidon_params::<Fr>(Curve::EdOnBn254, 5, 3).err(),
+			Some(RegistryError::FieldMismatch {
+				curve: Curve::EdOnBn254
+			})
+		);
+	}
+}